@@ -0,0 +1,354 @@
+//! A static website with the full text of the I Ching, as written by `book`.
+//!
+//! The site has an index page, which lists the hexagrams in the chosen [`HexagramOrdering`], a
+//! page for each of the 64 hexagrams (with the commentaries that were asked for with `--with`)
+//! and a page for each of the 8 trigrams. Pages only link to
+//! each other, so the site can be browsed without a network connection. The same repository and
+//! ordering always produce the same files, so the site can be checked into version control.
+
+use crate::{
+    config::Settings,
+    hexagram_json::HexagramJsonInfo,
+    locale::{ordering_name, Lang, Messages},
+    render::{
+        self, render_document, HeadingLevel, HtmlRenderer, Link, Render, Renderer, SectionRole,
+    },
+    view::{HexagramView, TrigramView},
+};
+use iching::{
+    hexagram::{Hexagram, HexagramOrdering},
+    hexagram_repository::HexagramRepository,
+    trigram::Trigram,
+};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+const INDEX_PAGE: &str = "index.html";
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to write the book to {}: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("Failed to write the book to {}: {1}", .0.display())]
+    Render(PathBuf, #[source] render::Error),
+}
+
+fn hexagram_page(number: usize) -> String {
+    format!("hexagram-{number:02}.html")
+}
+
+fn trigram_page(number: usize) -> String {
+    format!("trigram-{number}.html")
+}
+
+fn hexagram_link(info: &HexagramJsonInfo) -> Link {
+    Link {
+        text: HexagramView::from(info).label(),
+        target: hexagram_page(info.number()),
+    }
+}
+
+fn trigram_link(trigram: &Trigram, messages: &Messages) -> Link {
+    Link {
+        text: format!(
+            "{} {} {} ({})",
+            trigram.symbol(),
+            trigram.number(),
+            messages.trigram(trigram, None),
+            trigram.pinyin()
+        ),
+        target: trigram_page(trigram.number()),
+    }
+}
+
+/// All eight trigrams, by their number.
+fn trigrams() -> impl Iterator<Item = Trigram> {
+    (1..=8u8).map(|number| Trigram::try_from(number).expect("trigrams are numbered from 1 to 8"))
+}
+
+/// Write the book into `directory`, creating it if needed, in the language and with the texts and
+/// commentaries of `settings`. Files that are already in the directory are overwritten.
+pub(crate) fn write_book(
+    directory: &Path,
+    ordering: HexagramOrdering,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Error> {
+    let lang = settings.lang;
+    let messages = lang.messages();
+    fs::create_dir_all(directory).map_err(|err| Error::Io(directory.to_owned(), err))?;
+
+    let ordered: Vec<&HexagramJsonInfo> = ordering
+        .hexagrams()
+        .map(|hexagram| hexagrams.get_info_for_hexagram(&hexagram))
+        .collect();
+
+    write_page(
+        &directory.join(INDEX_PAGE),
+        &IndexPage {
+            ordering,
+            hexagrams: &ordered,
+            lang,
+        },
+    )?;
+
+    for (index, info) in ordered.iter().enumerate() {
+        let related =
+            |hexagram: Hexagram| hexagram_link(hexagrams.get_info_for_hexagram(&hexagram));
+        let page = HexagramPage {
+            view: HexagramView::from(*info)
+                .with_lines()
+                .with_line_labels(settings.line_labels)
+                .with_lang(lang)
+                .with_texts(settings.texts)
+                .with_commentary(&settings.with),
+            above: trigram_link(info.hexagram().above(), messages),
+            below: trigram_link(info.hexagram().below(), messages),
+            nuclear: related(info.hexagram().nuclear_hexagram()),
+            inverse: related(info.hexagram().inverse_hexagram()),
+            complement: related(info.hexagram().complement_hexagram()),
+            previous: index
+                .checked_sub(1)
+                .map(|previous| hexagram_link(ordered[previous])),
+            next: ordered.get(index + 1).map(|next| hexagram_link(next)),
+        };
+        write_page(&directory.join(hexagram_page(info.number())), &page)?;
+    }
+
+    for trigram in trigrams() {
+        let page = TrigramPage {
+            view: TrigramView::from(&trigram).with_lang(lang),
+            above: ordered
+                .iter()
+                .filter(|info| info.hexagram().above() == &trigram)
+                .map(|info| hexagram_link(info))
+                .collect(),
+            below: ordered
+                .iter()
+                .filter(|info| info.hexagram().below() == &trigram)
+                .map(|info| hexagram_link(info))
+                .collect(),
+        };
+        write_page(&directory.join(trigram_page(trigram.number())), &page)?;
+    }
+
+    Ok(())
+}
+
+fn write_page(path: &Path, page: &impl Render) -> Result<(), Error> {
+    let file = File::create(path).map_err(|err| Error::Io(path.to_owned(), err))?;
+    render_document(&mut HtmlRenderer::new(BufWriter::new(file)), page)
+        .map_err(|err| Error::Render(path.to_owned(), err))
+}
+
+/// Links back to the index, at the top of every other page.
+fn index_link(renderer: &mut dyn Renderer, messages: &Messages) -> Result<(), render::Error> {
+    renderer.links(&[Link {
+        text: messages.get("book-all-hexagrams"),
+        target: String::from(INDEX_PAGE),
+    }])
+}
+
+struct IndexPage<'a> {
+    ordering: HexagramOrdering,
+    hexagrams: &'a [&'a HexagramJsonInfo],
+    lang: Lang,
+}
+
+impl Render for IndexPage<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().get("book-title")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        renderer.subtitle(&messages.get("book-subtitle"))?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-hexagrams"),
+        )?;
+        renderer.paragraph(&messages.format(
+            "book-ordering",
+            &[("ordering", ordering_name(self.ordering).into())],
+        ))?;
+        let links: Vec<Link> = self
+            .hexagrams
+            .iter()
+            .map(|info| hexagram_link(info))
+            .collect();
+        renderer.links(&links)?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-trigrams"),
+        )?;
+        let links: Vec<Link> = trigrams()
+            .map(|trigram| trigram_link(&trigram, messages))
+            .collect();
+        renderer.links(&links)
+    }
+}
+
+struct HexagramPage<'a> {
+    view: HexagramView<'a>,
+    above: Link,
+    below: Link,
+    nuclear: Link,
+    inverse: Link,
+    complement: Link,
+    previous: Option<Link>,
+    next: Option<Link>,
+}
+
+impl Render for HexagramPage<'_> {
+    fn title(&self) -> String {
+        self.view.title()
+    }
+
+    fn lang(&self) -> Lang {
+        self.view.lang()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang().messages();
+        index_link(renderer, messages)?;
+        self.view.render(renderer)?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-trigrams"),
+        )?;
+        renderer.heading(HeadingLevel::Subsection, &messages.get("book-above"))?;
+        renderer.links(std::slice::from_ref(&self.above))?;
+        renderer.heading(HeadingLevel::Subsection, &messages.get("book-below"))?;
+        renderer.links(std::slice::from_ref(&self.below))?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-related"),
+        )?;
+        renderer.heading(HeadingLevel::Subsection, &messages.get("book-nuclear"))?;
+        renderer.links(std::slice::from_ref(&self.nuclear))?;
+        renderer.heading(HeadingLevel::Subsection, &messages.get("book-inverse"))?;
+        renderer.links(std::slice::from_ref(&self.inverse))?;
+        renderer.heading(HeadingLevel::Subsection, &messages.get("book-complement"))?;
+        renderer.links(std::slice::from_ref(&self.complement))?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-sequence"),
+        )?;
+        if let Some(previous) = &self.previous {
+            renderer.heading(HeadingLevel::Subsection, &messages.get("book-previous"))?;
+            renderer.links(std::slice::from_ref(previous))?;
+        }
+        if let Some(next) = &self.next {
+            renderer.heading(HeadingLevel::Subsection, &messages.get("book-next"))?;
+            renderer.links(std::slice::from_ref(next))?;
+        }
+
+        Ok(())
+    }
+}
+
+struct TrigramPage {
+    view: TrigramView,
+    above: Vec<Link>,
+    below: Vec<Link>,
+}
+
+impl Render for TrigramPage {
+    fn title(&self) -> String {
+        self.view.title()
+    }
+
+    fn lang(&self) -> Lang {
+        self.view.lang()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang().messages();
+        index_link(renderer, messages)?;
+        self.view.render(renderer)?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-hexagrams-above"),
+        )?;
+        renderer.links(&self.above)?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-hexagrams-below"),
+        )?;
+        renderer.links(&self.below)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+
+    #[test]
+    fn test_book_has_a_page_for_every_hexagram_and_trigram() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let directory = std::env::temp_dir().join(format!("iching-book-{}", std::process::id()));
+
+        write_book(
+            &directory,
+            HexagramOrdering::Binary,
+            &Settings::default(),
+            &hexagrams,
+        )
+        .unwrap();
+        let mut files: Vec<String> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        let index = fs::read_to_string(directory.join(INDEX_PAGE)).unwrap();
+        let creative = fs::read_to_string(directory.join("hexagram-01.html")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(files.len(), 1 + 64 + 8);
+        assert!(files.contains(&String::from("hexagram-64.html")));
+        assert!(files.contains(&String::from("trigram-8.html")));
+        // The binary sequence starts with ䷁ The Receptive.
+        let first_link = index.find("<li><a href=").unwrap();
+        assert!(index[first_link..].starts_with("<li><a href=\"hexagram-02.html\">"));
+        // ䷀ The Creative is its own nuclear hexagram, and the last hexagram in the sequence.
+        assert!(creative.contains("<a href=\"trigram-1.html\">"));
+        assert!(creative.contains("<h3>Nuclear</h3>\n<ul>\n<li><a href=\"hexagram-01.html\">"));
+        assert!(!creative.contains("<h3>Next</h3>"));
+    }
+
+    #[test]
+    fn test_book_is_written_in_the_language_asked_for() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let directory = std::env::temp_dir().join(format!("iching-book-zh-{}", std::process::id()));
+        let settings = Settings {
+            lang: Lang::ZhHant,
+            ..Settings::default()
+        };
+
+        write_book(&directory, HexagramOrdering::KingWen, &settings, &hexagrams).unwrap();
+        let index = fs::read_to_string(directory.join(INDEX_PAGE)).unwrap();
+        let creative = fs::read_to_string(directory.join("hexagram-01.html")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(index.contains("<html lang=\"zh-Hant\">"));
+        assert!(index.contains("<title>易經</title>"));
+        assert!(index.contains("按文王卦序排列。"));
+        assert!(creative.contains("<h3>上卦</h3>"));
+        assert!(creative.contains("<h3>互卦</h3>"));
+        assert!(!creative.contains("Nuclear"));
+    }
+}
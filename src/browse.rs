@@ -0,0 +1,416 @@
+//! A full-screen browser for the 64 hexagrams, opened by `browse`.
+//!
+//! The left of the screen shows the hexagrams as an 8×8 grid in the chosen ordering, and below it
+//! the lines of the selected hexagram and the hexagrams related to it. The right of the screen
+//! shows the selected hexagram's full text. Flipping one of its lines jumps to the neighboring
+//! hexagram that differs in only that line.
+//!
+//! The [`Browser`] only keeps state and draws it, so that it can be tested with ratatui's
+//! [`TestBackend`](ratatui::backend::TestBackend). [`browse`] runs it in the terminal.
+
+use crate::{
+    config::Settings,
+    hexagram_json::HexagramJsonInfo,
+    locale::ordering_name,
+    render::{render_document, TuiRenderer},
+    view::HexagramView,
+};
+use iching::{
+    hexagram::{Hexagram, HexagramOrdering},
+    hexagram_repository::HexagramRepository,
+    line::Line,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{self, Text},
+    widgets::{Block, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::io;
+use unicode_width::UnicodeWidthStr;
+
+const GRID_SIZE: u8 = 8;
+/// Each cell of the grid is a symbol (2 columns wide), a number of up to 2 digits and spaces.
+const CELL_WIDTH: u16 = 6;
+/// The grid, lines and relations panels are this wide, including their borders.
+const SIDEBAR_WIDTH: u16 = CELL_WIDTH * GRID_SIZE as u16 + 2;
+/// How many lines PageUp and PageDown scroll the text by.
+const PAGE: u16 = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to run the browser: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A kind of hexagram that's related to the selected hexagram, with the key that jumps to it, the
+/// ID of its name's message and the color that it's marked with in the grid.
+struct Relation {
+    key: char,
+    name: &'static str,
+    related: fn(&Hexagram) -> Hexagram,
+    color: Color,
+}
+
+const RELATIONS: [Relation; 3] = [
+    Relation {
+        key: 'n',
+        name: "book-nuclear",
+        related: Hexagram::nuclear_hexagram,
+        color: Color::Yellow,
+    },
+    Relation {
+        key: 'i',
+        name: "book-inverse",
+        related: Hexagram::inverse_hexagram,
+        color: Color::Cyan,
+    },
+    Relation {
+        key: 'c',
+        name: "book-complement",
+        related: Hexagram::complement_hexagram,
+        color: Color::Magenta,
+    },
+];
+
+/// Whether the browser keeps running after a key press.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+    Continue,
+    Quit,
+}
+
+/// The state of the browser: the ordering of the grid, the selected hexagram and how far its
+/// text is scrolled. The text is shown in the theme, language, texts and commentaries of the
+/// settings.
+pub(crate) struct Browser<'a, R> {
+    hexagrams: &'a R,
+    ordering: HexagramOrdering,
+    selected: Hexagram,
+    scroll: u16,
+    settings: Settings,
+}
+
+impl<'a, R: HexagramRepository<HexagramInfo = HexagramJsonInfo>> Browser<'a, R> {
+    /// Start browsing at the first hexagram of `ordering`, showing text as `settings` ask for.
+    pub(crate) fn new(hexagrams: &'a R, ordering: HexagramOrdering, settings: &Settings) -> Self {
+        Browser {
+            hexagrams,
+            ordering,
+            selected: ordering
+                .hexagram_at(1)
+                .expect("every ordering has a first hexagram"),
+            scroll: 0,
+            settings: settings.clone(),
+        }
+    }
+
+    fn select(&mut self, hexagram: Hexagram) {
+        self.selected = hexagram;
+        self.scroll = 0;
+    }
+
+    /// Select the hexagram `offset` places away in the ordering, if there is one.
+    fn move_by(&mut self, offset: i16) {
+        let position = i16::from(self.ordering.position(&self.selected)) + offset;
+        if let Some(hexagram) = u8::try_from(position)
+            .ok()
+            .and_then(|position| self.ordering.hexagram_at(position))
+        {
+            self.select(hexagram);
+        }
+    }
+
+    fn next_ordering(&self) -> HexagramOrdering {
+        match self.ordering {
+            HexagramOrdering::KingWen => HexagramOrdering::Binary,
+            HexagramOrdering::Binary => HexagramOrdering::Mawangdui,
+            HexagramOrdering::Mawangdui => HexagramOrdering::KingWen,
+        }
+    }
+
+    /// Respond to a key press.
+    pub(crate) fn handle_key(&mut self, key: KeyCode) -> Action {
+        let grid_size = i16::from(GRID_SIZE);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Left | KeyCode::Char('h') => self.move_by(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-grid_size),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(grid_size),
+            KeyCode::Char(digit @ '1'..='6') => {
+                let position = digit.to_digit(10).expect("the key is a digit") as usize;
+                if let Some(hexagram) = self.selected.with_line_flipped(position) {
+                    self.select(hexagram);
+                }
+            }
+            KeyCode::Char('o') => self.ordering = self.next_ordering(),
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.scroll = self.scroll.saturating_add(PAGE).min(self.text_height());
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::Char(key) => {
+                if let Some(relation) = RELATIONS.iter().find(|relation| relation.key == key) {
+                    self.select((relation.related)(&self.selected));
+                }
+            }
+            _ => {}
+        }
+
+        Action::Continue
+    }
+
+    fn info(&self, hexagram: &Hexagram) -> &'a HexagramJsonInfo {
+        self.hexagrams.get_info_for_hexagram(hexagram)
+    }
+
+    /// A short label for a hexagram, e.g. `䷀ 1 The Creative`.
+    fn label(&self, hexagram: &Hexagram) -> String {
+        HexagramView::from(self.info(hexagram)).label()
+    }
+
+    /// The selected hexagram's full text.
+    fn text(&self) -> Text<'static> {
+        let view = HexagramView::from(self.info(&self.selected))
+            .with_lines()
+            .with_line_labels(self.settings.line_labels)
+            .with_lang(self.settings.lang)
+            .with_texts(self.settings.texts)
+            .with_commentary(&self.settings.with);
+        let mut renderer = TuiRenderer::new(&self.settings.theme);
+        render_document(&mut renderer, &view).expect("rendering to text in memory succeeds");
+        renderer.into_text()
+    }
+
+    /// The number of lines of text, before wrapping.
+    fn text_height(&self) -> u16 {
+        u16::try_from(self.text().height()).unwrap_or(u16::MAX)
+    }
+
+    fn grid(&self) -> Text<'static> {
+        let hexagrams: Vec<Hexagram> = self.ordering.hexagrams().collect();
+        let related: Vec<(Hexagram, Color)> = RELATIONS
+            .iter()
+            .map(|relation| ((relation.related)(&self.selected), relation.color))
+            .collect();
+
+        let rows = hexagrams.chunks(usize::from(GRID_SIZE)).map(|row| {
+            let cells: Vec<text::Span> = row
+                .iter()
+                .map(|hexagram| {
+                    let cell = format!(
+                        "{}{:>3} ",
+                        hexagram.symbol(false),
+                        self.ordering.position(hexagram)
+                    );
+                    let style = if *hexagram == self.selected {
+                        Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+                    } else if let Some((_, color)) =
+                        related.iter().find(|(related, _)| related == hexagram)
+                    {
+                        Style::new().fg(*color)
+                    } else {
+                        Style::new()
+                    };
+                    text::Span::styled(cell, style)
+                })
+                .collect();
+            text::Line::from(cells)
+        });
+
+        Text::from_iter(rows)
+    }
+
+    /// The selected hexagram's lines from the top down, each with the key that flips it, and its
+    /// related hexagrams.
+    fn lines_and_relations(&self) -> Text<'static> {
+        let values = self.selected.line_values();
+        let mut lines: Vec<text::Line> = (1..=6)
+            .rev()
+            .map(|position| {
+                let line = Line::try_from(values[position - 1]).expect("lines have valid values");
+                let art = match line {
+                    Line::Unbroken { .. } => "━━━━━━━━━━━",
+                    Line::Broken { .. } => "━━━━   ━━━━",
+                };
+                text::Line::raw(format!("{position}  {art}"))
+            })
+            .collect();
+        lines.push(text::Line::default());
+        let messages = self.settings.lang.messages();
+        for relation in RELATIONS {
+            // Padded by hand, as `format!` counts Chinese characters as one column instead of two.
+            let name = messages.get(relation.name);
+            let padding = " ".repeat(11usize.saturating_sub(name.width()));
+            lines.push(text::Line::from(vec![
+                text::Span::raw(format!("{}  ", relation.key)),
+                text::Span::styled(format!("{name}{padding}"), Style::new().fg(relation.color)),
+                text::Span::raw(self.label(&(relation.related)(&self.selected))),
+            ]));
+        }
+
+        Text::from(lines)
+    }
+
+    /// Draw the whole browser.
+    pub(crate) fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, text] =
+            Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(0)]).areas(main);
+        let [grid, lines] = Layout::vertical([
+            Constraint::Length(u16::from(GRID_SIZE) + 2),
+            Constraint::Min(0),
+        ])
+        .areas(sidebar);
+        let messages = self.settings.lang.messages();
+        let grid_title = messages.format(
+            "browse-grid",
+            &[("ordering", ordering_name(self.ordering).into())],
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.grid()).block(Block::bordered().title(format!(" {grid_title} "))),
+            grid,
+        );
+        frame.render_widget(
+            Paragraph::new(self.lines_and_relations())
+                .block(Block::bordered().title(format!(" {} ", messages.get("browse-lines")))),
+            lines,
+        );
+        frame.render_widget(
+            Paragraph::new(self.text())
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(Block::bordered().title(format!(" {} ", self.label(&self.selected)))),
+            text,
+        );
+        frame.render_widget(
+            Paragraph::new(messages.get("browse-help"))
+                .style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+}
+
+/// Browse the hexagrams in the terminal until the browser is quit.
+pub(crate) fn browse(
+    ordering: HexagramOrdering,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Error> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, Browser::new(hexagrams, ordering, settings));
+    // The terminal is restored even if the browser failed, so that the error can be read.
+    ratatui::try_restore()?;
+    result
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut browser: Browser<impl HexagramRepository<HexagramInfo = HexagramJsonInfo>>,
+) -> Result<(), Error> {
+    loop {
+        terminal.draw(|frame| browser.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && browser.handle_key(key.code) == Action::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hexagram_json::HexagramJson,
+        locale::Lang,
+        view::{Layer, Texts},
+    };
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn hexagrams() -> HexagramJson {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        hexagrams
+    }
+
+    fn screen(browser: &Browser<HexagramJson>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(usize::from(buffer.area.width))
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_keys_move_flip_and_jump() {
+        let hexagrams = hexagrams();
+        let mut browser = Browser::new(&hexagrams, HexagramOrdering::KingWen, &Settings::default());
+        let number = |browser: &Browser<HexagramJson>| browser.info(&browser.selected).number();
+
+        browser.handle_key(KeyCode::Right);
+        assert_eq!(number(&browser), 2);
+        browser.handle_key(KeyCode::Down);
+        assert_eq!(number(&browser), 10);
+        browser.handle_key(KeyCode::Up);
+        browser.handle_key(KeyCode::Up);
+        assert_eq!(number(&browser), 2);
+
+        // Flipping the bottom line of ䷁ The Receptive gives ䷗ Return.
+        browser.handle_key(KeyCode::Char('1'));
+        assert_eq!(number(&browser), 24);
+        // The inverse of ䷗ Return is ䷖ Splitting Apart.
+        browser.handle_key(KeyCode::Char('i'));
+        assert_eq!(number(&browser), 23);
+
+        browser.handle_key(KeyCode::Char('o'));
+        assert_eq!(browser.ordering, HexagramOrdering::Binary);
+        assert_eq!(number(&browser), 23);
+        assert_eq!(browser.handle_key(KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_screen_shows_grid_relations_and_text() {
+        let hexagrams = hexagrams();
+        let browser = Browser::new(&hexagrams, HexagramOrdering::KingWen, &Settings::default());
+
+        let screen = screen(&browser);
+
+        assert!(screen.contains("Hexagrams, King Wen sequence"), "{screen}");
+        assert!(screen.contains("Complement"), "{screen}");
+        assert!(screen.contains("Judgement:"), "{screen}");
+        assert!(screen.contains("arrows move"), "{screen}");
+    }
+
+    #[test]
+    fn test_text_follows_the_settings() {
+        let hexagrams = hexagrams();
+        let settings = Settings {
+            lang: Lang::ZhHans,
+            texts: Texts::Original,
+            with: vec![Layer::Tuan],
+            ..Settings::default()
+        };
+        let browser = Browser::new(&hexagrams, HexagramOrdering::KingWen, &settings);
+
+        let text: String = browser
+            .text()
+            .lines
+            .iter()
+            .flat_map(|line| line.spans.iter().map(|span| span.content.to_string()))
+            .collect();
+
+        assert!(text.contains("卦辞"), "{text}");
+        assert!(text.contains("元亨利貞"), "{text}");
+        assert!(text.contains("彖传"), "{text}");
+        assert!(!text.contains("Judgement"), "{text}");
+    }
+}
@@ -0,0 +1,316 @@
+//! Casting a hexagram step by step, for `divine --interactive`: one line at a time from the
+//! bottom up, waiting for the querent before each line, or before each coin with the coin toss
+//! method. At every step a line (or coin) can be typed in instead, so that the app can record a
+//! cast made with real coins or stalks.
+//!
+//! The terminal is only read a line at a time, so "waiting" means waiting for Enter.
+
+use crate::locale::{Lang, Messages};
+use iching::{
+    divination_method::DivinationMethod,
+    hexagram::Hexagram,
+    line::Line,
+    symbols::big_line::{rows, LINE_SPACER},
+};
+use std::io::{self, BufRead, Write};
+
+/// Heads count for 3 and tails for 2, so three coins add up to the value of a line.
+const HEADS: u8 = 3;
+const TAILS: u8 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to cast interactively: {0}")]
+    Io(#[from] io::Error),
+    #[error("The cast was abandoned before all six lines were cast")]
+    Abandoned,
+}
+
+/// What was typed at a prompt.
+#[derive(Debug, PartialEq, Eq)]
+enum Entry {
+    /// Nothing, so the app casts for the querent.
+    Cast,
+    /// A whole line, as its value or as three coins.
+    Line(Line),
+    /// A single coin, as its value.
+    Coin(u8),
+}
+
+/// Parse what was typed at a prompt. Coins are typed as `h` or `t`, or as their values 3 and 2,
+/// and lines as three coins (e.g. `hht` or `3 3 2`) or as their values from 6 to 9.
+fn parse_entry(text: &str) -> Option<Entry> {
+    let coins: Vec<u8> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .map(|c| match c.to_ascii_lowercase() {
+            'h' | '3' => Some(HEADS),
+            't' | '2' => Some(TAILS),
+            _ => None,
+        })
+        .collect::<Option<_>>()
+        .unwrap_or_default();
+
+    match text.trim() {
+        "" => Some(Entry::Cast),
+        value @ ("6" | "7" | "8" | "9") => {
+            let value: u8 = value.parse().expect("the value is a digit");
+            Some(Entry::Line(
+                Line::try_from(value).expect("6 to 9 are lines"),
+            ))
+        }
+        _ => match coins.as_slice() {
+            [coin] => Some(Entry::Coin(*coin)),
+            [_, _, _] => Some(Entry::Line(
+                Line::try_from(coins.iter().sum::<u8>()).expect("three coins add up to 6 to 9"),
+            )),
+            _ => None,
+        },
+    }
+}
+
+/// Ask for what was cast, until something that can be used is typed in.
+fn prompt(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    messages: &Messages,
+    message: &str,
+) -> Result<Entry, Error> {
+    loop {
+        write!(output, "{message} ")?;
+        output.flush()?;
+        let mut text = String::new();
+        if input.read_line(&mut text)? == 0 {
+            return Err(Error::Abandoned);
+        }
+        match parse_entry(&text) {
+            Some(entry) => return Ok(entry),
+            None => writeln!(output, "{}", messages.get("casting-invalid-entry"))?,
+        }
+    }
+}
+
+fn coin_name(messages: &Messages, coin: u8) -> String {
+    let side = if coin == HEADS { "heads" } else { "tails" };
+    messages.format("casting-coin", &[("side", side.into())])
+}
+
+/// Cast a single line, coin by coin with the coin toss method.
+fn cast_line(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    messages: &Messages,
+    position: usize,
+    method: DivinationMethod,
+    rng: &fastrand::Rng,
+) -> Result<Line, Error> {
+    if method != DivinationMethod::CoinToss {
+        let message = messages.format("casting-cast-line", &[("position", position.into())]);
+        loop {
+            match prompt(input, output, messages, &message)? {
+                Entry::Line(line) => return Ok(line),
+                Entry::Cast => return Ok(Line::new_random_with_rng(method, rng)),
+                // A single coin means nothing to the yarrow stalks, and casting a line in its
+                // place would record a line that was never cast.
+                Entry::Coin(_) => writeln!(output, "{}", messages.get("casting-whole-line"))?,
+            }
+        }
+    }
+
+    let mut coins = Vec::new();
+    while coins.len() < 3 {
+        let message = messages.format(
+            "casting-toss-coin",
+            &[
+                ("coin", (coins.len() + 1).into()),
+                ("position", position.into()),
+            ],
+        );
+        let coin = match prompt(input, output, messages, &message)? {
+            Entry::Line(line) if coins.is_empty() => return Ok(line),
+            Entry::Line(_) => {
+                writeln!(output, "{}", messages.get("casting-single-coin"))?;
+                continue;
+            }
+            Entry::Coin(coin) => coin,
+            Entry::Cast => {
+                if rng.bool() {
+                    HEADS
+                } else {
+                    TAILS
+                }
+            }
+        };
+        writeln!(output, "{}", coin_name(messages, coin))?;
+        coins.push(coin);
+    }
+
+    Ok(Line::try_from(coins.iter().sum::<u8>()).expect("three coins add up to 6 to 9"))
+}
+
+/// Draw the lines cast so far, given from the bottom up, as big lines with the newest on top.
+fn draw_lines(output: &mut impl Write, lines: &[Line]) -> io::Result<()> {
+    for line in lines.iter().rev() {
+        for row in line.big_rows() {
+            writeln!(output, "{row}")?;
+        }
+        for row in rows(LINE_SPACER) {
+            writeln!(output, "{row}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Cast a hexagram step by step, reading from `input` and prompting on `output` in the given
+/// language. The question is asked for first, unless one was given already. Returns the question,
+/// if any, and the hexagram.
+pub(crate) fn cast_interactively(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: Option<String>,
+    method: DivinationMethod,
+    lang: Lang,
+    rng: &fastrand::Rng,
+) -> Result<(Option<String>, Hexagram), Error> {
+    let messages = lang.messages();
+    let question = match question {
+        Some(question) => Some(question),
+        None => {
+            write!(output, "{} ", messages.get("casting-question"))?;
+            output.flush()?;
+            let mut question = String::new();
+            if input.read_line(&mut question)? == 0 {
+                return Err(Error::Abandoned);
+            }
+            Some(question.trim().to_owned()).filter(|question| !question.is_empty())
+        }
+    };
+    writeln!(output, "\n{}\n", messages.get("casting-begin"))?;
+
+    let mut lines = Vec::new();
+    for position in 1..=6 {
+        let line = cast_line(input, output, messages, position, method, rng)?;
+        lines.push(line);
+        let cast = messages.format(
+            "casting-line",
+            &[
+                ("position", position.into()),
+                ("value", line.value().into()),
+            ],
+        );
+        writeln!(output, "\n{cast}\n")?;
+        draw_lines(output, &lines)?;
+    }
+
+    let values: Vec<u8> = lines.iter().map(Line::value).collect();
+    let values: [u8; 6] = values.try_into().expect("six lines were cast");
+    let hexagram = Hexagram::try_from(values).expect("cast lines have valid values");
+
+    Ok((question, hexagram))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_are_parsed() {
+        assert_eq!(parse_entry("\n"), Some(Entry::Cast));
+        assert_eq!(
+            parse_entry("9\n"),
+            Some(Entry::Line(Line::unbroken_changing()))
+        );
+        assert_eq!(parse_entry("H h T"), Some(Entry::Line(Line::broken())));
+        assert_eq!(
+            parse_entry("3, 3, 3"),
+            Some(Entry::Line(Line::unbroken_changing()))
+        );
+        assert_eq!(parse_entry("t"), Some(Entry::Coin(TAILS)));
+        assert_eq!(parse_entry("hh"), None);
+        assert_eq!(parse_entry("5"), None);
+    }
+
+    #[test]
+    fn test_lines_are_cast_from_the_bottom_up() {
+        // A question, then line 1 typed in, line 2 as three coins, line 3 coin by coin (with a
+        // mistake), then the rest cast by the app.
+        let mut input = "Will it rain?\n6\nhhh\nh\nhh\nt\nh\n\n\n\n\n\n\n\n\n\n".as_bytes();
+        let mut output = Vec::new();
+        let rng = fastrand::Rng::with_seed(1);
+
+        let (question, hexagram) = cast_interactively(
+            &mut input,
+            &mut output,
+            None,
+            DivinationMethod::CoinToss,
+            Lang::En,
+            &rng,
+        )
+        .unwrap();
+
+        assert_eq!(question.as_deref(), Some("Will it rain?"));
+        assert_eq!(hexagram.line_values()[..3], [6, 9, 8]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Line 6 is "));
+        assert!(output.contains("Type h or t for a coin"));
+    }
+
+    #[test]
+    fn test_single_coins_are_refused_by_the_yarrow_stalks() {
+        // Line 1 as a single coin, which is refused, and then as its value.
+        let mut input = "h\n7\n8\n8\n8\n8\n8\n".as_bytes();
+        let mut output = Vec::new();
+
+        let (_, hexagram) = cast_interactively(
+            &mut input,
+            &mut output,
+            Some(String::from("Will it rain?")),
+            DivinationMethod::AncientYarrowStalk,
+            Lang::En,
+            &fastrand::Rng::with_seed(1),
+        )
+        .unwrap();
+
+        assert_eq!(hexagram.line_values(), [7, 8, 8, 8, 8, 8]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("The yarrow stalks cast a whole line at once"));
+        assert_eq!(output.matches("Press Enter to cast line 1,").count(), 2);
+    }
+
+    #[test]
+    fn test_running_out_of_input_abandons_the_cast() {
+        let mut input = "\n\n".as_bytes();
+        let result = cast_interactively(
+            &mut input,
+            &mut Vec::new(),
+            Some(String::from("Will it rain?")),
+            DivinationMethod::AncientYarrowStalk,
+            Lang::En,
+            &fastrand::Rng::new(),
+        );
+
+        assert!(matches!(result, Err(Error::Abandoned)));
+    }
+
+    #[test]
+    fn test_prompts_are_in_the_language_asked_for() {
+        let input = "\n".repeat(6);
+        let mut output = Vec::new();
+
+        cast_interactively(
+            &mut input.as_bytes(),
+            &mut output,
+            Some(String::from("会下雨吗？")),
+            DivinationMethod::AncientYarrowStalk,
+            Lang::ZhHans,
+            &fastrand::Rng::with_seed(1),
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("按 Enter 起第 1 爻"), "{output}");
+        assert!(output.contains("第 6 爻为 "), "{output}");
+        assert!(!output.contains("Line"), "{output}");
+    }
+}
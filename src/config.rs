@@ -0,0 +1,441 @@
+//! The config file, which sets the defaults that command line options override.
+//!
+//! The config file is written in TOML and read from `iching/config.toml` in the user's config
+//! directory (e.g. `~/.config` on Linux), or from the path given with `--config`. Every setting is
+//! optional:
+//!
+//! ```toml
+//! method = "coin-toss"              # or "ancient-yarrow-stalk"
+//! data = "my-translation.json"      # the hexagram texts, in the format of src/hexagrams.json
+//! format = "markdown"               # text, json, yaml, markdown, html or svg
+//! color = "never"                   # auto, always or never
+//! journal = "readings.json"
+//! line-labels = "traditional"       # positions ("Line 1") or traditional ("Nine at the beginning")
+//! school = "zhu-xi"                 # every-changing-line or zhu-xi
+//! theme = "paper"                   # dark, light, high-contrast, monochrome, 16-color or your own
+//! width = 100                       # columns to wrap text to, or 0 not to wrap
+//! lang = "zh-Hant"                  # en, zh-Hans or zh-Hant; your locale's language by default
+//! texts = "both"                    # translation, original (the classical Chinese) or both
+//! with = ["tuan", "small-images"]   # commentaries of the Ten Wings to show, or ["commentary"]
+//!
+//! # How images (`--format svg` and `--output reading.png`) are drawn. Colors are anything that
+//! # SVG accepts.
+//! [image]
+//! scale = 1.5                       # the size of images, as a multiple of the usual size
+//! line-color = "#222"
+//! changing-color = "darkred"        # the color of changing lines and what they change into
+//! background = "none"               # a color, or none for a transparent background
+//! text-color = "#222"
+//! markers = false                   # whether changing lines are marked with a circle or cross
+//!
+//! # A theme of your own, which starts from a built-in theme (dark unless `base` says otherwise)
+//! # and restyles some of its roles.
+//! [themes.paper]
+//! base = "light"
+//! title = "bold #8b0000"
+//! changing-line = "bright blue"
+//! ```
+//!
+//! Relative paths are relative to the directory of the config file. Theme roles are `title`,
+//! `judgement`, `image`, `lines`, `subsection`, `changing-line` and `highlight`, and their styles
+//! are written as described in [`theme`](crate::render::theme).
+
+use crate::{
+    journal::Journal,
+    locale::Lang,
+    render::{
+        self,
+        theme::{Style, THEME_NAMES},
+        Column, HeadingLevel, Render, Renderer, Theme,
+    },
+    view::{Layer, OutputFormat, Texts},
+    ColorPreference,
+};
+use clap::ValueEnum;
+use iching::{
+    divination_method::DivinationMethod,
+    interpretation::{LineLabels, School},
+    svg::SvgStyle,
+};
+use ratatui::crossterm::terminal;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to read the config file {}: {1}", .0.display())]
+    Io(PathBuf, #[source] io::Error),
+    #[error("Failed to parse the config file {}: {1}", .0.display())]
+    Parse(PathBuf, #[source] toml::de::Error),
+    #[error("Unknown theme {0:?}. Use one of {themes}, or define it under [themes.{0}] in the config file", themes = THEME_NAMES.join(", "))]
+    UnknownTheme(String),
+    #[error("The theme {0:?} is based on {1:?}, which isn't a built-in theme. Use one of {themes}", themes = THEME_NAMES.join(", "))]
+    UnknownBaseTheme(String, String),
+    #[error("The image scale in {} must be a number above 0, but it's {1}", .0.display())]
+    ImageScale(PathBuf, f64),
+}
+
+/// The settings of a config file, each of which may be left out.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    pub(crate) method: Option<DivinationMethod>,
+    pub(crate) data: Option<PathBuf>,
+    pub(crate) format: Option<OutputFormat>,
+    pub(crate) color: Option<ColorPreference>,
+    pub(crate) journal: Option<PathBuf>,
+    pub(crate) line_labels: Option<LineLabels>,
+    pub(crate) school: Option<School>,
+    pub(crate) theme: Option<String>,
+    pub(crate) width: Option<usize>,
+    pub(crate) lang: Option<Lang>,
+    pub(crate) texts: Option<Texts>,
+    pub(crate) with: Option<Vec<Layer>>,
+    /// Themes of the user's own, by name.
+    pub(crate) themes: BTreeMap<String, ThemeConfig>,
+    pub(crate) image: ImageConfig,
+}
+
+/// A theme of the user's own, which restyles some roles of a built-in theme.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ThemeConfig {
+    base: Option<String>,
+    title: Option<Style>,
+    judgement: Option<Style>,
+    image: Option<Style>,
+    lines: Option<Style>,
+    subsection: Option<Style>,
+    changing_line: Option<Style>,
+    highlight: Option<Style>,
+}
+
+/// How images are drawn, each part of which may be left out.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ImageConfig {
+    /// The size of images, as a multiple of the size of [`SvgStyle::default`].
+    pub(crate) scale: f64,
+    pub(crate) line_color: String,
+    pub(crate) changing_color: String,
+    /// The color that fills images, or `none` to leave them transparent.
+    pub(crate) background: String,
+    pub(crate) text_color: String,
+    pub(crate) markers: bool,
+}
+
+impl ImageConfig {
+    /// Get the style that images are drawn in.
+    pub(crate) fn style(&self) -> SvgStyle {
+        SvgStyle {
+            line_color: self.line_color.clone(),
+            changing_color: self.changing_color.clone(),
+            background: (self.background != "none").then(|| self.background.clone()),
+            text_color: self.text_color.clone(),
+            markers: self.markers,
+            ..SvgStyle::default()
+        }
+        .scaled(self.scale)
+    }
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        let style = SvgStyle::default();
+        ImageConfig {
+            scale: 1.0,
+            line_color: style.line_color,
+            changing_color: style.changing_color,
+            background: style.background.unwrap_or_else(|| String::from("none")),
+            text_color: style.text_color,
+            markers: style.markers,
+        }
+    }
+}
+
+impl Config {
+    /// Get the default location of the config file, inside the user's config directory.
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|config_dir| config_dir.join("iching").join("config.toml"))
+    }
+
+    /// Load the config file at `path`, or at the default location if no path was given. Returns
+    /// the config and the path that it was read from. A missing file at the default location
+    /// gives an empty config, but a missing file that was asked for is an error.
+    pub(crate) fn load(path: Option<&Path>) -> Result<(Config, Option<PathBuf>), Error> {
+        let (path, required) = match path {
+            Some(path) => (path.to_owned(), true),
+            None => match Config::default_path() {
+                Some(path) => (path, false),
+                None => return Ok((Config::default(), None)),
+            },
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => {
+                return Ok((Config::default(), None))
+            }
+            Err(err) => return Err(Error::Io(path, err)),
+        };
+
+        let mut config: Config =
+            toml::from_str(&contents).map_err(|err| Error::Parse(path.clone(), err))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        config.data = config.data.map(|data| directory.join(data));
+        config.journal = config.journal.map(|journal| directory.join(journal));
+        if !(config.image.scale > 0.0 && config.image.scale.is_finite()) {
+            return Err(Error::ImageScale(path, config.image.scale));
+        }
+
+        Ok((config, Some(path)))
+    }
+
+    /// Get the theme with the given name: one of the config file's own themes, which take
+    /// precedence, or a built-in theme.
+    pub(crate) fn theme(&self, name: &str) -> Result<Theme, Error> {
+        let Some(custom) = self.themes.get(name) else {
+            return Theme::built_in(name).ok_or_else(|| Error::UnknownTheme(name.to_owned()));
+        };
+        let base = custom.base.as_deref().unwrap_or("dark");
+        let base = Theme::built_in(base)
+            .ok_or_else(|| Error::UnknownBaseTheme(name.to_owned(), base.to_owned()))?;
+
+        Ok(Theme {
+            name: name.to_owned(),
+            title: custom.title.unwrap_or(base.title),
+            judgement: custom.judgement.unwrap_or(base.judgement),
+            image: custom.image.unwrap_or(base.image),
+            lines: custom.lines.unwrap_or(base.lines),
+            subsection: custom.subsection.unwrap_or(base.subsection),
+            changing_line: custom.changing_line.unwrap_or(base.changing_line),
+            highlight: custom.highlight.unwrap_or(base.highlight),
+        })
+    }
+}
+
+/// The settings that the app runs with: the command line options, then the config file, then the
+/// defaults. These are shown by `config show`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Settings {
+    /// The config file that was read, if any.
+    pub(crate) config_file: Option<PathBuf>,
+    pub(crate) method: DivinationMethod,
+    /// The file that the hexagram texts are read from, instead of the texts built into the app.
+    pub(crate) data: Option<PathBuf>,
+    pub(crate) format: OutputFormat,
+    pub(crate) color: ColorPreference,
+    pub(crate) journal: Option<PathBuf>,
+    pub(crate) line_labels: LineLabels,
+    pub(crate) school: School,
+    pub(crate) theme: Theme,
+    /// The number of columns to wrap text to, if it was set. 0 turns wrapping off.
+    pub(crate) width: Option<usize>,
+    /// The language of messages and labels.
+    pub(crate) lang: Lang,
+    /// Which texts of the hexagrams to show: the translation, the original Chinese or both.
+    pub(crate) texts: Texts,
+    /// The commentaries of the Ten Wings to show along with the text of the hexagrams.
+    pub(crate) with: Vec<Layer>,
+    pub(crate) image: ImageConfig,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            config_file: None,
+            method: DivinationMethod::AncientYarrowStalk,
+            data: None,
+            format: OutputFormat::Text,
+            color: ColorPreference::Auto,
+            journal: None,
+            line_labels: LineLabels::default(),
+            school: School::default(),
+            theme: Theme::default(),
+            width: None,
+            lang: Lang::default(),
+            texts: Texts::default(),
+            with: Vec::new(),
+            image: ImageConfig::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Get the number of columns to wrap text output to: the configured width, or else the width
+    /// of the terminal. Output that isn't going to a terminal isn't wrapped unless a width was
+    /// set.
+    pub(crate) fn text_width(&self) -> Option<usize> {
+        match self.width {
+            Some(0) => None,
+            Some(width) => Some(width),
+            None if atty::is(atty::Stream::Stdout) => terminal::size()
+                .ok()
+                .map(|(columns, _)| usize::from(columns)),
+            None => None,
+        }
+    }
+}
+
+/// Get the name of a setting's value, as it's written in the config file and on the command line.
+fn value_name(value: &impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .expect("settings aren't skipped values")
+        .get_name()
+        .to_owned()
+}
+
+impl Render for Settings {
+    fn title(&self) -> String {
+        self.lang.messages().get("settings-title")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        let path_message =
+            |id, path: &Path| messages.format(id, &[("path", path.display().to_string().into())]);
+        match (&self.config_file, Config::default_path()) {
+            (Some(path), _) => renderer.paragraph(&path_message("settings-read-from", path))?,
+            (None, Some(path)) => {
+                renderer.paragraph(&path_message("settings-no-config-file-at", &path))?;
+            }
+            (None, None) => renderer.paragraph(&messages.get("settings-no-config-file"))?,
+        }
+
+        let journal = match Journal::path_or_default(self.journal.clone()) {
+            Ok(path) => path.display().to_string(),
+            Err(err) => err.to_string(),
+        };
+        let data = self.data.as_ref().map_or_else(
+            || messages.get("settings-built-in-data"),
+            |data| data.display().to_string(),
+        );
+        let width = match self.width {
+            Some(0) => messages.get("settings-not-wrapped"),
+            Some(width) => width.to_string(),
+            None => messages.get("settings-terminal-width"),
+        };
+        let with = if self.with.is_empty() {
+            messages.get("settings-none")
+        } else {
+            let names: Vec<String> = self.with.iter().map(value_name).collect();
+            names.join(", ")
+        };
+        let rows = vec![
+            vec![String::from("method"), value_name(&self.method)],
+            vec![String::from("data"), data],
+            vec![String::from("format"), value_name(&self.format)],
+            vec![String::from("color"), value_name(&self.color)],
+            vec![String::from("journal"), journal],
+            vec![String::from("line-labels"), value_name(&self.line_labels)],
+            vec![String::from("school"), value_name(&self.school)],
+            vec![String::from("theme"), self.theme.name.clone()],
+            vec![String::from("width"), width],
+            vec![String::from("lang"), value_name(&self.lang)],
+            vec![String::from("texts"), value_name(&self.texts)],
+            vec![String::from("with"), with],
+            vec![String::from("image.scale"), self.image.scale.to_string()],
+            vec![
+                String::from("image.line-color"),
+                self.image.line_color.clone(),
+            ],
+            vec![
+                String::from("image.changing-color"),
+                self.image.changing_color.clone(),
+            ],
+            vec![
+                String::from("image.background"),
+                self.image.background.clone(),
+            ],
+            vec![
+                String::from("image.text-color"),
+                self.image.text_color.clone(),
+            ],
+            vec![
+                String::from("image.markers"),
+                self.image.markers.to_string(),
+            ],
+        ];
+        renderer.table(
+            &[
+                Column::left(&messages.get("column-setting")),
+                Column::left(&messages.get("column-value")),
+            ],
+            &rows,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_files_are_parsed() {
+        let path = std::env::temp_dir().join(format!("iching-config-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "method = \"coin-toss\"\nline-labels = \"traditional\"\njournal = \"readings.json\"\n",
+        )
+        .unwrap();
+        let loaded = Config::load(Some(&path));
+        fs::write(&path, "colour = \"never\"\n").unwrap();
+        let misspelled = Config::load(Some(&path));
+        fs::remove_file(&path).unwrap();
+
+        let (config, loaded_from) = loaded.unwrap();
+        assert_eq!(config.method, Some(DivinationMethod::CoinToss));
+        assert_eq!(config.line_labels, Some(LineLabels::Traditional));
+        assert_eq!(config.format, None);
+        assert_eq!(
+            config.journal,
+            Some(std::env::temp_dir().join("readings.json"))
+        );
+        assert_eq!(loaded_from, Some(path.clone()));
+        assert!(matches!(misspelled, Err(Error::Parse(..))));
+        assert!(matches!(Config::load(Some(&path)), Err(Error::Io(..))));
+    }
+
+    #[test]
+    fn test_custom_themes_restyle_a_built_in_theme() {
+        let config: Config = toml::from_str(
+            "theme = \"paper\"\n[themes.paper]\nbase = \"light\"\ntitle = \"bold #8b0000\"\n",
+        )
+        .unwrap();
+
+        let paper = config.theme("paper").unwrap();
+        let light = Theme::built_in("light").unwrap();
+        assert_eq!(paper.title.to_string(), "bold #8b0000");
+        assert_eq!(paper.judgement, light.judgement);
+        assert_eq!(config.theme("16-color").unwrap().name, "16-color");
+        assert!(matches!(config.theme("sepia"), Err(Error::UnknownTheme(_))));
+        assert!(toml::from_str::<Config>("[themes.paper]\ntitle = \"bold grey\"\n").is_err());
+    }
+
+    #[test]
+    fn test_images_are_styled_by_the_config_file() {
+        let config: Config = toml::from_str(
+            "[image]\nscale = 2\nchanging-color = \"darkred\"\nbackground = \"none\"\nmarkers = false\n",
+        )
+        .unwrap();
+        let style = config.image.style();
+        let default = SvgStyle::default();
+
+        assert_eq!(style.line_width, default.line_width * 2.0);
+        assert_eq!(style.changing_color, "darkred");
+        assert_eq!(style.line_color, default.line_color);
+        assert_eq!(style.background, None);
+        assert!(!style.markers);
+        assert_eq!(Config::default().image.style(), default);
+        assert!(toml::from_str::<Config>("[image]\nsize = 2\n").is_err());
+    }
+}
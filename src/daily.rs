@@ -0,0 +1,78 @@
+use crate::{divination_method::DivinationMethod, Hexagram};
+use chrono::NaiveDate;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Get the seed for the "hexagram of the day" of the given date. An optional salt can be given so
+/// that different groups receive different hexagrams on the same day.
+///
+/// The seed is derived with [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// instead of the standard library's hasher, whose output may change between Rust releases.
+/// This keeps the seed identical across machines and builds.
+pub fn seed_for_date(date: NaiveDate, salt: Option<&str>) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    write(date.format("%Y-%m-%d").to_string().as_bytes());
+    if let Some(salt) = salt {
+        // Separate the salt from the date so that e.g. a salt of "1" can't be confused with a
+        // longer date.
+        write(&[0]);
+        write(salt.as_bytes());
+    }
+
+    hash
+}
+
+/// Get the "hexagram of the day" for the given date. Everyone using the same date, salt and
+/// [`DivinationMethod`] will receive the same `Hexagram`, including its changing lines.
+pub fn hexagram_of_the_day(
+    date: NaiveDate,
+    salt: Option<&str>,
+    divination_method: DivinationMethod,
+) -> Hexagram {
+    Hexagram::new_seeded(divination_method, seed_for_date(date, salt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_seed_is_stable() {
+        // FNV-1a of "2023-01-01". If this changes, everyone's hexagram of the day changes too.
+        assert_eq!(seed_for_date(date(2023, 1, 1), None), 0xe9d8_90a1_f07d_0ef4);
+    }
+
+    #[test]
+    fn test_salt_changes_seed() {
+        let day = date(2023, 1, 1);
+        assert_ne!(seed_for_date(day, None), seed_for_date(day, Some("team")));
+        assert_ne!(
+            seed_for_date(day, Some("team")),
+            seed_for_date(day, Some("other team"))
+        );
+    }
+
+    #[test]
+    fn test_hexagram_of_the_day_is_reproducible() {
+        for method in [
+            DivinationMethod::AncientYarrowStalk,
+            DivinationMethod::CoinToss,
+        ] {
+            let first = hexagram_of_the_day(date(2023, 1, 1), Some("team"), method);
+            let second = hexagram_of_the_day(date(2023, 1, 1), Some("team"), method);
+            assert!(first.lines().eq(second.lines()));
+        }
+    }
+}
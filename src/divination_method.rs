@@ -1,6 +1,12 @@
+use crate::{line::Line, probability::Probability};
 use clap::ValueEnum;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+/// The odds of each method are expressed as a number of outcomes out of this many.
+pub(crate) const LINE_OUTCOMES: u8 = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum DivinationMethod {
     /// The ancient yarrow stalk method of divination.
     ///
@@ -11,3 +17,44 @@ pub enum DivinationMethod {
     /// See [this Wikipedia article](https://en.wikipedia.org/wiki/I_Ching_divination#Coins) for more info.
     CoinToss,
 }
+
+impl DivinationMethod {
+    /// Get the number of outcomes (out of 16) that produce each kind of [`Line`] with this method.
+    /// Random lines are generated from these odds, so this is the source of truth for both
+    /// generating lines and calculating probabilities.
+    pub(crate) fn line_weights(&self) -> [(Line, u8); 4] {
+        match self {
+            Self::AncientYarrowStalk => [
+                (Line::broken_changing(), 1),
+                (Line::broken(), 7),
+                (Line::unbroken_changing(), 3),
+                (Line::unbroken(), 5),
+            ],
+            Self::CoinToss => [
+                (Line::broken_changing(), 2),
+                (Line::broken(), 6),
+                (Line::unbroken_changing(), 2),
+                (Line::unbroken(), 6),
+            ],
+        }
+    }
+
+    /// Get the probability of casting each kind of [`Line`] with this method: old yin, young
+    /// yin, old yang and young yang, in that order.
+    pub fn line_probabilities(&self) -> [(Line, Probability); 4] {
+        self.line_weights().map(|(line, weight)| {
+            (
+                line,
+                Probability::new(u64::from(weight), u64::from(LINE_OUTCOMES)),
+            )
+        })
+    }
+
+    /// Get the probability of casting the given [`Line`] with this method.
+    pub fn line_probability(&self, line: Line) -> Probability {
+        self.line_probabilities()
+            .into_iter()
+            .find_map(|(candidate, probability)| (candidate == line).then_some(probability))
+            .expect("every kind of line has a probability")
+    }
+}
@@ -0,0 +1,252 @@
+//! Export of the full text of the I Ching, as written by `export`: either a single Markdown
+//! document or an EPUB 3 book for e-readers.
+//!
+//! Both have a table of contents followed by a chapter for each hexagram, with its symbol, name,
+//! trigrams, judgement, images and the text of each line, from the bottom line up, and the
+//! commentaries that were asked for with `--with`.
+
+mod epub;
+mod zip;
+
+use crate::{
+    config::Settings,
+    hexagram_json::HexagramJsonInfo,
+    locale::{Lang, Messages},
+    render::{
+        self, render_document, HeadingLevel, Link, MarkdownRenderer, Render, Renderer, SectionRole,
+    },
+    view::HexagramView,
+};
+use iching::{
+    hexagram::HexagramOrdering, hexagram_repository::HexagramRepository, trigram::Trigram,
+};
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Can't tell which format to export {} in. Use a file name ending in .md or .epub", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("Failed to export to {}: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("Failed to export to {}: {1}", .0.display())]
+    Render(PathBuf, #[source] render::Error),
+}
+
+/// The formats that the full text can be exported in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Markdown,
+    Epub,
+}
+
+impl ExportFormat {
+    /// Pick the export format by the extension of a file name.
+    pub(crate) fn from_path(path: &Path) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("md" | "markdown") => Ok(ExportFormat::Markdown),
+            Some("epub") => Ok(ExportFormat::Epub),
+            _ => Err(Error::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+/// Export the full text to `path`, in the format picked by [`ExportFormat::from_path`], with the
+/// hexagrams in the given `ordering`, in the language and with the texts and commentaries of
+/// `settings`.
+pub(crate) fn export(
+    path: &Path,
+    ordering: HexagramOrdering,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Error> {
+    let format = ExportFormat::from_path(path)?;
+    let chapters: Vec<Chapter> = ordering
+        .hexagrams()
+        .map(|hexagram| Chapter::new(hexagrams.get_info_for_hexagram(&hexagram), settings))
+        .collect();
+
+    match format {
+        ExportFormat::Markdown => {
+            let file = File::create(path).map_err(|err| Error::Io(path.to_owned(), err))?;
+            let mut renderer = MarkdownRenderer::new(BufWriter::new(file));
+            let text = FullText {
+                chapters: &chapters,
+                lang: settings.lang,
+            };
+            render_document(&mut renderer, &text).map_err(|err| Error::Render(path.to_owned(), err))
+        }
+        ExportFormat::Epub => {
+            let book = epub::write_epub(&chapters, settings.lang)
+                .map_err(|err| Error::Render(path.to_owned(), err))?;
+            std::fs::write(path, book).map_err(|err| Error::Io(path.to_owned(), err))
+        }
+    }
+}
+
+/// The chapter of the full text about a single hexagram.
+struct Chapter<'a> {
+    number: usize,
+    view: HexagramView<'a>,
+    above: Trigram,
+    below: Trigram,
+}
+
+impl<'a> Chapter<'a> {
+    fn new(info: &'a HexagramJsonInfo, settings: &Settings) -> Self {
+        Chapter {
+            number: info.number(),
+            view: HexagramView::from(info)
+                .with_lines()
+                .with_line_labels(settings.line_labels)
+                .with_lang(settings.lang)
+                .with_texts(settings.texts)
+                .with_commentary(&settings.with),
+            above: *info.hexagram().above(),
+            below: *info.hexagram().below(),
+        }
+    }
+
+    /// The ID that links to this chapter point to.
+    fn id(&self) -> String {
+        format!("hexagram-{:02}", self.number)
+    }
+
+    /// A link to this chapter, with the given target.
+    fn link(&self, target: String) -> Link {
+        Link {
+            text: self.view.label(),
+            target,
+        }
+    }
+}
+
+/// Describe a trigram of the hexagram, whose position is given by the ID of its message.
+fn describe_trigram(messages: &Messages, position: &str, trigram: &Trigram) -> String {
+    format!(
+        "{}: {} {} ({}), {}",
+        messages.get(position),
+        trigram.symbol(),
+        messages.trigram(trigram, None),
+        trigram.pinyin(),
+        messages.trigram(trigram, Some("image"))
+    )
+}
+
+impl Render for Chapter<'_> {
+    fn title(&self) -> String {
+        self.view.title()
+    }
+
+    fn lang(&self) -> Lang {
+        self.view.lang()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang().messages();
+        renderer.anchor(&self.id())?;
+        self.view.render(renderer)?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-trigrams"),
+        )?;
+        renderer.list(&[
+            describe_trigram(messages, "book-above", &self.above),
+            describe_trigram(messages, "book-below", &self.below),
+        ])
+    }
+}
+
+/// The whole text as a single document.
+struct FullText<'a> {
+    chapters: &'a [Chapter<'a>],
+    lang: Lang,
+}
+
+impl Render for FullText<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().get("book-title")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        renderer.subtitle(&messages.get("book-subtitle"))?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("book-contents"),
+        )?;
+        let contents: Vec<Link> = self
+            .chapters
+            .iter()
+            .map(|chapter| chapter.link(format!("#{}", chapter.id())))
+            .collect();
+        renderer.links(&contents)?;
+
+        for chapter in self.chapters {
+            chapter.render(renderer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+
+    #[test]
+    fn test_format_is_picked_by_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("i-ching.md")).unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("i-ching.EPUB")).unwrap(),
+            ExportFormat::Epub
+        );
+        assert!(ExportFormat::from_path(Path::new("i-ching.pdf")).is_err());
+    }
+
+    #[test]
+    fn test_markdown_links_contents_to_chapters() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let chapters: Vec<Chapter> = HexagramOrdering::KingWen
+            .hexagrams()
+            .map(|hexagram| {
+                Chapter::new(
+                    hexagrams.get_info_for_hexagram(&hexagram),
+                    &Settings::default(),
+                )
+            })
+            .collect();
+
+        let mut markdown = Vec::new();
+        let text = FullText {
+            chapters: &chapters,
+            lang: Lang::En,
+        };
+        render_document(&mut MarkdownRenderer::new(&mut markdown), &text).unwrap();
+        let markdown = String::from_utf8(markdown).unwrap();
+
+        assert!(markdown.starts_with("# The I Ching\n\n*The Book of Changes*\n\n## Contents\n\n"));
+        assert!(markdown.contains("- [䷀ 1 The Creative](#hexagram-01)\n"));
+        assert!(markdown.contains("<a id=\"hexagram-64\"></a>\n\n# Hexagram No. 64  ䷿\n"));
+        let first_line = markdown.find("### Line 1").unwrap();
+        let sixth_line = markdown.find("### Line 6").unwrap();
+        assert!(first_line < sixth_line);
+    }
+}
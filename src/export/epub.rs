@@ -0,0 +1,225 @@
+//! EPUB 3 books, made of an XHTML page for each chapter, a navigation page with the table of
+//! contents and the embedded DejaVu Sans font, for e-readers whose fonts lack the hexagram
+//! symbols.
+
+use super::{zip::ZipWriter, Chapter};
+use crate::{
+    locale::Lang,
+    raster::{FONT, FONT_FAMILY},
+    render::{
+        self,
+        html::{escape, STYLE},
+        render_document, HtmlRenderer,
+    },
+};
+use std::fmt::Write;
+
+const MIMETYPE: &str = "application/epub+zip";
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+const STYLESHEET: &str = "style.css";
+const FONT_FILE: &str = "DejaVuSans.ttf";
+
+/// Write an EPUB book in the given language with the given chapters, in order.
+pub(super) fn write_epub(chapters: &[Chapter], lang: Lang) -> Result<Vec<u8>, render::Error> {
+    let mut zip = ZipWriter::new();
+    // The mimetype must be the first file, so that it can be found at a fixed offset.
+    zip.add("mimetype", MIMETYPE.as_bytes());
+    zip.add("META-INF/container.xml", CONTAINER.as_bytes());
+    zip.add("OEBPS/content.opf", package(chapters, lang).as_bytes());
+    zip.add("OEBPS/nav.xhtml", navigation(chapters, lang).as_bytes());
+    zip.add(&format!("OEBPS/{STYLESHEET}"), stylesheet().as_bytes());
+    zip.add(&format!("OEBPS/{FONT_FILE}"), FONT);
+    for chapter in chapters {
+        let mut page = Vec::new();
+        render_document(&mut HtmlRenderer::xhtml(&mut page, STYLESHEET), chapter)?;
+        zip.add(&format!("OEBPS/{}", chapter_file(chapter)), &page);
+    }
+
+    Ok(zip.finish())
+}
+
+fn chapter_file(chapter: &Chapter) -> String {
+    format!("{}.xhtml", chapter.id())
+}
+
+fn stylesheet() -> String {
+    // The embedded font is only a fallback, for the characters that the e-reader's own fonts lack.
+    format!(
+        "@font-face {{ font-family: \"{FONT_FAMILY}\"; src: url(\"{FONT_FILE}\"); }}\n\
+         {STYLE}\n\
+         body {{ font-family: serif, \"{FONT_FAMILY}\"; }}\n"
+    )
+}
+
+/// The package document, which describes the book and lists its files in reading order.
+fn package(chapters: &[Chapter], lang: Lang) -> String {
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    for chapter in chapters {
+        writeln!(
+            manifest,
+            r#"<item id="{id}" href="{}" media-type="application/xhtml+xml"/>"#,
+            chapter_file(chapter),
+            id = chapter.id()
+        )
+        .expect("writing to a string succeeds");
+        writeln!(spine, r#"<itemref idref="{}"/>"#, chapter.id())
+            .expect("writing to a string succeeds");
+    }
+    let modified = modified(std::env::var("SOURCE_DATE_EPOCH").ok().as_deref());
+    let messages = lang.messages();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{tag}">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:identifier id="book-id">urn:iching:{version}</dc:identifier>
+<dc:title>{title}: {subtitle}</dc:title>
+<dc:language>{tag}</dc:language>
+<meta property="dcterms:modified">{modified}</meta>
+</metadata>
+<manifest>
+<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="style" href="{STYLESHEET}" media-type="text/css"/>
+<item id="font" href="{FONT_FILE}" media-type="font/ttf"/>
+{manifest}</manifest>
+<spine>
+{spine}</spine>
+</package>
+"#,
+        version = env!("CARGO_PKG_VERSION"),
+        tag = lang.tag(),
+        title = escape(&messages.get("book-title")),
+        subtitle = escape(&messages.get("book-subtitle")),
+    )
+}
+
+/// The time that the book was last modified, which EPUB requires. So that the same text always
+/// makes the same book, this is the time given by `SOURCE_DATE_EPOCH` (in seconds since the Unix
+/// epoch), as for reproducible builds, or else the date that [`ZipWriter`] gives every file.
+fn modified(source_date_epoch: Option<&str>) -> String {
+    source_date_epoch
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+        .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| String::from("1980-01-01T00:00:00Z"))
+}
+
+/// The navigation document, which holds the table of contents.
+fn navigation(chapters: &[Chapter], lang: Lang) -> String {
+    let messages = lang.messages();
+    let mut contents = String::new();
+    for chapter in chapters {
+        let link = chapter.link(chapter_file(chapter));
+        writeln!(
+            contents,
+            r#"<li><a href="{}">{}</a></li>"#,
+            escape(&link.target),
+            escape(&link.text)
+        )
+        .expect("writing to a string succeeds");
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" lang="{tag}" xml:lang="{tag}">
+<head>
+<meta charset="utf-8" />
+<title>{title}</title>
+<link rel="stylesheet" type="text/css" href="{STYLESHEET}" />
+</head>
+<body>
+<nav epub:type="toc" id="toc">
+<h1>{contents_title}</h1>
+<ol>
+{contents}</ol>
+</nav>
+</body>
+</html>
+"#,
+        tag = lang.tag(),
+        title = escape(&messages.get("book-title")),
+        contents_title = escape(&messages.get("book-contents")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Settings, hexagram_json::HexagramJson};
+    use iching::{hexagram::HexagramOrdering, hexagram_repository::HexagramRepository};
+
+    /// Find the contents of a file in an archive written by [`ZipWriter`], whose files are
+    /// stored without compression.
+    fn find_file<'a>(archive: &'a [u8], name: &str) -> Option<&'a [u8]> {
+        let mut offset = 0;
+        while archive[offset..].starts_with(b"PK\x03\x04") {
+            let read_u16 =
+                |at: usize| usize::from(u16::from_le_bytes([archive[at], archive[at + 1]]));
+            let size = u32::from_le_bytes(archive[offset + 22..offset + 26].try_into().unwrap());
+            let name_length = read_u16(offset + 26);
+            let extra_length = read_u16(offset + 28);
+            let start = offset + 30 + name_length + extra_length;
+            let end = start + size as usize;
+            if &archive[offset + 30..offset + 30 + name_length] == name.as_bytes() {
+                return Some(&archive[start..end]);
+            }
+            offset = end;
+        }
+
+        None
+    }
+
+    fn chapters(hexagrams: &HexagramJson) -> Vec<Chapter<'_>> {
+        HexagramOrdering::KingWen
+            .hexagrams()
+            .map(|hexagram| {
+                Chapter::new(
+                    hexagrams.get_info_for_hexagram(&hexagram),
+                    &Settings::default(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_epub_has_every_chapter_in_order() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+
+        let book = write_epub(&chapters(&hexagrams), Lang::En).unwrap();
+
+        assert!(book.starts_with(b"PK\x03\x04"));
+        assert_eq!(find_file(&book, "mimetype"), Some(MIMETYPE.as_bytes()));
+        let package = std::str::from_utf8(find_file(&book, "OEBPS/content.opf").unwrap()).unwrap();
+        let first = package.find(r#"<itemref idref="hexagram-01"/>"#).unwrap();
+        let last = package.find(r#"<itemref idref="hexagram-64"/>"#).unwrap();
+        assert!(first < last);
+        let chapter =
+            std::str::from_utf8(find_file(&book, "OEBPS/hexagram-01.xhtml").unwrap()).unwrap();
+        assert!(chapter.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+        assert!(chapter.contains("<h1>Hexagram No. 1  ䷀</h1>"));
+        assert!(!chapter.contains("<br>"));
+        assert!(find_file(&book, "OEBPS/nav.xhtml").is_some());
+    }
+    #[test]
+    fn test_exporting_the_same_text_makes_the_same_book() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+
+        let first = write_epub(&chapters(&hexagrams), Lang::En).unwrap();
+        let second = write_epub(&chapters(&hexagrams), Lang::En).unwrap();
+
+        assert!(first == second);
+        assert_eq!(modified(None), "1980-01-01T00:00:00Z");
+        assert_eq!(modified(Some("not a time")), "1980-01-01T00:00:00Z");
+        assert_eq!(modified(Some("1700000000")), "2023-11-14T22:13:20Z");
+    }
+}
@@ -0,0 +1,110 @@
+//! Just enough of the ZIP format to write EPUB books: files are stored without compression, with
+//! a fixed modification time, so the same files always make the same archive.
+
+/// The modification date of every file, 1980-01-01, which is the earliest date that ZIP can
+/// store. The time is midnight.
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+/// ZIP 1.0 is enough for files that are stored without compression.
+const VERSION: u16 = 10;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Writes a ZIP archive into memory, one file at a time.
+#[derive(Default)]
+pub(super) struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+impl ZipWriter {
+    pub(super) fn new() -> Self {
+        ZipWriter::default()
+    }
+
+    /// Add a file to the archive. Files are stored in the order that they're added.
+    pub(super) fn add(&mut self, name: &str, contents: &[u8]) {
+        let entry = Entry {
+            name: name.to_owned(),
+            crc: crc32fast::hash(contents),
+            size: u32::try_from(contents.len()).expect("files are smaller than 4 GiB"),
+            offset: self.offset(),
+        };
+
+        self.u32(0x0403_4b50);
+        self.u16(VERSION);
+        self.u16(0); // flags
+        self.u16(0); // stored without compression
+        self.u16(DOS_TIME);
+        self.u16(DOS_DATE);
+        self.u32(entry.crc);
+        self.u32(entry.size); // compressed size
+        self.u32(entry.size);
+        self.u16(name_length(name));
+        self.u16(0); // extra field length
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        self.entries.push(entry);
+    }
+
+    /// Write the archive's central directory, which lists its files, and return the archive.
+    pub(super) fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.offset();
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.u32(0x0201_4b50);
+            self.u16(VERSION); // version made by
+            self.u16(VERSION);
+            self.u16(0); // flags
+            self.u16(0); // stored without compression
+            self.u16(DOS_TIME);
+            self.u16(DOS_DATE);
+            self.u32(entry.crc);
+            self.u32(entry.size); // compressed size
+            self.u32(entry.size);
+            self.u16(name_length(&entry.name));
+            self.u16(0); // extra field length
+            self.u16(0); // comment length
+            self.u16(0); // disk number
+            self.u16(0); // internal attributes
+            self.u32(0); // external attributes
+            self.u32(entry.offset);
+            self.data.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = self.offset() - directory_offset;
+        let count = u16::try_from(entries.len()).expect("archives have fewer than 65536 files");
+
+        self.u32(0x0605_4b50);
+        self.u16(0); // disk number
+        self.u16(0); // disk with the central directory
+        self.u16(count);
+        self.u16(count);
+        self.u32(directory_size);
+        self.u32(directory_offset);
+        self.u16(0); // comment length
+
+        self.data
+    }
+
+    fn offset(&self) -> u32 {
+        u32::try_from(self.data.len()).expect("archives are smaller than 4 GiB")
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn name_length(name: &str) -> u16 {
+    u16::try_from(name.len()).expect("file names are shorter than 64 KiB")
+}
@@ -0,0 +1,682 @@
+#!/usr/bin/env python3
+"""Write IChingHanzi.ttf, the font that the Chinese names in exported images are set in.
+
+The font only has the characters of the names of the 64 hexagrams and the 8 trigrams, in both
+traditional and simplified characters, and of the title of the hexagram of the day. Each glyph is
+drawn here as a few straight strokes of even width, on a grid from 0 to 100 with y pointing down,
+which is enough to read the names at the sizes that cards are drawn at. Run it from this
+directory after changing a glyph:
+
+    python3 make_hanzi.py
+
+Pass --preview to also write preview.svg, with every glyph drawn large.
+"""
+
+import math
+import struct
+import sys
+
+FAMILY = "IChing Hanzi"
+UNITS_PER_EM = 1000
+ASCENDER = 880
+DESCENDER = -120
+# The grid from 0 to 100 is drawn at this scale, inside a margin on each side.
+SCALE = 8.6
+LEFT = 70
+TOP = 830
+STROKE_WIDTH = 6.5
+
+
+def strokes(*paths):
+    """Strokes given as strings of x y pairs, e.g. "10 20 90 20"."""
+    return [("path", [float(v) for v in path.split()]) for path in paths]
+
+
+def at(component, x0, y0, x1, y1):
+    """Place a component, drawn on the whole grid, into the box from (x0, y0) to (x1, y1)."""
+    return [("place", component, (x0, y0, x1, y1))]
+
+
+def left_right(left, right, split=45, gap=5):
+    return at(left, 0, 0, split - gap / 2, 100) + at(right, split + gap / 2, 0, 100, 100)
+
+
+def box(x0, y0, x1, y1):
+    """A closed box, as a left stroke, a top and right stroke and a bottom stroke."""
+    return strokes(f"{x0} {y0} {x0} {y1}", f"{x0} {y0} {x1} {y0} {x1} {y1}", f"{x0} {y1} {x1} {y1}")
+
+
+# Components, each drawn on the whole grid.
+KOU = box(12, 15, 88, 85)
+RI = box(15, 5, 85, 95) + strokes("15 50 85 50")
+MU_EYE = box(15, 5, 85, 95) + strokes("15 35 85 35", "15 65 85 65")
+TIAN = box(10, 8, 90, 92) + strokes("10 50 90 50", "50 8 50 92")
+YUE = strokes("20 5 20 70 5 97", "20 5 85 5 85 97 75 92", "20 35 85 35", "20 63 85 63")
+MU = strokes("5 30 95 30", "50 3 50 97", "48 32 5 82", "52 32 95 82")
+SHUI = strokes("28 8 62 24", "18 40 52 56", "12 95 55 62")
+NV = strokes("45 5 22 55 88 90", "72 28 55 70 10 97", "5 48 95 48")
+REN = strokes("50 5 45 50 5 95", "48 45 95 95")
+YAN = strokes("45 2 60 12", "8 20 92 20", "24 36 76 36", "24 52 76 52") + box(24, 66, 76, 95)
+YAN_SIMPLIFIED = strokes("30 5 55 22", "8 42 40 42 40 88 62 72")
+TU_LEFT = strokes("10 42 82 42", "45 10 45 85", "5 90 92 78")
+SHOU_LEFT = strokes("5 32 90 32", "45 5 45 92 30 84", "5 72 92 52")
+XIN_LEFT = strokes("50 3 50 97", "20 30 12 58", "78 28 90 46")
+FU_LEFT = strokes("20 5 20 97", "20 5 75 5 45 38 80 55 75 75 48 78")
+CHUO = strokes("12 5 26 20", "4 36 28 36 10 64 24 72", "24 72 40 90 100 94")
+CAO = strokes("5 45 95 45", "30 8 30 85", "70 8 70 85")
+MIAN = strokes("48 2 56 22", "8 38 8 62", "8 38 92 38 88 62")
+MI = strokes("8 30 8 62", "8 30 92 30 88 62")
+ZHU = strokes(
+    "30 5 8 42", "20 22 46 22", "32 22 42 42", "76 5 56 42", "66 22 96 22", "80 22 90 42"
+)
+BEI = (
+    box(15, 5, 85, 72)
+    + strokes("15 27 85 27", "15 50 85 50", "36 80 6 97", "64 80 94 97")
+)
+BEI_SIMPLIFIED = strokes(
+    "15 8 15 75", "15 8 85 8 85 75 75 70", "50 28 50 60 12 97", "56 76 94 97"
+)
+YE = (
+    strokes("5 6 95 6", "50 6 40 20")
+    + box(18, 20, 82, 76)
+    + strokes("18 39 82 39", "18 58 82 58", "38 84 8 97", "62 84 94 97")
+)
+YE_SIMPLIFIED = strokes(
+    "5 6 95 6", "50 6 40 22", "18 22 18 74", "18 22 82 22 82 74 72 68", "50 38 50 62 12 97",
+    "56 78 94 97",
+)
+JIAN = box(15, 5, 85, 62) + strokes(
+    "15 24 85 24", "15 43 85 43", "38 62 32 82 6 97", "62 62 62 90 70 95 96 95 96 84"
+)
+JIAN_SIMPLIFIED = strokes(
+    "15 5 15 62", "15 5 85 5 85 62 76 56", "42 24 38 72 8 97", "62 30 62 90 70 95 96 95 96 84"
+)
+YOU = strokes("10 12 82 12 52 60 10 96", "32 36 92 96")
+MIN = box(15, 8, 85, 90) + strokes("38 8 38 90", "62 8 62 90", "5 92 95 92")
+CHONG = box(22, 12, 78, 56) + strokes("50 0 50 80", "10 88 90 78", "70 68 82 86")
+ZHUI = strokes(
+    "40 3 12 42", "26 28 26 97", "56 5 64 18", "26 24 95 24", "60 24 60 90", "26 46 90 46",
+    "26 68 90 68", "26 91 98 91",
+)
+JIN = strokes("90 8 62 20", "62 20 62 62 52 97", "62 46 98 46", "82 46 82 97")
+CHE = strokes("5 18 58 18", "32 4 10 56 56 56", "32 36 32 97", "5 78 60 78")
+CHE_TRADITIONAL = (
+    strokes("5 10 57 10")
+    + box(12, 24, 50, 64)
+    + strokes("12 44 50 44", "5 80 57 80", "31 2 31 97")
+)
+ER_REN = strokes("30 5 6 30", "34 30 8 58", "22 46 22 97")
+ZI = strokes("18 8 78 8 50 32", "50 30 50 95 40 88", "5 55 95 55")
+JIE = strokes("15 10 75 10 75 62 62 56", "15 10 15 97")
+GONG = strokes("40 8 8 46", "60 8 94 46", "46 50 26 88 88 80", "74 66 90 90")
+JIAN_ALL = strokes(
+    "34 3 42 13", "66 3 58 13", "8 19 92 19", "18 33 88 33 88 63", "12 48 94 48", "16 63 88 63",
+    "38 19 38 76 12 97", "62 19 62 97", "66 74 94 97",
+)
+BAI = strokes("26 3 18 16") + box(10, 18, 44, 92) + strokes("10 55 44 55")
+CUN = strokes("5 32 95 32", "66 5 66 90 54 84", "28 50 40 68")
+YU = strokes("34 3 20 22", "28 12 80 12", "44 28 24 42", "70 26 86 42")
+SHUI_BOTTOM = strokes("50 5 50 97 40 90", "12 20 34 40", "88 12 64 40", "62 50 92 94", "38 50 8 94")
+SHUI_SIDE = strokes("50 5 50 96 42 90", "10 28 36 50", "86 20 60 48", "60 52 90 94", "36 54 8 90")
+ZHI = strokes("45 12 45 88", "45 46 82 46", "18 40 18 88", "5 92 95 92")
+SI = strokes("55 5 12 82 90 74", "68 52 88 92")
+PI = strokes("28 5 28 92 48 80", "28 46 48 36")
+WEI = strokes("20 22 80 22", "5 46 95 46", "50 5 50 97", "48 48 6 90", "52 48 94 90")
+CHEN = strokes("8 8 44 8", "14 8 14 92 44 92", "14 36 34 36", "14 64 34 64", "34 8 34 92")
+HOU = strokes("86 5 28 15", "28 15 28 62 8 97", "28 38 96 38") + box(42, 56, 90, 92)
+FANG = strokes("40 3 48 14", "5 22 90 22", "40 22 36 62 8 95", "38 42 80 42 80 86 66 80")
+BAI_BI = strokes("26 3 20 12") + box(12, 14, 42, 56) + strokes(
+    "12 35 42 35", "12 56 12 95 48 78", "32 70 46 95"
+)
+DOU = (
+    strokes("10 6 90 6")
+    + box(24, 20, 76, 50)
+    + strokes("34 60 42 78", "66 60 58 78", "5 90 95 90")
+)
+YU_RAIN = strokes(
+    "10 6 90 6", "50 6 50 40", "14 16 14 42", "14 16 86 16 86 42", "28 24 38 30", "28 34 38 40",
+    "62 24 72 30", "62 34 72 40",
+)
+SHI_PIG = strokes(
+    "10 8 90 8", "55 8 15 40", "40 26 62 46 62 92 50 86", "56 44 15 70", "60 62 12 94",
+    "66 50 92 34", "66 60 95 94",
+)
+GUI_TOP = strokes("26 3 18 14", "14 14 14 92", "14 14 36 14 36 48 14 48", "14 52 40 52 40 90 14 90")
+ZHOU = strokes(
+    "10 8 88 8 88 30", "14 19 88 19", "6 30 94 30", "50 2 50 97", "18 45 18 72",
+    "18 45 84 45 84 70 76 66",
+)
+XUAN = strokes("48 2 54 12", "8 16 92 16", "56 18 34 36 64 38 28 56 76 54", "68 42 82 56")
+SHEN = box(10, 20, 90, 75) + strokes("10 47 90 47", "50 3 50 97")
+
+
+def glyphs():
+    """Every glyph, by its character."""
+    qian_left = strokes("8 14 44 14", "26 3 26 24") + at(RI, 10, 26, 42, 58) + strokes(
+        "5 68 47 68", "26 58 26 97"
+    )
+    qi = strokes("64 4 50 26", "56 17 94 17", "58 32 88 32", "60 46 86 46 62 74 62 92 96 92 96 80")
+    lin_right_top = strokes("60 3 48 26", "54 16 96 16", "68 22 78 34")
+    ear_lin = strokes("12 24 12 74", "28 5 28 97")
+    pin = at(KOU, 54, 36, 90, 62) + at(KOU, 40, 66, 70, 96) + at(KOU, 72, 66, 100, 96)
+    dui_rest = at(KOU, 18, 24, 82, 56) + strokes("40 56 36 80 8 97", "60 56 60 90 66 95 94 95 94 84")
+    bo_top = strokes("10 8 52 8 52 40", "14 24 52 24", "5 40 60 40")
+    knife = strokes("72 18 72 62", "90 4 90 92 80 86")
+    lu = at(bo_top + at(SHUI_SIDE, 0, 40, 62, 100), 0, 0, 62, 100)
+    lu_traditional = at(
+        strokes("14 6 10 24", "10 8 52 8 52 40", "14 24 52 24", "5 40 60 40")
+        + at(SHUI_SIDE, 0, 40, 62, 100),
+        0, 0, 62, 100,
+    )
+    zhuang_left_simplified = strokes("36 8 36 97", "10 24 22 40", "5 72 34 56")
+    zhuang_left = strokes("34 5 34 97", "8 22 16 38", "8 50 34 50", "5 82 34 66")
+    shi = strokes("44 35 96 35", "70 10 70 86", "50 86 92 86")
+    fu_bottom = strokes("48 52 28 76", "44 63 80 63 30 97", "48 76 94 97")
+    fu = strokes("34 3 20 22", "28 13 86 13") + at(RI, 28, 22, 78, 50) + fu_bottom
+    shi_traditional_left = GUI_TOP
+    shi_right = strokes("46 12 96 12", "71 3 71 97", "52 30 52 76", "52 30 90 30 90 72 82 66")
+    heng = strokes("44 10 96 10") + box(52, 26, 88, 74) + strokes("52 50 88 50", "44 90 96 90")
+    heng_traditional = strokes("44 10 96 10") + box(52, 26, 88, 74) + strokes(
+        "52 26 88 74", "44 90 96 90"
+    )
+    yuan = at(KOU, 50, 0, 90, 30) + at(BEI_SIMPLIFIED, 40, 34, 100, 100)
+    yuan_traditional = at(KOU, 50, 0, 90, 26) + at(BEI, 40, 30, 100, 100)
+    jin_top = at(SI, 14, 14, 46, 44) + at(SI, 54, 14, 86, 44)
+    ya = strokes("12 10 88 10", "38 10 38 48", "62 10 62 48", "22 24 30 42", "78 24 70 42")
+    xiu = at(RI, 5, 20, 35, 80) + at(YUE, 42, 5, 97, 100)
+    jian_simplified_right = left_right(CHE, JIN, split=55)
+    jian_right = left_right(CHE_TRADITIONAL, JIN, split=55)
+    huan_right = strokes(
+        "60 3 48 22", "55 12 84 12 76 24", "48 24 48 52", "48 24 92 24 92 52", "64 34 60 50",
+        "76 34 80 50", "38 62 98 62", "70 52 68 72 40 97", "72 72 98 97",
+    )
+    huan_right_traditional = strokes(
+        "60 3 48 22", "55 12 84 12 76 24", "48 24 48 52", "48 24 92 24 92 52", "62 30 62 52",
+        "78 30 78 52", "56 40 84 40", "38 62 98 62", "70 52 68 72 40 97", "72 72 98 97",
+    )
+    qi_simplified = strokes(
+        "70 3 72 12", "40 18 98 18", "52 22 86 48", "86 22 44 52", "52 58 44 97", "86 58 86 97"
+    )
+    qi_traditional = strokes(
+        "70 2 70 10", "40 14 98 14", "54 20 70 36 86 20", "56 40 46 56", "70 36 70 56",
+        "84 40 94 56", "44 62 96 62", "52 62 44 97", "88 62 88 97", "60 76 80 76", "60 88 80 88",
+    )
+    li_simplified = strokes(
+        "50 2 52 10", "8 15 92 15", "30 22 30 44 70 44 70 22", "42 24 58 40", "58 24 42 40",
+        "14 52 14 97", "14 52 86 52 86 97 78 92", "50 52 50 66 32 84 70 80", "64 72 72 84",
+    )
+    xiang = strokes(
+        "68 3 55 18", "62 12 86 12 78 22", "54 22 54 42", "54 22 92 22 92 42", "54 42 92 42",
+    ) + at(SHI_PIG, 48, 46, 100, 100)
+    guai_top = strokes("18 22 78 22 78 46", "8 46 92 46", "50 5 50 50 12 95", "55 55 92 95")
+    sheng = strokes("60 6 22 22", "8 45 92 45", "38 18 38 62 10 97", "68 10 68 97")
+    tong = strokes("12 10 12 97", "12 10 88 10 88 95 78 90", "30 28 70 28") + box(30, 46, 70, 78)
+    fou = strokes("8 10 92 10", "56 10 14 50", "46 26 46 58", "62 32 86 48") + box(26, 62, 74, 95)
+    xian = strokes(
+        "18 22 94 22", "18 22 18 70 5 95", "70 5 72 50 96 94 98 78", "82 8 92 14", "96 50 70 88",
+        "30 38 56 38",
+    ) + box(30, 50, 56, 74)
+    he_right = strokes(
+        "10 15 90 15", "5 33 95 33", "50 3 50 33", "44 38 24 56 82 52", "74 44 86 58"
+    ) + at(MIN, 0, 60, 100, 100)
+    shi_right_bite = at(ZHU, 0, 0, 100, 30) + strokes("8 40 92 40", "2 96 98 96", "50 40 50 96") + at(
+        REN, 12, 48, 44, 86
+    ) + at(REN, 56, 48, 88, 86)
+    kun_trap = box(8, 5, 92, 97) + at(MU, 22, 16, 78, 88)
+    qian_kan = strokes("60 5 46 32", "52 22 92 22 84 40", "70 30 68 60 38 97", "70 60 96 97")
+    wu = strokes("15 15 85 15", "5 42 95 42", "50 15 48 55 12 97", "52 45 55 88 62 95 92 95 92 82")
+    ji_right = strokes("8 12 92 12", "4 40 96 40", "56 12 54 52 8 97", "56 52 58 90 66 95 98 95 98 84")
+    you = strokes("8 28 92 28", "56 5 10 97") + at(YUE, 34, 42, 96, 100)
+    bi = at(PI, 0, 0, 50, 100) + strokes(
+        "62 5 62 88 70 95 96 95 96 82", "62 46 92 30"
+    )
+    tai = strokes(
+        "18 15 82 15", "22 30 78 30", "6 45 94 45", "50 3 50 45 12 76", "52 48 94 76",
+    ) + at(SHUI_BOTTOM, 20, 58, 80, 100)
+    wu_none = strokes(
+        "32 3 12 26", "22 15 90 15", "8 34 92 34", "8 58 92 58", "26 15 26 58", "42 15 42 58",
+        "58 15 58 58", "74 15 74 58", "16 74 8 92", "38 76 34 92", "62 76 66 92", "84 74 92 92",
+    )
+    chu = at(XUAN, 0, 0, 100, 54) + at(TIAN, 16, 56, 84, 100)
+    yi = strokes(
+        "32 3 40 15", "68 3 60 15", "8 22 92 22", "40 26 18 46", "60 26 82 46"
+    ) + at(MIN, 0, 48, 100, 100)
+    kui_right = strokes(
+        "48 6 38 24 54 28", "60 8 66 16", "70 5 78 10", "66 18 96 30", "46 42 92 42",
+        "40 58 96 58", "70 42 68 66 40 97", "70 68 96 97",
+    )
+    jie_traditional_bottom = at(BAI_BI, 0, 0, 55, 100) + at(JIE, 60, 0, 100, 100)
+    gen = strokes("18 8 18 88 50 74", "18 8 80 8 80 50 18 50", "18 29 80 29", "46 56 94 92",
+                  "90 54 70 68")
+    cui = strokes(
+        "50 2 52 10", "10 14 90 14", "32 20 20 38", "34 26 44 38", "70 20 58 38", "70 26 80 38",
+        "8 56 92 56", "50 42 50 97",
+    )
+    meng = at(MI, 0, 0, 100, 20) + strokes("24 26 76 26") + at(SHI_PIG, 0, 32, 100, 100)
+    gu_simplified = at(CHONG, 15, 0, 85, 58) + at(MIN, 0, 60, 100, 100)
+    gu = at(CHONG, 25, 0, 75, 32) + at(CHONG, 0, 30, 50, 62) + at(CHONG, 50, 30, 100, 62) + at(
+        MIN, 0, 64, 100, 100
+    )
+    guan = at(CAO, 0, 0, 100, 20) + at(KOU, 5, 18, 45, 40) + at(KOU, 55, 18, 95, 40) + at(
+        ZHUI, 0, 42, 100, 100
+    )
+    jiao = strokes(
+        "34 3 14 24", "26 12 50 12 42 24", "12 28 12 80 3 97", "12 28 48 28 48 97 40 92",
+        "12 48 48 48", "12 68 48 68", "30 28 30 68",
+    )
+    jie_right = strokes("56 10 94 10 94 40 86 36", "76 10 70 30 55 44", "66 48 58 62", "60 60 96 60",
+                        "55 78 98 78", "77 46 77 100")
+    feng = strokes("10 20 10 46", "10 20 90 20 90 46", "10 46 90 46", "35 3 35 46", "65 3 65 46",
+                   "18 28 28 38", "72 28 82 38") + at(DOU, 0, 50, 100, 100)
+    yu = strokes("8 10 48 10 30 28", "30 28 30 92 22 86", "5 46 50 42")
+    bi_top = strokes("15 10 85 10", "50 3 50 22", "8 25 92 25", "30 18 30 35", "70 18 70 35")
+    jian_lame = at(MIAN, 0, 0, 100, 24) + strokes(
+        "25 28 25 50", "75 28 75 50", "10 32 90 32", "8 44 92 44", "38 50 22 58", "62 50 78 58",
+    ) + box(32, 60, 68, 74) + strokes("50 74 50 94", "50 84 74 84", "30 80 20 97 96 97")
+    dun = strokes("90 5 25 12", "25 12 25 60 10 95", "25 25 92 25", "58 12 58 30") + at(
+        MU_EYE, 36, 32, 92, 97
+    )
+    guo = at(KOU, 25, 0, 75, 32) + strokes("12 38 12 97", "12 38 88 38 88 95 80 90") + box(
+        34, 54, 66, 80
+    )
+    tun_pig = at(YUE, 0, 0, 42, 100) + at(SHI_PIG, 42, 0, 100, 100)
+    sui_simplified = at(you, 0, 0, 100, 100)
+    sui = strokes("16 15 84 15", "42 3 12 42", "34 30 34 48", "34 30 80 30") + at(
+        YUE, 40, 42, 96, 100
+    ) + strokes("22 48 22 97")
+    er = strokes(
+        "8 10 92 10", "50 10 42 22", "15 24 15 97", "15 24 85 24 85 97 76 92", "40 24 40 92",
+        "60 24 60 92",
+    )
+    chen = strokes(
+        "16 10 92 10", "16 10 16 70 5 97", "30 28 82 28", "26 46 86 46", "42 46 42 97",
+        "56 54 70 66", "62 50 96 97", "90 56 74 72",
+    )
+    ge = strokes("30 3 30 22", "70 3 70 22", "10 12 90 12") + box(24, 26, 76, 54) + strokes(
+        "5 72 95 72", "50 26 50 97"
+    )
+    yi_left = strokes("8 8 42 8", "8 8 8 94 42 94", "22 30 22 72", "22 30 40 30 40 72", "22 51 40 51",
+                      "22 72 40 72")
+    ding = box(24, 5, 76, 46) + strokes(
+        "24 19 76 19", "24 32 76 32", "30 50 30 97", "10 62 30 62", "5 82 30 72", "70 50 70 97",
+        "70 62 94 62", "70 76 96 88",
+    )
+    de_right = strokes("66 5 54 30", "62 20 92 20 92 88 80 82", "64 44 76 60")
+    mei = strokes("32 3 12 28", "24 15 90 15", "22 30 14 86", "22 30 84 30 78 94 66 88",
+                  "5 58 95 58", "48 38 56 50", "46 68 54 80")
+    gua = strokes(
+        "8 16 52 16", "30 3 30 44", "5 44 55 44", "10 62 50 62", "30 50 30 92", "3 92 56 92",
+        "76 5 76 97", "78 40 96 52",
+    )
+    zheng_li = lambda inner: at(CHUO, 0, 0, 100, 100) + at(inner, 34, 2, 98, 78)
+    sui_ear = lambda inner: at(FU_LEFT, 0, 0, 26, 100) + at(CHUO, 26, 0, 100, 100) + at(
+        inner, 52, 2, 98, 74
+    )
+
+    return {
+        "中": box(20, 25, 80, 65) + strokes("50 5 50 97"),
+        "丰": strokes("22 20 78 20", "28 45 72 45", "12 72 88 72", "50 5 50 97"),
+        "临": at(ear_lin, 0, 0, 36, 100) + lin_right_top + strokes(
+            "45 48 45 92", "45 48 92 48 92 92", "60 48 60 92", "76 48 76 92", "45 92 92 92"
+        ),
+        "臨": at(CHEN, 0, 0, 46, 100) + lin_right_top + pin,
+        "乾": at(qian_left, 0, 0, 50, 100) + qi,
+        "井": strokes("15 32 85 32", "8 65 92 65", "35 8 35 70 16 97", "65 8 65 97"),
+        "人": REN,
+        "兌": strokes("36 5 22 20", "64 5 78 20") + dui_rest,
+        "兑": strokes("32 4 40 18", "68 4 60 18") + dui_rest,
+        "剝": lu_traditional + knife,
+        "剥": lu + knife,
+        "升": sheng,
+        "同": tong,
+        "否": fou,
+        "咸": xian,
+        "嗑": at(KOU, 0, 28, 30, 62) + at(he_right, 36, 0, 100, 100),
+        "噬": at(KOU, 0, 28, 30, 62) + at(shi_right_bite, 36, 0, 100, 100),
+        "困": kun_trap,
+        "坎": left_right(TU_LEFT, qian_kan, split=36),
+        "坤": left_right(TU_LEFT, SHEN, split=36),
+        "壮": zhuang_left_simplified + shi,
+        "壯": zhuang_left + shi,
+        "复": fu,
+        "復": left_right(ER_REN, fu, split=32),
+        "大": strokes("8 35 92 35", "50 5 50 45 10 95", "52 48 92 95"),
+        "夬": guai_top,
+        "夷": strokes(
+            "14 14 86 14", "20 30 76 30 76 48 24 48 24 66 82 66 78 80", "50 3 50 60 12 97",
+            "52 62 94 97",
+        ),
+        "妄": strokes("48 2 54 14", "10 20 90 20", "28 20 28 44 86 44") + at(NV, 8, 48, 92, 100),
+        "妹": left_right(NV, WEI, split=40),
+        "姤": left_right(NV, HOU, split=40),
+        "孚": at(YU, 0, 0, 100, 40) + at(ZI, 0, 44, 100, 100),
+        "家": at(MIAN, 0, 0, 100, 30) + at(SHI_PIG, 0, 32, 100, 100),
+        "小": strokes("50 5 50 92 40 86", "28 35 12 70", "72 35 88 65"),
+        "履": strokes("16 8 88 8 88 28 16 28", "16 8 16 65 5 97") + at(ER_REN, 26, 32, 46, 100) + at(
+            fu, 46, 32, 100, 100
+        ),
+        "屯": strokes("10 22 90 22", "26 38 26 64 74 64 74 38", "50 5 50 88 58 95 94 95 94 82"),
+        "巽": strokes(
+            "10 6 42 6 42 22 12 22", "12 6 12 40 46 40 46 34",
+            "56 6 88 6 88 22 58 22", "58 6 58 40 92 40 92 34",
+            "30 48 30 76", "70 48 70 76", "10 60 90 60", "5 76 95 76", "36 82 20 97", "64 82 80 97",
+        ),
+        "师": strokes("18 18 18 60", "34 5 34 70 14 95") + at(shi_right, 0, 0, 100, 100),
+        "師": at(shi_traditional_left, 0, 0, 44, 100) + shi_right,
+        "归": strokes("18 18 18 56", "36 5 36 70 14 95", "52 15 90 15 90 88", "56 50 90 50",
+                     "50 88 92 88"),
+        "歸": at(GUI_TOP, 0, 0, 44, 56) + at(ZHI, 0, 56, 44, 100) + at(ZHOU, 48, 0, 100, 100),
+        "恆": at(XIN_LEFT, 0, 0, 36, 100) + heng_traditional,
+        "恒": at(XIN_LEFT, 0, 0, 36, 100) + heng,
+        "损": at(SHOU_LEFT, 0, 0, 36, 100) + yuan,
+        "損": at(SHOU_LEFT, 0, 0, 36, 100) + yuan_traditional,
+        "旅": at(FANG, 0, 0, 46, 100) + strokes(
+            "62 3 52 22", "57 15 96 15", "56 34 56 95 72 84", "72 40 96 30", "72 40 72 60 96 97",
+            "88 50 58 78",
+        ),
+        "无": wu,
+        "既": at(BAI_BI, 0, 0, 46, 100) + at(ji_right, 50, 0, 100, 100),
+        "明": xiu,
+        "晉": strokes("10 8 90 8") + jin_top + strokes("5 50 95 50") + at(RI, 24, 56, 76, 98),
+        "晋": ya + strokes("5 50 95 50") + at(RI, 24, 56, 76, 98),
+        "有": you,
+        "未": WEI,
+        "比": bi,
+        "泰": tai,
+        "济": at(SHUI, 0, 0, 30, 100) + qi_simplified,
+        "濟": at(SHUI, 0, 0, 30, 100) + qi_traditional,
+        "涣": at(SHUI, 0, 0, 30, 100) + huan_right,
+        "渙": at(SHUI, 0, 0, 30, 100) + huan_right_traditional,
+        "渐": left_right(SHUI, jian_simplified_right, split=30),
+        "漸": left_right(SHUI, jian_right, split=30),
+        "無": wu_none,
+        "畜": chu,
+        "益": yi,
+        "睽": at(MU_EYE, 0, 15, 30, 85) + at(kui_right, 30, 0, 100, 100),
+        "离": li_simplified,
+        "離": at(li_simplified, 0, 0, 50, 100) + at(ZHUI, 50, 0, 100, 100),
+        "節": at(ZHU, 0, 0, 100, 30) + at(jie_traditional_bottom, 5, 32, 95, 100),
+        "节": at(CAO, 0, 0, 100, 30) + at(JIE, 22, 36, 80, 100),
+        "艮": gen,
+        "萃": at(CAO, 0, 0, 100, 24) + at(cui, 0, 24, 100, 100),
+        "蒙": at(CAO, 0, 0, 100, 22) + at(meng, 0, 22, 100, 100),
+        "蛊": gu_simplified,
+        "蠱": gu,
+        "觀": left_right(guan, JIAN, split=55),
+        "观": left_right(YOU, JIAN_SIMPLIFIED, split=46),
+        "解": at(jiao, 0, 0, 52, 100) + jie_right,
+        "訟": left_right(YAN, GONG, split=40),
+        "讼": left_right(YAN_SIMPLIFIED, GONG, split=32),
+        "謙": left_right(YAN, JIAN_ALL, split=40),
+        "谦": left_right(YAN_SIMPLIFIED, JIAN_ALL, split=32),
+        "豐": feng,
+        "豫": at(yu, 0, 0, 100, 100) + xiang,
+        "賁": at(bi_top, 0, 0, 100, 36) + at(BEI, 10, 38, 90, 100),
+        "贲": at(bi_top, 0, 0, 100, 36) + at(BEI_SIMPLIFIED, 10, 38, 90, 100),
+        "蹇": jian_lame,
+        "过": zheng_li(CUN),
+        "遁": zheng_li(dun),
+        "過": zheng_li(guo),
+        "遯": zheng_li(tun_pig),
+        "随": sui_ear(sui_simplified),
+        "隨": sui_ear(sui),
+        "需": at(YU_RAIN, 0, 0, 100, 46) + at(er, 0, 48, 100, 100),
+        "震": at(YU_RAIN, 0, 0, 100, 46) + at(chen, 0, 48, 100, 100),
+        "革": ge,
+        "頤": at(yi_left, 0, 0, 44, 100) + at(YE, 48, 0, 100, 100),
+        "颐": at(yi_left, 0, 0, 44, 100) + at(YE_SIMPLIFIED, 48, 0, 100, 100),
+        "鼎": ding,
+        "的": at(BAI, 0, 0, 46, 100) + de_right,
+        "每": mei,
+        "日": at(RI, 22, 5, 78, 97),
+        "一": strokes("5 50 95 50"),
+        "卦": gua,
+    }
+
+
+def flatten(items, transform=lambda x, y: (x, y), scale=1.0):
+    """Flatten placed components into strokes, as lists of points on the whole grid, with the
+    scale that each was drawn at."""
+    flat = []
+    for item in items:
+        if item[0] == "path":
+            values = item[1]
+            points = [transform(values[i], values[i + 1]) for i in range(0, len(values), 2)]
+            flat.append((points, scale))
+        else:
+            _, component, (x0, y0, x1, y1) = item
+            sx, sy = (x1 - x0) / 100, (y1 - y0) / 100
+
+            def inner(x, y, x0=x0, y0=y0, sx=sx, sy=sy):
+                return transform(x0 + x * sx, y0 + y * sy)
+
+            flat += flatten(component, inner, scale * math.sqrt(sx * sy))
+    return flat
+
+
+def to_font(x, y):
+    return LEFT + x * SCALE, TOP - y * SCALE
+
+
+def outline(points, scale):
+    """The contours of a stroke: a rectangle with square ends around each of its segments, all
+    drawn clockwise so that they add up under the nonzero rule."""
+    half = STROKE_WIDTH * (0.55 + 0.45 * min(scale, 1.0)) * SCALE / 2
+    contours = []
+    font_points = [to_font(x, y) for x, y in points]
+    for (ax, ay), (bx, by) in zip(font_points, font_points[1:]):
+        length = math.hypot(bx - ax, by - ay)
+        if length == 0:
+            continue
+        dx, dy = (bx - ax) / length * half, (by - ay) / length * half
+        nx, ny = -dy, dx
+        corners = [
+            (ax - dx + nx, ay - dy + ny),
+            (bx + dx + nx, by + dy + ny),
+            (bx + dx - nx, by + dy - ny),
+            (ax - dx - nx, ay - dy - ny),
+        ]
+        corners = [(round(x), round(y)) for x, y in corners]
+        area = sum(
+            x0 * y1 - x1 * y0
+            for (x0, y0), (x1, y1) in zip(corners, corners[1:] + corners[:1])
+        )
+        if area > 0:
+            corners.reverse()
+        contours.append(corners)
+    return contours
+
+
+def glyph_data(contours):
+    if not contours:
+        return b"", (0, 0, 0, 0)
+    xs = [x for contour in contours for x, _ in contour]
+    ys = [y for contour in contours for _, y in contour]
+    bounds = (min(xs), min(ys), max(xs), max(ys))
+    data = struct.pack(">hhhhh", len(contours), *bounds)
+    end = -1
+    for contour in contours:
+        end += len(contour)
+        data += struct.pack(">H", end)
+    data += struct.pack(">H", 0)
+    points = [point for contour in contours for point in contour]
+    data += bytes([1]) * len(points)
+    previous = 0
+    for x, _ in points:
+        data += struct.pack(">h", x - previous)
+        previous = x
+    previous = 0
+    for _, y in points:
+        data += struct.pack(">h", y - previous)
+        previous = y
+    if len(data) % 2:
+        data += b"\0"
+    return data, bounds
+
+
+def cmap_table(mapping):
+    """A format 4 cmap, with a segment for each character."""
+    codes = sorted(mapping) + [0xFFFF]
+    segments = len(codes)
+    ends = struct.pack(f">{segments}H", *codes)
+    starts = ends
+    deltas = struct.pack(
+        f">{segments}h",
+        *[((mapping[code] - code + 0x8000) % 0x10000) - 0x8000 for code in codes[:-1]], 1
+    )
+    offsets = struct.pack(f">{segments}H", *[0] * segments)
+    search = 2 ** int(math.log2(segments))
+    subtable = struct.pack(
+        ">HHHHHHH", 4, 16 + segments * 8, 0, segments * 2, search * 2,
+        int(math.log2(search)), segments * 2 - search * 2,
+    ) + ends + struct.pack(">H", 0) + starts + deltas + offsets
+    return struct.pack(">HHHHI", 0, 1, 3, 1, 12) + subtable
+
+
+def name_table():
+    names = {
+        0: "Glyphs drawn for the iching app. Licensed under the same terms as the app.",
+        1: FAMILY,
+        2: "Regular",
+        3: FAMILY,
+        4: FAMILY,
+        5: "Version 1.0",
+        6: FAMILY.replace(" ", ""),
+    }
+    records = b""
+    strings = b""
+    for name_id, text in names.items():
+        encoded = text.encode("utf-16-be")
+        records += struct.pack(">HHHHHH", 3, 1, 0x409, name_id, len(encoded), len(strings))
+        strings += encoded
+    return struct.pack(">HHH", 0, len(names), 6 + 12 * len(names)) + records + strings
+
+
+def checksum(data):
+    data += b"\0" * (-len(data) % 4)
+    return sum(struct.unpack(f">{len(data) // 4}I", data)) & 0xFFFFFFFF
+
+
+def build():
+    characters = glyphs()
+    # Glyph 0 is the .notdef glyph, an empty box.
+    notdef = [
+        [(100, -100), (100, 800), (700, 800), (700, -100)],
+        [(160, -40), (640, -40), (640, 740), (160, 740)],
+    ]
+    outlines = [notdef]
+    mapping = {}
+    for character in sorted(characters):
+        mapping[ord(character)] = len(outlines)
+        contours = []
+        for points, scale in flatten(characters[character]):
+            contours += outline(points, scale)
+        outlines.append(contours)
+
+    glyf = b""
+    loca = []
+    all_bounds = []
+    max_points = max_contours = 0
+    for contours in outlines:
+        loca.append(len(glyf))
+        data, bounds = glyph_data(contours)
+        glyf += data
+        if contours:
+            all_bounds.append(bounds)
+        max_points = max(max_points, sum(len(contour) for contour in contours))
+        max_contours = max(max_contours, len(contours))
+    loca.append(len(glyf))
+    count = len(outlines)
+    x_min = min(b[0] for b in all_bounds)
+    y_min = min(b[1] for b in all_bounds)
+    x_max = max(b[2] for b in all_bounds)
+    y_max = max(b[3] for b in all_bounds)
+
+    tables = {
+        b"cmap": cmap_table(mapping),
+        b"glyf": glyf,
+        b"head": struct.pack(
+            ">IIIIHHqqhhhhHHhhh", 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0b1011, UNITS_PER_EM,
+            0, 0, x_min, y_min, x_max, y_max, 0, 8, 2, 1, 0,
+        ),
+        b"hhea": struct.pack(
+            ">IhhhHhhhhhhhhhhhH", 0x00010000, ASCENDER, DESCENDER, 0, UNITS_PER_EM, 0, x_min,
+            UNITS_PER_EM - x_max, x_max, 1, 0, 0, 0, 0, 0, 0, count,
+        ),
+        b"hmtx": b"".join(
+            struct.pack(">Hh", UNITS_PER_EM, glyph_data(contours)[1][0]) for contours in outlines
+        ),
+        b"loca": struct.pack(f">{len(loca)}I", *loca),
+        b"maxp": struct.pack(
+            ">IHHHHHHHHHHHHHH", 0x00010000, count, max_points, max_contours, 0, 0, 2, 0, 0, 0, 0,
+            0, 0, 0, 0,
+        ),
+        b"name": name_table(),
+        b"OS/2": struct.pack(
+            ">HhHHHhhhhhhhhhhh10sIIII4sHHHhhhHHIIhhHHH", 4, UNITS_PER_EM, 400, 5, 0, 650, 600, 0,
+            75, 650, 600, 0, 350, 50, 250, 0, bytes(10), 0, 0, 0x08000000, 0, b"    ", 0x40,
+            min(mapping), max(mapping), ASCENDER, DESCENDER, 0, ASCENDER, -DESCENDER, 0x40000, 0,
+            500, 700, 0, 0, 0,
+        ),
+        b"post": struct.pack(">IIhhIIIII", 0x00030000, 0, -100, 50, 1, 0, 0, 0, 0),
+    }
+
+    tags = sorted(tables)
+    search = 2 ** int(math.log2(len(tags)))
+    header = struct.pack(
+        ">IHHHH", 0x00010000, len(tags), search * 16, int(math.log2(search)),
+        len(tags) * 16 - search * 16,
+    )
+    offset = len(header) + 16 * len(tags)
+    directory = b""
+    body = b""
+    for tag in tags:
+        data = tables[tag]
+        if tag == b"head":
+            head = offset + len(body)
+        directory += struct.pack(">4sIII", tag, checksum(data), offset + len(body), len(data))
+        body += data + b"\0" * (-len(data) % 4)
+    font = bytearray(header + directory + body)
+    adjustment = (0xB1B0AFBA - checksum(bytes(font))) & 0xFFFFFFFF
+    font[head + 8 : head + 12] = struct.pack(">I", adjustment)
+    return bytes(font), characters
+
+
+def preview(characters):
+    """An SVG with every glyph drawn large, and then at the size of card labels."""
+    columns = 12
+    cell = 110
+    paths = []
+    for index, character in enumerate(sorted(characters)):
+        x0 = (index % columns) * cell + 5
+        y0 = (index // columns) * cell + 5
+        data = ""
+        for points, scale in flatten(characters[character]):
+            for contour in outline(points, scale):
+                data += "M" + " L".join(
+                    f"{x0 + (x - LEFT) / SCALE:.1f} {y0 + (TOP - y) / SCALE:.1f}" for x, y in contour
+                ) + "Z"
+        paths.append(f'<path d="{data}"/>')
+    rows = (len(characters) + columns - 1) // columns
+    height = rows * cell + 10
+    text = "".join(sorted(characters))
+    small = "".join(
+        f'<text x="5" y="{height + 30 + 30 * i}" font-family="{FAMILY}" font-size="16">'
+        f"{text[i * 40:(i + 1) * 40]}</text>"
+        for i in range((len(text) + 39) // 40)
+    )
+    return (
+        f'<svg xmlns="http://www.w3.org/2000/svg" width="{columns * cell + 10}" '
+        f'height="{height + 160}"><rect width="100%" height="100%" fill="white"/>'
+        f'{"".join(paths)}{small}</svg>'
+    )
+
+
+if __name__ == "__main__":
+    font, characters = build()
+    with open("IChingHanzi.ttf", "wb") as file:
+        file.write(font)
+    if "--preview" in sys.argv:
+        with open("preview.svg", "w") as file:
+            file.write(preview(characters))
+    print(f"Wrote {len(characters)} glyphs, {len(font)} bytes.")
@@ -1,27 +1,91 @@
 use crate::{
-    divination_method::DivinationMethod, line::Line, symbols::big_line::LINE_SPACER,
+    divination_method::DivinationMethod,
+    line::Line,
+    symbols::big_line::{rows, LINE_SPACER},
     trigram::Trigram,
 };
+use clap::ValueEnum;
+use std::io::{self, Write};
 
 /// The 64 Hexagrams have several different orderings, the most
 /// common of which is the King Wen sequence.
 /// [See here for more details / history](https://en.wikipedia.org/wiki/King_Wen_sequence)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum HexagramOrdering {
     /// The most common sequence
     KingWen,
     /// a.k.a Fu Xi sequence, Shao Yong sequence
     Binary,
-    /// From the [Mawangdui Silk Texts](https://en.wikipedia.org/wiki/Mawangdui_Silk_Texts)
+    /// From the Mawangdui Silk Texts
+    ///
+    /// See [this Wikipedia article](https://en.wikipedia.org/wiki/Mawangdui_Silk_Texts) for more info.
     Mawangdui,
 }
 
+/// The King Wen sequence number of each hexagram, indexed by its position in the binary sequence
+/// (counting from 0.)
+const KING_WEN_SEQUENCE: [u8; 64] = [
+    2, 23, 8, 20, 16, 35, 45, 12, 15, 52, 39, 53, 62, 56, 31, 33, 7, 4, 29, 59, 40, 64, 47, 6, 46,
+    18, 48, 57, 32, 50, 28, 44, 24, 27, 3, 42, 51, 21, 17, 25, 36, 22, 63, 37, 55, 30, 49, 13, 19,
+    41, 60, 61, 54, 38, 58, 10, 11, 26, 5, 9, 34, 14, 43, 1,
+];
+
+/// The Mawangdui sequence groups hexagrams by their upper trigram, in this order. Trigrams are
+/// given by their [number](Trigram::number).
+const MAWANGDUI_ABOVE: [usize; 8] = [1, 7, 6, 4, 8, 2, 3, 5];
+/// Within each group of the Mawangdui sequence, the hexagram whose lower trigram is the same as
+/// its upper trigram comes first. The rest follow in this order of their lower trigram.
+const MAWANGDUI_BELOW: [usize; 8] = [1, 8, 7, 2, 6, 3, 4, 5];
+
+impl HexagramOrdering {
+    /// Get the position (1-64) of a hexagram in this ordering. Changing lines are ignored.
+    pub fn position(&self, hexagram: &Hexagram) -> u8 {
+        match self {
+            Self::KingWen => KING_WEN_SEQUENCE[hexagram.binary_index() as usize],
+            Self::Binary => hexagram.binary_index() + 1,
+            Self::Mawangdui => {
+                let above = hexagram.above.number();
+                let below = hexagram.below.number();
+                let group = MAWANGDUI_ABOVE
+                    .iter()
+                    .position(|&trigram| trigram == above)
+                    .expect("every trigram has a group");
+                let offset = if above == below {
+                    0
+                } else {
+                    1 + MAWANGDUI_BELOW
+                        .iter()
+                        .filter(|&&trigram| trigram != above)
+                        .position(|&trigram| trigram == below)
+                        .expect("every trigram is in the order")
+                };
+
+                (group * 8 + offset + 1) as u8
+            }
+        }
+    }
+
+    /// Get the hexagram at a position (1-64) in this ordering, or `None` if there's no such
+    /// position.
+    pub fn hexagram_at(&self, position: u8) -> Option<Hexagram> {
+        self.hexagrams().nth(usize::from(position).checked_sub(1)?)
+    }
+
+    /// Get all 64 hexagrams, in this ordering.
+    pub fn hexagrams(&self) -> impl Iterator<Item = Hexagram> {
+        let mut hexagrams: Vec<Hexagram> = (0..64).map(Hexagram::from_binary_index).collect();
+        hexagrams.sort_by_key(|hexagram| self.position(hexagram));
+        hexagrams.into_iter()
+    }
+}
+
 /// A `Hexagram` is a collection of lines divided into two groups: lines above and lines below.
 /// The order of the lines determines the specific hexagram (the primary hexagram) and its
 /// meaning. If lines are marked as "changing", then a second hexagram (the relating hexagram)
 /// will be produced that provides additional meaning. Special attention should be paid to
 /// "changing" lines as they can change the meaning of the primary hexagram.
 /// [See here for more details / history](https://en.wikipedia.org/wiki/Hexagram_\(I_Ching\))
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Hexagram {
     above: Trigram,
     below: Trigram,
@@ -43,12 +107,23 @@ impl Hexagram {
 
     /// Create a new `Hexagram` from random [`Trigram`]s.
     pub fn new_random(divination_method: DivinationMethod) -> Self {
+        Self::new_random_with_rng(divination_method, &fastrand::Rng::new())
+    }
+
+    /// Create a new random `Hexagram`, drawing each [`Line`] from the given [`fastrand::Rng`].
+    pub fn new_random_with_rng(divination_method: DivinationMethod, rng: &fastrand::Rng) -> Self {
         Hexagram {
-            above: Trigram::new_random(divination_method),
-            below: Trigram::new_random(divination_method),
+            above: Trigram::new_random_with_rng(divination_method, rng),
+            below: Trigram::new_random_with_rng(divination_method, rng),
         }
     }
 
+    /// Create a new random `Hexagram` from a seed. The same seed and [`DivinationMethod`] will
+    /// always produce the same `Hexagram`, including its changing lines.
+    pub fn new_seeded(divination_method: DivinationMethod, seed: u64) -> Self {
+        Self::new_random_with_rng(divination_method, &fastrand::Rng::with_seed(seed))
+    }
+
     /// Get the unicode symbol representing this Hexagram. The symbol is retrieved from the given
     /// `HexagramRepository`.
     pub fn symbol(&self, with_changes: bool) -> &str {
@@ -126,19 +201,25 @@ impl Hexagram {
         }
     }
 
+    /// Get the `Hexagram` that a unicode hexagram symbol stands for, e.g. `䷀` for The Creative.
+    /// Whitespace around the symbol is ignored.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let symbol = symbol.trim();
+        (0..64)
+            .map(Hexagram::from_binary_index)
+            .find(|hexagram| hexagram.symbol(false) == symbol)
+    }
+
     /// Get a `Vec` of `usize`s representing the positions of lines that are marked as "changing".
+    /// Positions are counted from the bottom line up, starting at 1, as is traditional.
+    ///
+    /// Up to and including 0.5.0 positions were counted from the top line down; a position `p`
+    /// returned by those versions is `7 - p` now.
     pub fn get_changing_line_positions(&self) -> Vec<usize> {
-        self.lines()
+        self.line_values()
+            .iter()
             .enumerate()
-            .filter_map(|(index, &line)| {
-                if matches!(line, Line::Broken { changing: true })
-                    || matches!(line, Line::Unbroken { changing: true })
-                {
-                    Some(index + 1)
-                } else {
-                    None
-                }
-            })
+            .filter_map(|(index, value)| matches!(value, 6 | 9).then_some(index + 1))
             .collect()
     }
 
@@ -147,11 +228,110 @@ impl Hexagram {
         self.above.lines().chain(self.below.lines())
     }
 
+    /// Get the numeric values (6-9) of this `Hexagram`'s lines, from the bottom line up. This is
+    /// the order in which the lines are cast. See [`Line::value`] for the meaning of each value.
+    pub fn line_values(&self) -> [u8; 6] {
+        let Hexagram { above, below } = self;
+
+        [
+            below.2.value(),
+            below.1.value(),
+            below.0.value(),
+            above.2.value(),
+            above.1.value(),
+            above.0.value(),
+        ]
+    }
+
     /// Print the `Hexagram` as large ASCII-art lines.
     pub fn print_big(&self) {
-        print!("{LINE_SPACER}");
-        self.above.print_big();
-        self.below.print_big();
+        self.write_big(&mut std::io::stdout().lock())
+            .expect("stdout is writable");
+    }
+
+    /// Write the `Hexagram` as large ASCII-art lines, from the top line down.
+    pub fn write_big<W: Write + ?Sized>(&self, output: &mut W) -> io::Result<()> {
+        for row in rows(LINE_SPACER) {
+            writeln!(output, "{row}")?;
+        }
+        self.above.write_big(output)?;
+        self.below.write_big(output)
+    }
+
+    /// Return this Hexagram's "primary" Hexagram, which is this Hexagram without any of its lines
+    /// marked as "changing".
+    pub fn primary_hexagram(&self) -> Self {
+        let Hexagram { above, below } = self;
+        let Trigram(a1, a2, a3) = above;
+        let Trigram(b1, b2, b3) = below;
+
+        Hexagram::new(
+            Trigram(a1.primary(), a2.primary(), a3.primary()),
+            Trigram(b1.primary(), b2.primary(), b3.primary()),
+        )
+    }
+
+    /// Return this Hexagram's nuclear Hexagram (互卦), whose lower trigram is made of the second
+    /// to fourth lines of this Hexagram and whose upper trigram is made of the third to fifth
+    /// lines. Changing lines are ignored.
+    pub fn nuclear_hexagram(&self) -> Self {
+        let [_, second, third, fourth, fifth, _] = self.primary_lines();
+        Hexagram::from_lines([second, third, fourth, third, fourth, fifth])
+    }
+
+    /// Return this Hexagram's inverse Hexagram (綜卦), which is this Hexagram turned upside down.
+    /// Changing lines are ignored.
+    pub fn inverse_hexagram(&self) -> Self {
+        let mut lines = self.primary_lines();
+        lines.reverse();
+        Hexagram::from_lines(lines)
+    }
+
+    /// Return this Hexagram's complement Hexagram (錯卦), in which every line is replaced by its
+    /// opposite. Changing lines are ignored.
+    pub fn complement_hexagram(&self) -> Self {
+        Hexagram::from_lines(self.primary_lines().map(|line| line.opposite()))
+    }
+
+    /// Return the Hexagram that differs from this Hexagram in only one line, at `position` (1-6,
+    /// from the bottom line up), or `None` if there's no such line. Changing lines are ignored.
+    pub fn with_line_flipped(&self, position: usize) -> Option<Self> {
+        let mut lines = self.primary_lines();
+        let line = lines.get_mut(position.checked_sub(1)?)?;
+        *line = line.opposite();
+        Some(Hexagram::from_lines(lines))
+    }
+
+    /// Get the lines of this Hexagram's primary Hexagram, from the bottom line up.
+    fn primary_lines(&self) -> [Line; 6] {
+        let Hexagram { above, below } = self.primary_hexagram();
+        [below.2, below.1, below.0, above.2, above.1, above.0]
+    }
+
+    /// Create a Hexagram from its lines, listed from the bottom line up.
+    fn from_lines([first, second, third, fourth, fifth, sixth]: [Line; 6]) -> Self {
+        Hexagram::new(Trigram(sixth, fifth, fourth), Trigram(third, second, first))
+    }
+
+    /// Get this Hexagram's position (0-63) in the binary sequence, which reads the lines as
+    /// binary digits with yang as 1, from the bottom line (the most significant digit) up.
+    /// Changing lines are ignored.
+    fn binary_index(&self) -> u8 {
+        self.primary_lines().iter().fold(0, |index, line| {
+            (index << 1) | u8::from(line == &Line::unbroken())
+        })
+    }
+
+    /// The inverse of [`Hexagram::binary_index`].
+    fn from_binary_index(index: u8) -> Self {
+        let line = |position: u8| {
+            if index & (1 << (5 - position)) == 0 {
+                Line::broken()
+            } else {
+                Line::unbroken()
+            }
+        };
+        Hexagram::from_lines([line(0), line(1), line(2), line(3), line(4), line(5)])
     }
 
     /// Return this Hexagram's "relating" Hexagram, if it has one. Only Hexagrams with changing lines
@@ -174,3 +354,142 @@ impl Hexagram {
         }
     }
 }
+
+impl TryFrom<[u8; 6]> for Hexagram {
+    type Error = Error;
+
+    /// Create a `Hexagram` from the numeric values (6-9) of its lines, listed from the bottom line
+    /// up. This is the inverse of [`Hexagram::line_values`].
+    fn try_from(
+        [first, second, third, fourth, fifth, sixth]: [u8; 6],
+    ) -> Result<Self, Self::Error> {
+        Ok(Hexagram::from_lines([
+            first.try_into()?,
+            second.try_into()?,
+            third.try_into()?,
+            fourth.try_into()?,
+            fifth.try_into()?,
+            sixth.try_into()?,
+        ]))
+    }
+}
+
+/// Errors related to `Hexagram`s.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to create Hexagram line: {0}")]
+    Line(#[from] crate::line::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hexagram(lines: [u8; 6]) -> Hexagram {
+        Hexagram::try_from(lines).unwrap()
+    }
+
+    #[test]
+    fn test_orderings_contain_every_hexagram_once() {
+        for ordering in HexagramOrdering::value_variants() {
+            let mut positions: Vec<u8> = ordering
+                .hexagrams()
+                .map(|hexagram| ordering.position(&hexagram))
+                .collect();
+            positions.dedup();
+            assert_eq!(positions, (1..=64).collect::<Vec<u8>>(), "{ordering:?}");
+        }
+    }
+
+    #[test]
+    fn test_positions_in_each_ordering() {
+        // ䷀ The Creative
+        let creative = hexagram([7, 7, 7, 7, 7, 7]);
+        assert_eq!(HexagramOrdering::KingWen.position(&creative), 1);
+        assert_eq!(HexagramOrdering::Binary.position(&creative), 64);
+        assert_eq!(HexagramOrdering::Mawangdui.position(&creative), 1);
+
+        // ䷖ Splitting Apart
+        let splitting_apart = hexagram([8, 8, 8, 8, 8, 7]);
+        assert_eq!(HexagramOrdering::KingWen.position(&splitting_apart), 23);
+        assert_eq!(HexagramOrdering::Binary.position(&splitting_apart), 2);
+        assert_eq!(HexagramOrdering::Mawangdui.position(&splitting_apart), 11);
+
+        // Changing lines are ignored.
+        assert_eq!(
+            HexagramOrdering::KingWen.position(&hexagram([9, 7, 7, 7, 7, 7])),
+            1
+        );
+        assert_eq!(
+            HexagramOrdering::KingWen.hexagram_at(23),
+            Some(splitting_apart)
+        );
+        assert_eq!(HexagramOrdering::KingWen.hexagram_at(65), None);
+        assert_eq!(HexagramOrdering::KingWen.hexagram_at(0), None);
+    }
+
+    #[test]
+    fn test_hexagrams_are_found_by_symbol() {
+        assert_eq!(
+            Hexagram::from_symbol(" ䷖ "),
+            Some(hexagram([8, 8, 8, 8, 8, 7]))
+        );
+        assert_eq!(Hexagram::from_symbol("☰"), None);
+    }
+
+    #[test]
+    fn test_changing_lines_are_counted_from_the_bottom() {
+        // ䷗ Return, whose first line is changing.
+        assert_eq!(
+            hexagram([9, 8, 8, 8, 8, 8]).get_changing_line_positions(),
+            vec![1]
+        );
+        // ䷖ Splitting Apart, whose top line is changing.
+        assert_eq!(
+            hexagram([8, 8, 8, 8, 8, 9]).get_changing_line_positions(),
+            vec![6]
+        );
+        assert_eq!(
+            hexagram([6, 7, 8, 9, 7, 6]).get_changing_line_positions(),
+            vec![1, 4, 6]
+        );
+        assert!(hexagram([7, 8, 7, 8, 7, 8])
+            .get_changing_line_positions()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_related_hexagrams() {
+        // ䷂ Difficulty at the Beginning
+        let difficulty = hexagram([9, 8, 8, 8, 7, 8]);
+
+        // ䷖ Splitting Apart
+        assert_eq!(difficulty.nuclear_hexagram(), hexagram([8, 8, 8, 8, 8, 7]));
+        // ䷃ Youthful Folly
+        assert_eq!(difficulty.inverse_hexagram(), hexagram([8, 7, 8, 8, 8, 7]));
+        // ䷱ The Caldron
+        assert_eq!(
+            difficulty.complement_hexagram(),
+            hexagram([8, 7, 7, 7, 8, 7])
+        );
+    }
+
+    #[test]
+    fn test_flipping_a_line_gives_a_neighboring_hexagram() {
+        // ䷂ Difficulty at the Beginning, whose first line is changing.
+        let difficulty = hexagram([9, 8, 8, 8, 7, 8]);
+
+        // ䷇ Holding Together
+        assert_eq!(
+            difficulty.with_line_flipped(1),
+            Some(hexagram([8, 8, 8, 8, 7, 8]))
+        );
+        // ䷗ Return
+        assert_eq!(
+            difficulty.with_line_flipped(5),
+            Some(hexagram([7, 8, 8, 8, 8, 8]))
+        );
+        assert_eq!(difficulty.with_line_flipped(0), None);
+        assert_eq!(difficulty.with_line_flipped(7), None);
+    }
+}
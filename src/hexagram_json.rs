@@ -3,13 +3,51 @@ use iching::{
     hexagram_repository::HexagramRepository,
     trigram::Trigram,
 };
-use serde_derive::Deserialize;
-use std::io::Write;
-use termcolor::{Color, ColorSpec, WriteColor};
+use serde_derive::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to read the hexagrams from {}: {1}", .0.display())]
+    Io(PathBuf, #[source] io::Error),
+    #[error("Failed to parse the hexagrams in {}: {1}", .0.display())]
+    Parse(PathBuf, #[source] serde_json::Error),
+    #[error("Expected 64 hexagrams, but found {0}")]
+    Count(usize),
+    #[error("Hexagram numbers go from 1 to 64, but one is numbered {0}")]
+    Number(usize),
+    #[error("There are two hexagrams numbered {0}")]
+    DuplicateNumber(usize),
+    #[error("Hexagram No. {number} has trigram {trigram} {position}, but trigram numbers go from 1 to 8")]
+    Trigram {
+        number: usize,
+        trigram: usize,
+        position: &'static str,
+    },
+    #[error("Hexagram No. {number} is made of trigrams {above} above and {below} below, which make hexagram No. {actual}")]
+    Trigrams {
+        number: usize,
+        above: usize,
+        below: usize,
+        actual: u8,
+    },
+    #[error(
+        "Hexagram No. {number} has a line at position {position}, but positions go from 1 to 6"
+    )]
+    LinePosition { number: usize, position: usize },
+    #[error("Hexagram No. {number} has {count} texts for the line at position {position}, instead of one")]
+    LineCount {
+        number: usize,
+        position: usize,
+        count: usize,
+    },
+}
 
 pub(crate) struct HexagramJson {
     ordering: HexagramOrdering,
     list: Vec<HexagramJsonInfo>,
+    /// A file to read the hexagrams from, instead of the ones built into the app.
+    data_file: Option<PathBuf>,
     is_initialized: bool,
 }
 
@@ -17,6 +55,13 @@ impl HexagramJson {
     pub(crate) fn new() -> Self {
         HexagramJson::default()
     }
+
+    /// Read the hexagrams from a JSON file in the same format as `src/hexagrams.json`, e.g. to
+    /// use another translation.
+    pub(crate) fn with_data_file(mut self, path: PathBuf) -> Self {
+        self.data_file = Some(path);
+        self
+    }
 }
 
 impl HexagramRepository for HexagramJson {
@@ -59,26 +104,125 @@ impl HexagramRepository for HexagramJson {
     }
 
     // Load hexagram data from json. The json file is inlined into the executable using
-    // `include_str!`. Then, `serde_json` parses it into a `Vec<RawHexagramInfo>` which
-    // is then converted into a `Vec<HexagramInfo>`.
+    // `include_str!`, unless a data file was given. Then, `serde_json` parses it into a
+    // `Vec<RawHexagramInfo>` which is then converted into a `Vec<HexagramInfo>`. The original
+    // Chinese texts are always the ones in `classical.json`, and the commentaries the ones in
+    // `commentary.json`, whichever translation is used.
     fn initialize(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let json_file = include_str!("hexagrams.json");
-        let raw_hexagram_json: Vec<RawHexagramJsonInfo> = serde_json::from_str(json_file)?;
+        let mut raw_hexagram_json: Vec<RawHexagramJsonInfo> = match &self.data_file {
+            Some(path) => {
+                let json_file =
+                    fs::read_to_string(path).map_err(|err| Error::Io(path.clone(), err))?;
+                serde_json::from_str(&json_file).map_err(|err| Error::Parse(path.clone(), err))?
+            }
+            None => serde_json::from_str(include_str!("hexagrams.json"))?,
+        };
+        check(&raw_hexagram_json)?;
+        // `get_by_number` finds hexagrams by their position in the list.
+        raw_hexagram_json.sort_by_key(|raw_hexagram_json| raw_hexagram_json.number);
+        let mut classical: Vec<RawClassicalText> =
+            serde_json::from_str(include_str!("classical.json"))?;
+        let mut commentaries: Vec<RawCommentary> =
+            serde_json::from_str(include_str!("commentary.json"))?;
         self.list = raw_hexagram_json
             .into_iter()
-            .map(|raw_hexagram_json| raw_hexagram_json.into())
-            .collect();
+            .map(|raw_hexagram_json| {
+                let index = classical
+                    .iter()
+                    .position(|text| text.number == raw_hexagram_json.number)
+                    .ok_or_else(|| {
+                        format!("There's no hexagram No. {}", raw_hexagram_json.number)
+                    })?;
+                let classical = classical.swap_remove(index);
+                let index = commentaries
+                    .iter()
+                    .position(|commentary| commentary.number == raw_hexagram_json.number)
+                    .ok_or_else(|| {
+                        format!(
+                            "There's no commentary on hexagram No. {}",
+                            raw_hexagram_json.number
+                        )
+                    })?;
+                let commentary = commentaries.swap_remove(index).commentary;
+                let mut hexagram_info = HexagramJsonInfo::from(raw_hexagram_json);
+                hexagram_info.name.chinese_simplified = classical.name.simplified;
+                hexagram_info.classical = ClassicalText {
+                    judgement: classical.judgement,
+                    images: classical.images,
+                    lines: classical.lines,
+                };
+                hexagram_info.commentary = commentary;
+                Ok(hexagram_info)
+            })
+            .collect::<Result<_, String>>()?;
 
         self.is_initialized = true;
         Ok(())
     }
 }
 
+/// Check that the hexagrams read from JSON are the 64 hexagrams of the King Wen sequence, each
+/// with the texts of its six lines, so that a data file with a mistake in it can't make the app
+/// panic or show the wrong hexagram later.
+fn check(raw_hexagram_json: &[RawHexagramJsonInfo]) -> Result<(), Error> {
+    if raw_hexagram_json.len() != 64 {
+        return Err(Error::Count(raw_hexagram_json.len()));
+    }
+
+    let mut numbers = [false; 64];
+    for raw in raw_hexagram_json {
+        let number = raw.number;
+        if !(1..=64).contains(&number) {
+            return Err(Error::Number(number));
+        }
+        if std::mem::replace(&mut numbers[number - 1], true) {
+            return Err(Error::DuplicateNumber(number));
+        }
+
+        let trigram = |trigram: usize, position| {
+            Trigram::try_from(trigram).map_err(|_| Error::Trigram {
+                number,
+                trigram,
+                position,
+            })
+        };
+        let above = trigram(raw.trigrams.above, "above")?;
+        let below = trigram(raw.trigrams.below, "below")?;
+        let actual = HexagramOrdering::KingWen.position(&Hexagram::new(above, below));
+        if usize::from(actual) != number {
+            return Err(Error::Trigrams {
+                number,
+                above: raw.trigrams.above,
+                below: raw.trigrams.below,
+                actual,
+            });
+        }
+
+        let mut counts = [0; 6];
+        for line in &raw.lines {
+            match line.position {
+                1..=6 => counts[line.position - 1] += 1,
+                position => return Err(Error::LinePosition { number, position }),
+            }
+        }
+        if let Some((index, &count)) = counts.iter().enumerate().find(|(_, &count)| count != 1) {
+            return Err(Error::LineCount {
+                number,
+                position: index + 1,
+                count,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 impl Default for HexagramJson {
     fn default() -> Self {
         Self {
             ordering: HexagramOrdering::KingWen,
             list: Vec::new(),
+            data_file: None,
             is_initialized: false,
         }
     }
@@ -94,18 +238,74 @@ struct RawHexagramJsonInfo {
     lines: Vec<ChangingLineMeaning>,
 }
 
-/// Associates the meaning of a changing line with the position of a changing line in a hexagram.
+/// The original Chinese text of a hexagram, as it's stored in `classical.json`.
+#[derive(Deserialize)]
+struct RawClassicalText {
+    number: usize,
+    name: RawClassicalName,
+    judgement: String,
+    images: String,
+    lines: Vec<ChangingLineMeaning>,
+}
+
+#[derive(Deserialize)]
+struct RawClassicalName {
+    simplified: String,
+}
+
+/// The original Chinese text of a hexagram, in traditional characters: the judgement (卦辭), the
+/// image (象) and the line statements (爻辭), from the bottom line up.
+#[derive(Default, Serialize)]
+pub(crate) struct ClassicalText {
+    pub(crate) judgement: String,
+    pub(crate) images: String,
+    pub(crate) lines: Vec<ChangingLineMeaning>,
+}
+
+/// The commentaries of the Ten Wings (十翼) on a hexagram, as they're stored in
+/// `commentary.json`.
 #[derive(Deserialize)]
+struct RawCommentary {
+    number: usize,
+    #[serde(flatten)]
+    commentary: Commentary,
+}
+
+/// The commentaries of the Ten Wings (十翼) on a hexagram, in traditional characters. The Great
+/// Image (大象) is the hexagram's classical image, so it isn't repeated here.
+#[derive(Default, Deserialize, Serialize)]
+pub(crate) struct Commentary {
+    /// The commentary on the judgement (彖傳).
+    pub(crate) tuan: String,
+    /// The Small Images (小象), which comment on each line, from the bottom line up.
+    pub(crate) small_images: Vec<ChangingLineMeaning>,
+    /// The commentary on the words of the text (文言傳), which only comments on hexagrams 1 and
+    /// 2. Its paragraphs are separated by newlines.
+    pub(crate) wenyan: Option<String>,
+    /// The passage of the sequence of the hexagrams (序卦傳) that leads up to this hexagram.
+    pub(crate) sequence: String,
+    /// The miscellaneous notes on the hexagrams (雜卦傳) about this hexagram.
+    pub(crate) miscellaneous: String,
+}
+
+/// Associates the meaning of a changing line with the position of a changing line in a hexagram.
+/// Positions are counted from the bottom line up, starting at 1.
+#[derive(Deserialize, Serialize)]
 pub(crate) struct ChangingLineMeaning {
     pub(crate) position: usize,
     pub(crate) meaning: String,
 }
 
-#[derive(Deserialize)]
-struct NameTranslations {
-    english: String,
-    chinese: String,
-    pinyin: String,
+#[derive(Deserialize, Serialize)]
+pub(crate) struct NameTranslations {
+    pub(crate) english: String,
+    /// The Chinese name in traditional characters.
+    pub(crate) chinese: String,
+    /// The Chinese name in simplified characters, which is read from `classical.json` rather
+    /// than from the translation.
+    #[serde(skip_deserializing)]
+    pub(crate) chinese_simplified: String,
+    pub(crate) pinyin: String,
 }
 
 #[derive(Deserialize)]
@@ -122,6 +322,8 @@ pub(crate) struct HexagramJsonInfo {
     number: usize,
     trigrams: Trigrams,
     hexagram: Hexagram,
+    classical: ClassicalText,
+    commentary: Commentary,
 }
 
 struct Trigrams {
@@ -130,51 +332,60 @@ struct Trigrams {
 }
 
 impl HexagramJsonInfo {
+    /// Get this hexagram's number in the King Wen sequence.
+    pub(crate) fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Get the `Hexagram` that this info describes.
+    pub(crate) fn hexagram(&self) -> &Hexagram {
+        &self.hexagram
+    }
+
+    /// Get the unicode symbol of this hexagram.
+    pub(crate) fn symbol(&self) -> &str {
+        self.hexagram.symbol(false)
+    }
+
+    /// Get the English translation of this hexagram's name.
+    pub(crate) fn english_name(&self) -> &str {
+        &self.name.english
+    }
+
+    /// Get this hexagram's name in English, Chinese and pinyin.
+    pub(crate) fn name(&self) -> &NameTranslations {
+        &self.name
+    }
+
+    pub(crate) fn judgement(&self) -> &str {
+        &self.judgement
+    }
+
+    pub(crate) fn images(&self) -> &str {
+        &self.images
+    }
+
+    /// Get the original Chinese text of this hexagram.
+    pub(crate) fn classical(&self) -> &ClassicalText {
+        &self.classical
+    }
+
+    /// Get the commentaries of the Ten Wings on this hexagram.
+    pub(crate) fn commentary(&self) -> &Commentary {
+        &self.commentary
+    }
+
+    /// Get the meanings of all of this hexagram's lines, from the bottom line up.
+    pub(crate) fn lines(&self) -> &[ChangingLineMeaning] {
+        &self.lines
+    }
+
     pub(crate) fn line_meanings(&self, changing_lines: &[usize]) -> Vec<&ChangingLineMeaning> {
         self.lines
             .iter()
             .filter(|&line_meaning| changing_lines.contains(&(line_meaning.position)))
             .collect()
     }
-
-    pub(crate) fn write_to<T>(&self, output: &mut T) -> Result<(), Box<dyn std::error::Error>>
-    where
-        T: WriteColor + Write,
-    {
-        // Write out header
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(196))))
-            .expect("output stream color can be set");
-        writeln!(
-            output,
-            "Hexagram No. {}  {}",
-            self.number,
-            self.hexagram.symbol(false)
-        )?;
-        output.reset().expect("output stream color can be reset");
-
-        writeln!(output, "\t{}", self.name.english)?;
-        writeln!(output, "\t{} ({})", self.name.chinese, self.name.pinyin)?;
-        writeln!(output)?;
-
-        // Write out judgement
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(160))))
-            .expect("output stream color can be set");
-        writeln!(output, "Judgement:")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "\t{}", self.judgement)?;
-
-        // Write out images
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(124))))
-            .expect("output stream color can be set");
-        writeln!(output, "Images:")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "\t{}", self.images)?;
-
-        Ok(())
-    }
 }
 
 impl From<RawHexagramJsonInfo> for HexagramJsonInfo {
@@ -188,20 +399,15 @@ impl From<RawHexagramJsonInfo> for HexagramJsonInfo {
             judgement,
         }: RawHexagramJsonInfo,
     ) -> Self {
-        // Add a tab to the beginning of each line in the judgement and images. This'll improve the
-        // formatting of the text when it's displayed in the terminal.
-        let judgement = judgement.replace('\n', "\n\t");
-        let images = images.replace('\n', "\n\t");
-
         let trigrams = Trigrams {
             above: trigrams
                 .above
                 .try_into()
-                .expect("trigram numbers in JSON file are valid"),
+                .expect("trigram numbers are checked on initialization"),
             below: trigrams
                 .below
                 .try_into()
-                .expect("trigram numbers in JSON file are valid"),
+                .expect("trigram numbers are checked on initialization"),
         };
         let hexagram = Hexagram::new(trigrams.above, trigrams.below);
 
@@ -213,44 +419,87 @@ impl From<RawHexagramJsonInfo> for HexagramJsonInfo {
             images,
             lines,
             hexagram,
+            classical: ClassicalText::default(),
+            commentary: Commentary::default(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use iching::HexagramRepository;
-    use termcolor::Buffer;
+    use super::*;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Initialize hexagrams from a copy of the built-in data that was changed by `change`.
+    fn initialize_changed(change: impl FnOnce(&mut Vec<Value>)) -> Result<HexagramJson, String> {
+        static FILES: AtomicUsize = AtomicUsize::new(0);
+        let mut data: Vec<Value> = serde_json::from_str(include_str!("hexagrams.json")).unwrap();
+        change(&mut data);
+        let path = std::env::temp_dir().join(format!(
+            "iching-hexagrams-{}-{}.json",
+            std::process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&path, serde_json::to_string(&data).unwrap()).unwrap();
+
+        let mut hexagrams = HexagramJson::new().with_data_file(path.clone());
+        let result = hexagrams.initialize().map_err(|err| err.to_string());
+        fs::remove_file(path).unwrap();
+        result.map(|()| hexagrams)
+    }
 
     #[test]
-    fn test_writing_without_color_works() {
-        let mut output = Buffer::no_color();
-        let mut hexagrams = super::HexagramJson::default();
-        hexagrams.initialize().unwrap();
-        let hexagram = hexagrams.get_by_number(1).unwrap();
-        hexagram.write_to(&mut output).unwrap();
-
-        let output = String::from_utf8(output.into_inner()).unwrap();
+    fn test_hexagrams_in_any_order_are_found_by_number() {
+        let hexagrams = initialize_changed(|data| data.reverse()).unwrap();
+        for number in 1..=64 {
+            assert_eq!(
+                hexagrams.get_by_number(number).unwrap().number(),
+                number as usize
+            );
+        }
+    }
 
+    #[test]
+    fn test_invalid_trigrams_are_an_error() {
+        let error = initialize_changed(|data| data[0]["trigrams"]["above"] = 9.into());
+        assert_eq!(
+            error.err().unwrap(),
+            "Hexagram No. 1 has trigram 9 above, but trigram numbers go from 1 to 8"
+        );
+        let error = initialize_changed(|data| data[0]["trigrams"]["below"] = 8.into());
         assert_eq!(
-            output,
-            "Hexagram No. 1  ䷀\n\tThe Creative\n\t乾 (Qián)\n\nJudgement:\n\tThe Creative works sublime success,\n\tFurthering through perseverance.\nImages:\n\tThe movement of heaven is full of power.\n\tThus the superior man makes himself strong and untiring.\n"
+            error.err().unwrap(),
+            "Hexagram No. 1 is made of trigrams 1 above and 8 below, which make hexagram No. 12"
         );
     }
 
     #[test]
-    fn test_writing_with_color_works() {
-        let mut output = Buffer::ansi();
-        let mut hexagrams = super::HexagramJson::default();
-        hexagrams.initialize().unwrap();
-        let hexagram = hexagrams.get_by_number(1).unwrap();
-        hexagram.write_to(&mut output).unwrap();
-
-        let output = String::from_utf8(output.into_inner()).unwrap();
+    fn test_invalid_line_positions_are_an_error() {
+        let error = initialize_changed(|data| data[2]["lines"][0]["position"] = 0.into());
+        assert_eq!(
+            error.err().unwrap(),
+            "Hexagram No. 3 has a line at position 0, but positions go from 1 to 6"
+        );
+        let error = initialize_changed(|data| data[2]["lines"][0]["position"] = 2.into());
+        assert_eq!(
+            error.err().unwrap(),
+            "Hexagram No. 3 has 0 texts for the line at position 1, instead of one"
+        );
+    }
 
+    #[test]
+    fn test_invalid_numbers_are_an_error() {
+        let error = initialize_changed(|data| data[63]["number"] = 65.into());
         assert_eq!(
-            output,
-            "\u{1b}[0m\u{1b}[38;5;196mHexagram No. 1  ䷀\n\u{1b}[0m\tThe Creative\n\t乾 (Qián)\n\n\u{1b}[0m\u{1b}[38;5;160mJudgement:\n\u{1b}[0m\tThe Creative works sublime success,\n\tFurthering through perseverance.\n\u{1b}[0m\u{1b}[38;5;124mImages:\n\u{1b}[0m\tThe movement of heaven is full of power.\n\tThus the superior man makes himself strong and untiring.\n"
+            error.err().unwrap(),
+            "Hexagram numbers go from 1 to 64, but one is numbered 65"
         );
+        let error = initialize_changed(|data| data[63]["number"] = 1.into());
+        assert_eq!(error.err().unwrap(), "There are two hexagrams numbered 1");
+        let error = initialize_changed(|data| {
+            data.pop();
+        });
+        assert_eq!(error.err().unwrap(), "Expected 64 hexagrams, but found 63");
     }
 }
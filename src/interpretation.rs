@@ -0,0 +1,166 @@
+use crate::{hexagram::Hexagram, line::Line};
+use clap::ValueEnum;
+use serde_derive::{Deserialize, Serialize};
+
+/// The traditional names of the line positions, from the bottom up.
+const PLACES: [&str; 6] = [
+    "at the beginning",
+    "in the second place",
+    "in the third place",
+    "in the fourth place",
+    "in the fifth place",
+    "at the top",
+];
+
+/// The rules that a school of interpretation follows to decide which line texts of a reading to
+/// consider.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum School {
+    /// Consider the text of every changing line of the primary hexagram.
+    #[default]
+    EveryChangingLine,
+    /// Follow Zhu Xi's rules, which pick at most one or two lines depending on how many are
+    /// changing, sometimes from the relating hexagram.
+    ///
+    /// See [Zhu Xi](https://en.wikipedia.org/wiki/Zhu_Xi) for more info.
+    ZhuXi,
+}
+
+/// Which of a reading's hexagrams a line text is read from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineSource {
+    /// The hexagram that was cast.
+    Primary,
+    /// The hexagram that the changing lines change into.
+    Relating,
+}
+
+/// A line text to consider, by the hexagram it's in and its position from the bottom, from 1 to 6.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineToRead {
+    pub source: LineSource,
+    pub position: usize,
+}
+
+impl School {
+    /// Get the line texts to consider for a cast `hexagram`, in the order they should be read.
+    pub fn lines_to_read(&self, hexagram: &Hexagram) -> Vec<LineToRead> {
+        let changing = hexagram.get_changing_line_positions();
+        let read = |source, positions: &[usize]| {
+            positions
+                .iter()
+                .map(|&position| LineToRead { source, position })
+                .collect()
+        };
+
+        match self {
+            School::EveryChangingLine => read(LineSource::Primary, &changing),
+            School::ZhuXi => {
+                let unchanging: Vec<usize> = (1..=6)
+                    .filter(|position| !changing.contains(position))
+                    .collect();
+                match changing.len() {
+                    // The upper of two changing lines is the one that rules.
+                    1 | 2 => read(LineSource::Primary, &changing[changing.len() - 1..]),
+                    // The lower of the relating hexagram's two unchanging lines rules.
+                    4 => read(LineSource::Relating, &unchanging[..1]),
+                    5 => read(LineSource::Relating, &unchanging),
+                    // No lines, three or six: the judgements are read instead.
+                    _ => Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Get advice for readings where the judgements are read instead of any line, or `None` if
+    /// the lines are enough.
+    pub fn judgement_advice(&self, hexagram: &Hexagram) -> Option<&'static str> {
+        match (self, hexagram.get_changing_line_positions().len()) {
+            (School::ZhuXi, 3) => Some(
+                "With three changing lines, Zhu Xi reads the judgements of both hexagrams, with \
+                 the primary hexagram's as the main one.",
+            ),
+            (School::ZhuXi, 6) => Some(
+                "With every line changing, Zhu Xi reads the judgement of the relating hexagram.",
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// How lines are named when their texts are shown.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineLabels {
+    /// By position, e.g. "Line 1".
+    #[default]
+    Positions,
+    /// By the traditional name of the line's value and place, e.g. "Nine at the beginning".
+    Traditional,
+}
+
+impl LineLabels {
+    /// Get the label of the `line` at `position` (from 1 at the bottom to 6 at the top).
+    ///
+    /// Traditional labels name yang lines "Nine" and yin lines "Six", after the values of their
+    /// changing forms, whether or not the line is changing.
+    pub fn label(&self, line: &Line, position: usize) -> String {
+        match self {
+            LineLabels::Positions => format!("Line {position}"),
+            LineLabels::Traditional => {
+                // Yang lines have the odd values, 7 and 9.
+                let value = if line.value() % 2 == 1 { "Nine" } else { "Six" };
+                format!("{value} {}", PLACES[position - 1])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hexagram(lines: [u8; 6]) -> Hexagram {
+        Hexagram::try_from(lines).unwrap()
+    }
+
+    #[test]
+    fn test_zhu_xi_picks_the_ruling_lines() {
+        let positions = |lines| {
+            School::ZhuXi
+                .lines_to_read(&hexagram(lines))
+                .into_iter()
+                .map(|line| (line.source, line.position))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(positions([7, 8, 7, 8, 7, 8]), []);
+        assert_eq!(positions([9, 8, 7, 8, 6, 8]), [(LineSource::Primary, 5)]);
+        assert_eq!(positions([9, 6, 9, 8, 7, 8]), []);
+        assert_eq!(positions([9, 6, 9, 6, 7, 8]), [(LineSource::Relating, 5)]);
+        assert_eq!(positions([9, 6, 9, 6, 9, 8]), [(LineSource::Relating, 6)]);
+        assert!(School::ZhuXi
+            .judgement_advice(&hexagram([9, 9, 9, 9, 9, 9]))
+            .is_some());
+        assert_eq!(
+            School::EveryChangingLine
+                .lines_to_read(&hexagram([9, 6, 9, 8, 7, 8]))
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_traditional_labels_name_the_value_and_place() {
+        let labels = LineLabels::Traditional;
+        assert_eq!(labels.label(&Line::unbroken(), 1), "Nine at the beginning");
+        assert_eq!(
+            labels.label(&Line::broken_changing(), 3),
+            "Six in the third place"
+        );
+        assert_eq!(labels.label(&Line::broken(), 6), "Six at the top");
+        assert_eq!(LineLabels::Positions.label(&Line::broken(), 6), "Line 6");
+    }
+}
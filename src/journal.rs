@@ -0,0 +1,287 @@
+//! A local journal of past readings.
+//!
+//! The journal is stored as a single JSON document, by default at `iching/journal.json` inside
+//! the user's data directory (e.g. `~/.local/share` on Linux.) The document looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "next_id": 3,
+//!   "readings": [
+//!     {
+//!       "id": 2,
+//!       "timestamp": "2023-01-01T12:30:00+01:00",
+//!       "question": "Should I start the project?",
+//!       "method": "ancient-yarrow-stalk",
+//!       "lines": [7, 8, 9, 7, 6, 8],
+//!       "primary": 55,
+//!       "relating": 17,
+//!       "notes": ["Started it anyway."]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `version` is the version of the format. It's incremented whenever the format changes in a
+//!   way that older versions of this app can't read.
+//! - `next_id` is the ID that the next reading will receive. IDs are never reused, even after a
+//!   reading has been deleted.
+//! - `lines` contains the value (6-9) of each line, from the bottom line up.
+//! - `primary` and `relating` are King Wen sequence numbers. `relating` is `null` when no lines
+//!   are changing.
+//! - `question` is `null` when no question was asked.
+
+use chrono::{DateTime, FixedOffset};
+use iching::{divination_method::DivinationMethod, hexagram::Hexagram};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The version of the journal format written by this version of the app.
+pub(crate) const JOURNAL_VERSION: u32 = 1;
+
+/// A single reading stored in the [`Journal`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Reading {
+    pub(crate) id: u64,
+    pub(crate) timestamp: DateTime<FixedOffset>,
+    pub(crate) question: Option<String>,
+    pub(crate) method: DivinationMethod,
+    pub(crate) lines: [u8; 6],
+    pub(crate) primary: usize,
+    pub(crate) relating: Option<usize>,
+    #[serde(default)]
+    pub(crate) notes: Vec<String>,
+}
+
+impl Reading {
+    /// Recreate the `Hexagram` that was cast for this reading, including its changing lines.
+    pub(crate) fn hexagram(&self) -> Result<Hexagram, Error> {
+        Hexagram::try_from(self.lines).map_err(|err| Error::InvalidLines(self.id, err))
+    }
+}
+
+/// A new reading that hasn't been added to the [`Journal`] yet, and so doesn't have an ID.
+pub(crate) struct NewReading {
+    pub(crate) timestamp: DateTime<FixedOffset>,
+    pub(crate) question: Option<String>,
+    pub(crate) method: DivinationMethod,
+    pub(crate) lines: [u8; 6],
+    pub(crate) primary: usize,
+    pub(crate) relating: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Journal {
+    version: u32,
+    next_id: u64,
+    readings: Vec<Reading>,
+}
+
+impl Journal {
+    /// Get the location of the journal file: either the given `path` or, if none was given, the
+    /// default location inside the user's data directory.
+    pub(crate) fn path_or_default(path: Option<PathBuf>) -> Result<PathBuf, Error> {
+        match path {
+            Some(path) => Ok(path),
+            None => dirs::data_dir()
+                .map(|data_dir| data_dir.join("iching").join("journal.json"))
+                .ok_or(Error::NoDataDirectory),
+        }
+    }
+
+    /// Load the journal stored at `path`. If no journal exists there yet, an empty journal is
+    /// returned instead.
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Journal::default()),
+            Err(err) => return Err(Error::Io(path.to_owned(), err)),
+        };
+
+        // Check the version before parsing the rest of the document, so that a journal from a
+        // newer version of the app produces a helpful error instead of a parsing error.
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+        let Versioned { version } =
+            serde_json::from_str(&contents).map_err(|err| Error::Json(path.to_owned(), err))?;
+        if version != JOURNAL_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        serde_json::from_str(&contents).map_err(|err| Error::Json(path.to_owned(), err))
+    }
+
+    /// Save the journal to `path`, creating any missing parent directories.
+    pub(crate) fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| Error::Io(parent.to_owned(), err))?;
+        }
+
+        let contents =
+            serde_json::to_string_pretty(self).map_err(|err| Error::Json(path.to_owned(), err))?;
+
+        // Write to a temporary file first so that a failed write can't corrupt the journal.
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, contents).map_err(|err| Error::Io(temp_path.clone(), err))?;
+        fs::rename(&temp_path, path).map_err(|err| Error::Io(path.to_owned(), err))
+    }
+
+    /// Add a new reading to the journal, returning the ID it was given.
+    pub(crate) fn add(&mut self, reading: NewReading) -> u64 {
+        let NewReading {
+            timestamp,
+            question,
+            method,
+            lines,
+            primary,
+            relating,
+        } = reading;
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.readings.push(Reading {
+            id,
+            timestamp,
+            question,
+            method,
+            lines,
+            primary,
+            relating,
+            notes: Vec::new(),
+        });
+
+        id
+    }
+
+    /// Get all readings, oldest first.
+    pub(crate) fn readings(&self) -> &[Reading] {
+        &self.readings
+    }
+
+    pub(crate) fn get(&self, id: u64) -> Result<&Reading, Error> {
+        self.readings
+            .iter()
+            .find(|reading| reading.id == id)
+            .ok_or(Error::NoSuchReading(id))
+    }
+
+    /// Add a note to the reading with the given ID.
+    pub(crate) fn add_note(&mut self, id: u64, note: String) -> Result<(), Error> {
+        let reading = self
+            .readings
+            .iter_mut()
+            .find(|reading| reading.id == id)
+            .ok_or(Error::NoSuchReading(id))?;
+        reading.notes.push(note);
+
+        Ok(())
+    }
+
+    /// Delete the reading with the given ID.
+    pub(crate) fn delete(&mut self, id: u64) -> Result<Reading, Error> {
+        let index = self
+            .readings
+            .iter()
+            .position(|reading| reading.id == id)
+            .ok_or(Error::NoSuchReading(id))?;
+
+        Ok(self.readings.remove(index))
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            version: JOURNAL_VERSION,
+            next_id: 1,
+            readings: Vec::new(),
+        }
+    }
+}
+
+/// Errors related to the [`Journal`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Couldn't find a data directory to store the journal in. Use --journal to choose a journal file")]
+    NoDataDirectory,
+    #[error("Failed to access journal at {}: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("Failed to read journal at {}: {1}", .0.display())]
+    Json(PathBuf, #[source] serde_json::Error),
+    #[error(
+        "Journal format version {0} is not supported. Only version {JOURNAL_VERSION} is supported"
+    )]
+    UnsupportedVersion(u32),
+    #[error("There is no reading with ID {0} in the journal")]
+    NoSuchReading(u64),
+    #[error("Reading {0} has invalid line values: {1}")]
+    InvalidLines(u64, #[source] iching::hexagram::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn new_reading(question: &str) -> NewReading {
+        NewReading {
+            timestamp: FixedOffset::east_opt(3600)
+                .unwrap()
+                .with_ymd_and_hms(2023, 1, 1, 12, 30, 0)
+                .unwrap(),
+            question: Some(question.to_owned()),
+            method: DivinationMethod::AncientYarrowStalk,
+            lines: [7, 8, 9, 7, 6, 8],
+            primary: 55,
+            relating: Some(17),
+        }
+    }
+
+    #[test]
+    fn test_ids_are_not_reused() {
+        let mut journal = Journal::default();
+        let first = journal.add(new_reading("first"));
+        journal.delete(first).unwrap();
+        let second = journal.add(new_reading("second"));
+
+        assert_ne!(first, second);
+        assert!(journal.get(first).is_err());
+        assert_eq!(
+            journal.get(second).unwrap().question.as_deref(),
+            Some("second")
+        );
+    }
+
+    #[test]
+    fn test_journal_round_trips_through_json() {
+        let mut journal = Journal::default();
+        let id = journal.add(new_reading("Should I start the project?"));
+        journal
+            .add_note(id, "Started it anyway.".to_owned())
+            .unwrap();
+
+        let json = serde_json::to_string(&journal).unwrap();
+        let journal: Journal = serde_json::from_str(&json).unwrap();
+        let reading = journal.get(id).unwrap();
+
+        assert_eq!(reading.lines, [7, 8, 9, 7, 6, 8]);
+        assert_eq!(reading.method, DivinationMethod::AncientYarrowStalk);
+        assert_eq!(reading.notes, vec!["Started it anyway.".to_owned()]);
+        assert_eq!(reading.timestamp.to_rfc3339(), "2023-01-01T12:30:00+01:00");
+    }
+
+    #[test]
+    fn test_newer_versions_are_rejected() {
+        let path = std::env::temp_dir().join(format!("iching-journal-{}.json", std::process::id()));
+        fs::write(&path, r#"{"version": 2, "next_id": 1, "readings": []}"#).unwrap();
+        let result = Journal::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(Error::UnsupportedVersion(2))));
+    }
+}
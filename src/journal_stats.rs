@@ -0,0 +1,597 @@
+use crate::{
+    hexagram_json::HexagramJsonInfo,
+    journal::{self, Reading},
+    locale::{Lang, Messages},
+    render::{self, Column, HeadingLevel, Render, Renderer, SectionRole},
+};
+use clap::ValueEnum;
+use iching::{
+    divination_method::DivinationMethod, hexagram_repository::HexagramRepository, line::Line,
+    statistics::ChiSquare, trigram::Trigram,
+};
+use num_traits::ToPrimitive;
+use serde_derive::Serialize;
+
+/// The line values that can be cast, in the order they're counted in.
+const LINE_VALUES: [u8; 4] = [6, 7, 8, 9];
+
+/// The probability of casting each of the [`LINE_VALUES`] with the given method.
+fn line_value_probabilities(method: DivinationMethod) -> [f64; 4] {
+    LINE_VALUES.map(|value| {
+        let line = Line::try_from(value).expect("line values are valid");
+        method
+            .line_probability(line)
+            .to_f64()
+            .expect("probabilities are representable as floats")
+    })
+}
+
+/// Frequencies of hexagrams, trigrams and lines across all readings in a journal.
+#[derive(Serialize)]
+pub(crate) struct JournalStatistics<'a> {
+    reading_count: u64,
+    /// Every hexagram that has appeared, in King Wen order
+    hexagrams: Vec<HexagramCount<'a>>,
+    /// Counts both trigrams of each primary hexagram, in [`Trigram::number`] order
+    trigrams: Vec<TrigramCount>,
+    /// In order of position, from the bottom line up
+    changing_lines: Vec<ChangingLineCount>,
+    methods: Vec<MethodStatistics>,
+    #[serde(skip)]
+    lang: Lang,
+}
+
+#[derive(Serialize)]
+struct HexagramCount<'a> {
+    number: usize,
+    symbol: &'a str,
+    english: &'a str,
+    as_primary: u64,
+    as_relating: u64,
+}
+
+#[derive(Serialize)]
+struct TrigramCount {
+    number: usize,
+    symbol: String,
+    pinyin: String,
+    english: String,
+    count: u64,
+}
+
+#[derive(Serialize)]
+struct ChangingLineCount {
+    position: usize,
+    count: u64,
+}
+
+/// Line value frequencies of the readings cast with a single method.
+#[derive(Serialize)]
+struct MethodStatistics {
+    method: DivinationMethod,
+    reading_count: u64,
+    line_values: Vec<LineValueCount>,
+    /// The line values compared against the odds of every method
+    comparisons: Vec<Comparison>,
+}
+
+#[derive(Serialize)]
+struct LineValueCount {
+    value: u8,
+    count: u64,
+    expected_probability: f64,
+}
+
+#[derive(Serialize)]
+struct Comparison {
+    method: DivinationMethod,
+    #[serde(flatten)]
+    chi_square: ChiSquare,
+}
+
+impl<'a> JournalStatistics<'a> {
+    pub(crate) fn new(
+        readings: &[Reading],
+        hexagrams: &'a impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+    ) -> Result<Self, journal::Error> {
+        let mut primary_counts = [0; 64];
+        let mut relating_counts = [0; 64];
+        let mut trigram_counts = [0; 8];
+        let mut changing_line_counts = [0; 6];
+        let mut method_counts: Vec<(DivinationMethod, u64, [u64; 4])> =
+            DivinationMethod::value_variants()
+                .iter()
+                .map(|&method| (method, 0, [0; 4]))
+                .collect();
+
+        for reading in readings {
+            let hexagram = reading.hexagram()?;
+
+            if let Some(count) = count_for_number(&mut primary_counts, reading.primary) {
+                *count += 1;
+            }
+            if let Some(count) = reading
+                .relating
+                .and_then(|relating| count_for_number(&mut relating_counts, relating))
+            {
+                *count += 1;
+            }
+
+            trigram_counts[hexagram.above().number() - 1] += 1;
+            trigram_counts[hexagram.below().number() - 1] += 1;
+
+            let (_, reading_count, line_value_counts) = method_counts
+                .iter_mut()
+                .find(|(method, _, _)| *method == reading.method)
+                .expect("all methods are included");
+            *reading_count += 1;
+
+            for (position, value) in reading.lines.iter().enumerate() {
+                if matches!(value, 6 | 9) {
+                    changing_line_counts[position] += 1;
+                }
+                if let Some(index) = LINE_VALUES.iter().position(|v| v == value) {
+                    line_value_counts[index] += 1;
+                }
+            }
+        }
+
+        Ok(JournalStatistics {
+            reading_count: readings.len() as u64,
+            hexagrams: (1..=64u8)
+                .zip(primary_counts.into_iter().zip(relating_counts))
+                .filter(|(_, (as_primary, as_relating))| as_primary + as_relating > 0)
+                .map(|(number, (as_primary, as_relating))| {
+                    let hexagram_info = hexagrams
+                        .get_by_number(number)
+                        .expect("repository contains all 64 hexagrams");
+                    HexagramCount {
+                        number: hexagram_info.number(),
+                        symbol: hexagram_info.symbol(),
+                        english: hexagram_info.english_name(),
+                        as_primary,
+                        as_relating,
+                    }
+                })
+                .collect(),
+            trigrams: trigram_counts
+                .into_iter()
+                .enumerate()
+                .map(|(index, count)| {
+                    let trigram = Trigram::try_from(index + 1).expect("trigram numbers are 1-8");
+                    TrigramCount {
+                        number: trigram.number(),
+                        symbol: trigram.symbol().to_owned(),
+                        pinyin: trigram.pinyin(),
+                        english: trigram.english(),
+                        count,
+                    }
+                })
+                .collect(),
+            changing_lines: changing_line_counts
+                .into_iter()
+                .enumerate()
+                .map(|(index, count)| ChangingLineCount {
+                    position: index + 1,
+                    count,
+                })
+                .collect(),
+            methods: method_counts
+                .into_iter()
+                .map(|(method, reading_count, line_value_counts)| {
+                    MethodStatistics::new(method, reading_count, line_value_counts)
+                })
+                .collect(),
+            lang: Lang::default(),
+        })
+    }
+
+    /// Show the headings and conclusions in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+}
+
+impl Render for JournalStatistics<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().get("statistics-title")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("statistics-readings"),
+        )?;
+        let mut rows: Vec<Vec<String>> = self
+            .methods
+            .iter()
+            .map(|method_statistics| {
+                vec![
+                    localized_method_name(messages, method_statistics.method),
+                    method_statistics.reading_count.to_string(),
+                ]
+            })
+            .collect();
+        rows.push(vec![
+            messages.get("statistics-total"),
+            self.reading_count.to_string(),
+        ]);
+        renderer.table(
+            &[
+                Column::left(&messages.get("column-method")),
+                Column::right(&messages.get("column-readings")),
+            ],
+            &rows,
+        )?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("statistics-hexagrams"),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .hexagrams
+            .iter()
+            .map(|hexagram| {
+                vec![
+                    format!("{} {}", hexagram.symbol, hexagram.number),
+                    hexagram.as_primary.to_string(),
+                    hexagram.as_relating.to_string(),
+                    hexagram.english.to_owned(),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-hexagram")),
+                Column::right(&messages.get("column-as-primary")),
+                Column::right(&messages.get("column-as-relating")),
+                Column::left(&messages.get("column-name")),
+            ],
+            &rows,
+        )?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("statistics-trigrams"),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .trigrams
+            .iter()
+            .map(|trigram| {
+                vec![
+                    trigram.symbol.clone(),
+                    trigram.pinyin.clone(),
+                    trigram.english.clone(),
+                    trigram.count.to_string(),
+                    percentage(trigram.count, self.reading_count * 2),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::left(&messages.get("column-trigram")),
+                Column::left(&messages.get("column-pinyin")),
+                Column::left(&messages.get("column-name")),
+                Column::right(&messages.get("column-count")),
+                Column::right(&messages.get("column-share")),
+            ],
+            &rows,
+        )?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("statistics-changing-lines"),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .changing_lines
+            .iter()
+            .map(|line| {
+                vec![
+                    line.position.to_string(),
+                    line.count.to_string(),
+                    percentage(line.count, self.reading_count),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-line")),
+                Column::right(&messages.get("column-count")),
+                Column::right(&messages.get("column-share")),
+            ],
+            &rows,
+        )?;
+
+        for method_statistics in &self.methods {
+            if method_statistics.reading_count == 0 {
+                continue;
+            }
+            method_statistics.render(renderer, messages)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MethodStatistics {
+    fn new(method: DivinationMethod, reading_count: u64, line_value_counts: [u64; 4]) -> Self {
+        MethodStatistics {
+            method,
+            reading_count,
+            line_values: LINE_VALUES
+                .iter()
+                .zip(line_value_counts)
+                .zip(line_value_probabilities(method))
+                .map(|((&value, count), expected_probability)| LineValueCount {
+                    value,
+                    count,
+                    expected_probability,
+                })
+                .collect(),
+            comparisons: DivinationMethod::value_variants()
+                .iter()
+                .map(|&other_method| Comparison {
+                    method: other_method,
+                    chi_square: ChiSquare::goodness_of_fit(
+                        &line_value_counts,
+                        &line_value_probabilities(other_method),
+                    ),
+                })
+                .collect(),
+        }
+    }
+
+    fn render(
+        &self,
+        renderer: &mut dyn Renderer,
+        messages: &Messages,
+    ) -> Result<(), render::Error> {
+        let line_count: u64 = self.line_values.iter().map(|line| line.count).sum();
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.format(
+                "statistics-line-values",
+                &[
+                    ("method", method_name(self.method).into()),
+                    ("count", line_count.into()),
+                ],
+            ),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .line_values
+            .iter()
+            .map(|line| {
+                vec![
+                    line.value.to_string(),
+                    line.count.to_string(),
+                    percentage(line.count, line_count),
+                    format!("{:.1}%", line.expected_probability * 100.0),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-value")),
+                Column::right(&messages.get("column-count")),
+                Column::right(&messages.get("column-share")),
+                Column::right(&messages.get("column-expected")),
+            ],
+            &rows,
+        )?;
+
+        let comparisons: Vec<String> = self
+            .comparisons
+            .iter()
+            .map(|comparison| {
+                let chi_square = &comparison.chi_square;
+                let result = messages.format(
+                    "statistics-comparison",
+                    &[
+                        ("method", method_name(comparison.method).into()),
+                        ("statistic", format!("{:.2}", chi_square.statistic).into()),
+                        ("degrees", chi_square.degrees_of_freedom.into()),
+                        ("p", format!("{:.4}", chi_square.p_value).into()),
+                    ],
+                );
+                format!("{result}\n{}", comparison.conclusion(messages))
+            })
+            .collect();
+        renderer.list(&comparisons)
+    }
+}
+
+impl Comparison {
+    /// Describe what the chi-squared test says about the lines.
+    fn conclusion(&self, messages: &Messages) -> String {
+        let id = if self.chi_square.min_expected < 5.0 {
+            "statistics-too-few-lines"
+        } else if self.chi_square.p_value < 0.05 {
+            "statistics-unlikely"
+        } else {
+            "statistics-consistent"
+        };
+        messages.get(id)
+    }
+}
+
+/// Get the count of the hexagram with the given King Wen number, if the number is valid.
+fn count_for_number(counts: &mut [u64; 64], number: usize) -> Option<&mut u64> {
+    number
+        .checked_sub(1)
+        .and_then(|index| counts.get_mut(index))
+}
+
+/// Get the name of a method as it's written on the command line.
+pub(crate) fn method_name(method: DivinationMethod) -> String {
+    method
+        .to_possible_value()
+        .expect("divination methods are never skipped")
+        .get_name()
+        .to_owned()
+}
+
+/// Get the name of a method in the given language, e.g. `coin-toss` in English.
+fn localized_method_name(messages: &Messages, method: DivinationMethod) -> String {
+    messages.format("method", &[("method", method_name(method).into())])
+}
+
+fn percentage(count: u64, total: u64) -> String {
+    if total == 0 {
+        String::from("-")
+    } else {
+        format!("{:.1}%", count as f64 / total as f64 * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+    use chrono::{FixedOffset, TimeZone};
+
+    fn reading(
+        method: DivinationMethod,
+        lines: [u8; 6],
+        primary: usize,
+        relating: Option<usize>,
+    ) -> Reading {
+        Reading {
+            id: 1,
+            timestamp: FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(2023, 1, 1, 12, 0, 0)
+                .unwrap(),
+            question: None,
+            method,
+            lines,
+            primary,
+            relating,
+            notes: Vec::new(),
+        }
+    }
+
+    fn hexagrams() -> HexagramJson {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        hexagrams
+    }
+
+    fn comparison(statistics: &MethodStatistics, method: DivinationMethod) -> &Comparison {
+        statistics
+            .comparisons
+            .iter()
+            .find(|comparison| comparison.method == method)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hexagrams_trigrams_and_lines_are_counted() {
+        use DivinationMethod::*;
+
+        let readings = [
+            reading(AncientYarrowStalk, [7, 7, 7, 7, 7, 7], 1, None),
+            reading(CoinToss, [9, 7, 7, 7, 7, 7], 1, Some(44)),
+            reading(AncientYarrowStalk, [8, 8, 8, 8, 6, 8], 2, Some(8)),
+        ];
+        let hexagrams = hexagrams();
+        let statistics = JournalStatistics::new(&readings, &hexagrams).unwrap();
+
+        assert_eq!(statistics.reading_count, 3);
+        let hexagram_counts: Vec<_> = statistics
+            .hexagrams
+            .iter()
+            .map(|hexagram| (hexagram.number, hexagram.as_primary, hexagram.as_relating))
+            .collect();
+        assert_eq!(
+            hexagram_counts,
+            [(1, 2, 0), (2, 1, 0), (8, 0, 1), (44, 0, 1)]
+        );
+
+        let trigram_counts: Vec<_> = statistics
+            .trigrams
+            .iter()
+            .map(|trigram| trigram.count)
+            .collect();
+        assert_eq!(trigram_counts, [4, 0, 0, 0, 0, 0, 0, 2]);
+
+        let changing_line_counts: Vec<_> = statistics
+            .changing_lines
+            .iter()
+            .map(|line| (line.position, line.count))
+            .collect();
+        assert_eq!(
+            changing_line_counts,
+            [(1, 1), (2, 0), (3, 0), (4, 0), (5, 1), (6, 0)]
+        );
+
+        let yarrow = &statistics.methods[0];
+        assert_eq!(yarrow.method, AncientYarrowStalk);
+        assert_eq!(yarrow.reading_count, 2);
+        let line_value_counts: Vec<_> = yarrow
+            .line_values
+            .iter()
+            .map(|line| (line.value, line.count))
+            .collect();
+        assert_eq!(line_value_counts, [(6, 1), (7, 6), (8, 5), (9, 0)]);
+
+        let coins = &statistics.methods[1];
+        assert_eq!(coins.method, CoinToss);
+        assert_eq!(coins.reading_count, 1);
+        let line_value_counts: Vec<_> = coins
+            .line_values
+            .iter()
+            .map(|line| (line.value, line.count))
+            .collect();
+        assert_eq!(line_value_counts, [(6, 0), (7, 5), (8, 0), (9, 1)]);
+        assert_eq!(
+            comparison(coins, CoinToss).conclusion(Lang::En.messages()),
+            "Too few lines have been cast for this comparison to be reliable."
+        );
+    }
+
+    #[test]
+    fn test_lines_are_compared_against_each_method() {
+        use DivinationMethod::*;
+
+        // 96 lines in exactly the yarrow stalk proportions of 1:5:7:3 sixes, sevens, eights and
+        // nines. Against the coin toss odds of 2:6:6:2 that's a χ² of 8 with 3 degrees of freedom,
+        // just past the 5% significance level.
+        let readings: Vec<Reading> = std::iter::repeat_n([6, 7, 8, 8, 8, 9], 6)
+            .chain(std::iter::repeat_n([7, 7, 8, 8, 9, 9], 6))
+            .chain(std::iter::repeat_n([7, 7, 7, 8, 8, 8], 4))
+            .map(|lines| reading(AncientYarrowStalk, lines, 1, None))
+            .collect();
+        let hexagrams = hexagrams();
+        let statistics = JournalStatistics::new(&readings, &hexagrams).unwrap();
+        let yarrow = &statistics.methods[0];
+
+        let line_value_counts: Vec<_> = yarrow.line_values.iter().map(|line| line.count).collect();
+        assert_eq!(line_value_counts, [6, 30, 42, 18]);
+
+        let against_yarrow = comparison(yarrow, AncientYarrowStalk);
+        assert!(against_yarrow.chi_square.statistic.abs() < 1e-9);
+        assert_eq!(
+            against_yarrow.conclusion(Lang::En.messages()),
+            "These lines are consistent with this method."
+        );
+
+        let against_coins = comparison(yarrow, CoinToss);
+        assert!((against_coins.chi_square.statistic - 8.0).abs() < 1e-9);
+        assert_eq!(against_coins.chi_square.degrees_of_freedom, 3);
+        assert!(against_coins.chi_square.p_value < 0.05);
+        assert!(against_coins.chi_square.p_value > 0.04);
+        assert_eq!(
+            against_coins.conclusion(Lang::En.messages()),
+            "These lines are unlikely to have been cast with this method."
+        );
+    }
+}
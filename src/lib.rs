@@ -61,6 +61,8 @@
 //! print!("{hexagram_info:?}");
 //! ```
 
+/// Reproducible "hexagram of the day" generation.
+pub mod daily;
 /// Types related to the various methods of divination.
 pub mod divination_method;
 /// `Hexagram`s are used for divination in the I Ching.
@@ -68,9 +70,19 @@ pub mod divination_method;
 pub mod hexagram;
 /// Contains traits for implementing generic repositories of hexagram meanings.
 pub mod hexagram_repository;
+/// Rules for which line texts of a reading to consider, and how lines are named.
+pub mod interpretation;
 /// `Line`s are the building blocks of `Hexagram`s and `Trigram`s.
 /// This module contains ways of randomly generating lines
 pub mod line;
+/// Exact probabilities of casting lines and hexagrams with each method of divination.
+pub mod probability;
+/// Full-text search over passages of text, such as the texts of the hexagrams.
+pub mod search;
+/// Statistical tools for checking how well cast lines match their expected distribution.
+pub mod statistics;
+/// Drawing of hexagrams, trigrams and whole readings as SVG images.
+pub mod svg;
 /// A collection of various I-Ching related symbols, in unicode or ASCII-art form.
 pub mod symbols;
 /// `Trigram`s are the building blocks of `Hexagrams`.
@@ -1,6 +1,9 @@
-use crate::divination_method::DivinationMethod;
+use crate::divination_method::{DivinationMethod, LINE_OUTCOMES};
 use num_bigint::BigInt;
-use std::fmt;
+use std::{
+    fmt,
+    io::{self, Write},
+};
 
 /// `Line` represents an individual line within a trigram or hexagram. Hexagrams and trigrams can
 /// "change" into other hexagrams and trigrams based on which lines are marked as "changing".
@@ -12,10 +15,23 @@ pub enum Line {
 
 impl Line {
     pub fn new_random(divination_method: DivinationMethod) -> Self {
-        match divination_method {
-            DivinationMethod::CoinToss => Self::from_coin_tosses(),
-            DivinationMethod::AncientYarrowStalk => Self::from_yarrow_stalks(),
+        Self::new_random_with_rng(divination_method, &fastrand::Rng::new())
+    }
+
+    /// Generate a new random `Line`, drawing from the given [`fastrand::Rng`]. Using a seeded
+    /// `Rng` makes the generated line reproducible.
+    ///
+    /// Lines are drawn with the odds given by [`DivinationMethod::line_probabilities`].
+    pub fn new_random_with_rng(divination_method: DivinationMethod, rng: &fastrand::Rng) -> Self {
+        let mut roll = rng.u8(1..=LINE_OUTCOMES);
+        for (line, weight) in divination_method.line_weights() {
+            if roll <= weight {
+                return line;
+            }
+            roll -= weight;
         }
+
+        unreachable!("line weights add up to {LINE_OUTCOMES}")
     }
 
     pub fn is_changing(&self) -> bool {
@@ -24,6 +40,17 @@ impl Line {
         }
     }
 
+    /// Get the numeric value of this `Line`, as produced by the traditional methods of divination:
+    /// 6 for old yin, 7 for young yang, 8 for young yin and 9 for old yang.
+    pub fn value(&self) -> u8 {
+        match self {
+            Self::Broken { changing: true } => 6,
+            Self::Unbroken { changing: false } => 7,
+            Self::Broken { changing: false } => 8,
+            Self::Unbroken { changing: true } => 9,
+        }
+    }
+
     /// young yin a.k.a. yin unchanging
     pub const fn broken() -> Self {
         Self::Broken { changing: false }
@@ -47,33 +74,25 @@ impl Line {
     // Generate a new `Line` by using the coin toss method.
     // https://en.wikipedia.org/wiki/I_Ching_divination#Coins
     pub fn from_coin_tosses() -> Self {
-        match fastrand::u8(1..=16) {
-            // 2/16
-            1..=2 => Line::broken_changing(),
-            // 6/16
-            3..=8 => Line::broken(),
-            // 2/16
-            9..=10 => Line::unbroken_changing(),
-            // 6/16
-            11..=16 => Line::unbroken(),
-            _ => unreachable!(),
-        }
+        Self::from_coin_tosses_with_rng(&fastrand::Rng::new())
+    }
+
+    /// Generate a new `Line` by using the coin toss method, drawing from the given
+    /// [`fastrand::Rng`].
+    pub fn from_coin_tosses_with_rng(rng: &fastrand::Rng) -> Self {
+        Self::new_random_with_rng(DivinationMethod::CoinToss, rng)
     }
 
     // Generate a new `Line`s with a random distribution based on the ancient yarrow stalk method.
     // https://en.wikipedia.org/wiki/I_Ching_divination#Yarrow_stalks
     pub fn from_yarrow_stalks() -> Self {
-        match fastrand::u8(1..=16) {
-            // 1/16
-            1 => Line::broken_changing(),
-            // 7/16
-            2..=8 => Line::broken(),
-            // 3/16
-            9..=11 => Line::unbroken_changing(),
-            // 5/16
-            12..=16 => Line::unbroken(),
-            _ => unreachable!(),
-        }
+        Self::from_yarrow_stalks_with_rng(&fastrand::Rng::new())
+    }
+
+    /// Generate a new `Line` by using the yarrow stalk method, drawing from the given
+    /// [`fastrand::Rng`].
+    pub fn from_yarrow_stalks_with_rng(rng: &fastrand::Rng) -> Self {
+        Self::new_random_with_rng(DivinationMethod::AncientYarrowStalk, rng)
     }
 
     /// `settle` a line that might be "changing". If the line is "changing", it will be settled to
@@ -87,15 +106,52 @@ impl Line {
         }
     }
 
+    /// Get this line as it appears in a primary hexagram, which is this line without its
+    /// "changing" mark. Compare with [`Line::settle`], which gives the line as it appears in a
+    /// relating hexagram.
+    pub fn primary(&self) -> Line {
+        match self {
+            Self::Broken { .. } => Self::broken(),
+            Self::Unbroken { .. } => Self::unbroken(),
+        }
+    }
+
+    /// Get the opposite of this line: yang for a yin line and yin for a yang line. The opposite
+    /// line is never "changing".
+    pub fn opposite(&self) -> Line {
+        match self {
+            Self::Broken { .. } => Self::unbroken(),
+            Self::Unbroken { .. } => Self::broken(),
+        }
+    }
+
     /// Print the `Line` as large ASCII art.
     pub fn print_big(&self) {
+        self.write_big(&mut std::io::stdout().lock())
+            .expect("stdout is writable");
+    }
+
+    /// Write the `Line` as large ASCII art.
+    pub fn write_big<W: Write + ?Sized>(&self, output: &mut W) -> io::Result<()> {
+        for row in self.big_rows() {
+            writeln!(output, "{row}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the rows of this `Line`'s large ASCII art, from top to bottom. Every row has the same
+    /// width.
+    pub fn big_rows(&self) -> impl Iterator<Item = &'static str> {
         use crate::symbols::big_line::*;
-        match self {
-            Self::Broken { changing: true } => print!("{BROKEN_CHANGING}"),
-            Self::Broken { changing: false } => print!("{BROKEN}"),
-            Self::Unbroken { changing: true } => print!("{UNBROKEN_CHANGING}"),
-            Self::Unbroken { changing: false } => print!("{UNBROKEN}"),
+        let art = match self {
+            Self::Broken { changing: true } => BROKEN_CHANGING,
+            Self::Broken { changing: false } => BROKEN,
+            Self::Unbroken { changing: true } => UNBROKEN_CHANGING,
+            Self::Unbroken { changing: false } => UNBROKEN,
         };
+
+        rows(art)
     }
 
     fn try_from<N>(n: N) -> Result<Self, Error>
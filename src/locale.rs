@@ -0,0 +1,226 @@
+//! The languages that the app's messages and labels are shown in.
+//!
+//! Messages are kept in a [Fluent](https://projectfluent.org) file for each language, in
+//! `src/locales`, which are built into the app. Messages that a language doesn't translate are
+//! shown in English. The texts of the hexagrams themselves aren't translated here.
+
+use clap::ValueEnum;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use iching::{
+    hexagram::HexagramOrdering,
+    trigram::{Trigram, TrigramName},
+};
+use serde_derive::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+const ENGLISH: &str = include_str!("locales/en.ftl");
+const SIMPLIFIED_CHINESE: &str = include_str!("locales/zh-Hans.ftl");
+const TRADITIONAL_CHINESE: &str = include_str!("locales/zh-Hant.ftl");
+
+/// The languages that messages are translated into.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+pub(crate) enum Lang {
+    /// English
+    #[default]
+    #[value(name = "en")]
+    #[serde(rename = "en")]
+    En,
+    /// Simplified Chinese
+    #[value(name = "zh-Hans", alias = "zh-CN")]
+    #[serde(rename = "zh-Hans")]
+    ZhHans,
+    /// Traditional Chinese
+    #[value(name = "zh-Hant", alias = "zh-TW")]
+    #[serde(rename = "zh-Hant")]
+    ZhHant,
+}
+
+impl Lang {
+    /// Pick the language of the user's locale, as set by `LANG` and the like on Unix, or English
+    /// if it isn't one that messages are translated into.
+    pub(crate) fn from_environment() -> Self {
+        sys_locale::get_locale()
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// Pick the language of a locale such as `zh_TW.UTF-8`, `zh-Hans-SG` or `en-US`.
+    pub(crate) fn from_locale(locale: &str) -> Option<Self> {
+        // Drop the encoding and modifier, e.g. `.UTF-8` and `@euro`.
+        let locale = locale.split(['.', '@']).next()?.replace('_', "-");
+        let mut subtags = locale.split('-').map(str::to_ascii_lowercase);
+        match subtags.next()?.as_str() {
+            "en" => Some(Lang::En),
+            "zh" => {
+                // The script says which characters to use. Without one, the region decides.
+                let subtags: Vec<String> = subtags.collect();
+                let has =
+                    |wanted: &[&str]| subtags.iter().any(|subtag| wanted.contains(&&**subtag));
+                let traditional = !has(&["hans"]) && has(&["hant", "tw", "hk", "mo"]);
+                Some(if traditional {
+                    Lang::ZhHant
+                } else {
+                    Lang::ZhHans
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the BCP 47 tag of this language, e.g. `zh-Hant`.
+    pub(crate) fn tag(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::ZhHans => "zh-Hans",
+            Lang::ZhHant => "zh-Hant",
+        }
+    }
+
+    /// Get the messages of this language.
+    pub(crate) fn messages(self) -> &'static Messages {
+        static MESSAGES: [OnceLock<Messages>; 3] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        let (index, translations) = match self {
+            Lang::En => (0, None),
+            Lang::ZhHans => (1, Some(SIMPLIFIED_CHINESE)),
+            Lang::ZhHant => (2, Some(TRADITIONAL_CHINESE)),
+        };
+        MESSAGES[index].get_or_init(|| Messages::new(self.tag(), translations))
+    }
+}
+
+/// Get the name of an ordering as it's written on the command line, which is how the `ordering`
+/// message picks its translation.
+pub(crate) fn ordering_name(ordering: HexagramOrdering) -> String {
+    ordering
+        .to_possible_value()
+        .expect("orderings are never skipped")
+        .get_name()
+        .to_owned()
+}
+
+/// The messages of one language, looked up by their IDs in the Fluent files.
+pub(crate) struct Messages {
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Messages {
+    fn new(tag: &str, translations: Option<&str>) -> Self {
+        let language: LanguageIdentifier = tag.parse().expect("language tags are valid");
+        let mut bundle = FluentBundle::new_concurrent(vec![language]);
+        // Unicode isolation marks around arguments show up as junk in many terminals.
+        bundle.set_use_isolating(false);
+
+        let resource = |source: &str| {
+            FluentResource::try_new(source.to_owned()).expect("the built-in messages are valid")
+        };
+        bundle
+            .add_resource(resource(ENGLISH))
+            .expect("English messages are only defined once");
+        if let Some(translations) = translations {
+            bundle.add_resource_overriding(resource(translations));
+        }
+
+        Messages { bundle }
+    }
+
+    /// Get a message that takes no arguments.
+    pub(crate) fn get(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+
+    /// Get a message, filling in its arguments.
+    pub(crate) fn format(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        self.format_attribute(id, None, args)
+    }
+
+    /// Get a message's value or, if an attribute is given, the attribute's value.
+    fn format_attribute(
+        &self,
+        id: &str,
+        attribute: Option<&str>,
+        args: &[(&str, FluentValue)],
+    ) -> String {
+        let message = self
+            .bundle
+            .get_message(id)
+            .unwrap_or_else(|| panic!("there's no message {id:?}"));
+        let pattern = match attribute {
+            Some(attribute) => message
+                .get_attribute(attribute)
+                .unwrap_or_else(|| panic!("the message {id:?} has no attribute {attribute:?}"))
+                .value(),
+            None => message
+                .value()
+                .unwrap_or_else(|| panic!("the message {id:?} has no value")),
+        };
+        let args: FluentArgs = args.iter().cloned().collect();
+        let mut errors = Vec::new();
+        let text = self
+            .bundle
+            .format_pattern(pattern, Some(&args), &mut errors);
+        debug_assert!(errors.is_empty(), "failed to format {id:?}: {errors:?}");
+
+        text.into_owned()
+    }
+
+    /// Get the name, attribute, image in nature or family relationship of a trigram, by the
+    /// attribute of its message (`None` for the name).
+    pub(crate) fn trigram(&self, trigram: &Trigram, attribute: Option<&str>) -> String {
+        let id = match TrigramName::from(trigram) {
+            TrigramName::Qian => "trigram-qian",
+            TrigramName::Kun => "trigram-kun",
+            TrigramName::Zhen => "trigram-zhen",
+            TrigramName::Kan => "trigram-kan",
+            TrigramName::Gen => "trigram-gen",
+            TrigramName::Xun => "trigram-xun",
+            TrigramName::Li => "trigram-li",
+            TrigramName::Dui => "trigram-dui",
+        };
+        self.format_attribute(id, attribute, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the IDs of the messages in a Fluent file.
+    fn message_ids(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_message_is_translated() {
+        let english = message_ids(ENGLISH);
+        for translations in [SIMPLIFIED_CHINESE, TRADITIONAL_CHINESE] {
+            assert_eq!(message_ids(translations), english);
+        }
+
+        let args = [("number", FluentValue::from(3)), ("symbol", "䷂".into())];
+        assert_eq!(
+            Lang::En.messages().format("hexagram-title", &args),
+            "Hexagram No. 3  ䷂"
+        );
+        assert_eq!(
+            Lang::ZhHant.messages().format("hexagram-title", &args),
+            "第3卦  ䷂"
+        );
+    }
+
+    #[test]
+    fn test_languages_are_picked_from_locales() {
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("zh_CN.UTF-8"), Some(Lang::ZhHans));
+        assert_eq!(Lang::from_locale("zh-Hans-HK"), Some(Lang::ZhHans));
+        assert_eq!(Lang::from_locale("zh_TW"), Some(Lang::ZhHant));
+        assert_eq!(Lang::from_locale("zh-Hant"), Some(Lang::ZhHant));
+        assert_eq!(Lang::from_locale("fr_FR"), None);
+        assert_eq!(Lang::from_locale("C"), None);
+    }
+}
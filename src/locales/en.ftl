@@ -0,0 +1,244 @@
+# The app's messages in English. Every message here must also be in the other languages' files,
+# in the same order.
+
+## Hexagrams
+
+hexagram-title = Hexagram No. { $number }  { $symbol }
+judgement = Judgement
+images = Images
+lines = Lines
+# A line by its position, from 1 at the bottom to 6 at the top.
+line-position = Line { $position }
+# A line by its traditional name. $value is yang-line-value or yin-line-value.
+line-traditional = { $value } { $position ->
+        [1] at the beginning
+        [2] in the second place
+        [3] in the third place
+        [4] in the fourth place
+        [5] in the fifth place
+       *[6] at the top
+    }
+yang-line-value = Nine
+yin-line-value = Six
+
+## Commentaries
+
+tuan = Commentary on the Judgement
+# A line's Small Image, the commentary on the line.
+small-image = The image: { $text }
+wenyan = Commentary on the Words of the Text
+sequence = The Sequence of the Hexagrams
+miscellaneous = Miscellaneous Notes
+
+## Trigrams
+
+trigram-title = Trigram No. { $number }  { $symbol }
+attribute = Attribute
+image-in-nature = Image in nature
+family-relationship = Family Relationship
+trigram-qian = The Creative
+    .attribute = strong, persisting
+    .image = heaven, sky
+    .family = father
+trigram-kun = The Receptive
+    .attribute = devoted, yielding
+    .image = earth, ground
+    .family = mother
+trigram-zhen = The Arousing
+    .attribute = inciting movement
+    .image = thunder
+    .family = first son
+trigram-kan = The Abysmal
+    .attribute = dangerous
+    .image = water
+    .family = second son
+trigram-gen = Keeping Still
+    .attribute = resting, stand-still
+    .image = mountain
+    .family = third son
+trigram-xun = The Gentle
+    .attribute = penetrating
+    .image = wind, air
+    .family = first daughter
+trigram-li = The Clinging
+    .attribute = light-giving
+    .image = fire, glow
+    .family = second daughter
+trigram-dui = The Joyous
+    .attribute = pleasure
+    .image = lake, marsh
+    .family = third daughter
+
+## Readings
+
+hexagram-of-the-day = Hexagram of the day for { $date }
+question = Q: { $question }
+lines-are-changing = Lines are changing! Consider:
+# $label is the line's label, e.g. "Line 3".
+line-changes = { $label } changes
+relating-line = { $label } of the relating hexagram
+changes-into = Changes into:
+# Zhu Xi's advice for readings where the judgements are read instead of any line.
+zhu-xi-advice = { $changing ->
+        [3] With three changing lines, Zhu Xi reads the judgements of both hexagrams, with the primary hexagram's as the main one.
+       *[6] With every line changing, Zhu Xi reads the judgement of the relating hexagram.
+    }
+method = { $method ->
+        [coin-toss] coin-toss
+       *[ancient-yarrow-stalk] ancient-yarrow-stalk
+    }
+
+## The journal
+
+reading-title = Reading { $id }
+reading-heading = Reading { $id } from { $timestamp }, cast with the { method } method
+notes = Notes
+journal-title = Journal
+journal-empty = The journal is empty. Readings are stored here after using `divine`.
+reading-deleted = Deleted reading { $id }.
+
+## Journal statistics
+
+statistics-title = Journal statistics
+statistics-readings = Readings
+statistics-total = total
+statistics-hexagrams = Hexagrams
+statistics-trigrams = Trigrams in primary hexagrams
+statistics-changing-lines = Changing lines (from the bottom line up)
+statistics-line-values = Line values cast with the { method } method ({ $count } lines)
+# $statistic, $degrees and $p are the results of a chi-squared test.
+statistics-comparison = Compared to the { method } method: χ² = { $statistic } ({ $degrees } degrees of freedom), p = { $p }
+statistics-too-few-lines = Too few lines have been cast for this comparison to be reliable.
+statistics-unlikely = These lines are unlikely to have been cast with this method.
+statistics-consistent = These lines are consistent with this method.
+
+## Odds
+
+odds-title = Odds of the { method } method
+odds-lines = Lines
+# A kind of line by its value.
+odds-line-name = { $value ->
+        [6] old yin
+        [7] young yang
+        [8] young yin
+       *[9] old yang
+    }
+odds-moving-lines = Number of moving lines
+odds-primary-hexagrams = Primary hexagrams
+odds-primary-hexagram = Primary hexagram { $hexagram } { $name }: { $probability }
+odds-relating-hexagrams = Relating hexagrams
+odds-relating-explanation = The odds of receiving both the primary and the relating hexagram.
+# Stands in for the relating hexagram when no lines are changing.
+odds-no-relating = (none)
+
+## Table columns
+
+column-id = ID
+column-date = Date
+column-primary = Primary
+column-relating = Relating
+column-question = Question
+column-method = Method
+column-readings = Readings
+column-hexagram = Hexagram
+column-as-primary = As primary
+column-as-relating = As relating
+column-name = Name
+column-trigram = Trigram
+column-pinyin = Pinyin
+column-count = Count
+column-share = Share
+column-line = Line
+column-value = Value
+column-expected = Expected
+column-probability = Probability
+column-moving-lines = Moving lines
+column-setting = Setting
+column-number = #
+column-command = Command
+column-changes-into = Changes into
+
+## Search
+
+search-title = Search results for "{ $query }"
+search-nothing-found = Nothing matches every word of the search.
+search-best-results = Showing the best { $shown } of { $total } results.
+# The part of a hexagram's text that a search result comes from.
+search-passage = { $passage ->
+        [name] name
+        [judgement] judgement
+        [images] images
+        [classical-judgement] original judgement
+        [classical-images] original images
+        [classical-line] original line { $position }
+       *[line] line { $position }
+    }
+
+## Settings
+
+settings-title = Settings
+settings-read-from = Read from { $path }.
+settings-no-config-file-at = There's no config file at { $path }, so these are the defaults.
+settings-no-config-file = There's no config file.
+settings-built-in-data = the text built into the app
+settings-not-wrapped = not wrapped
+settings-terminal-width = the width of the terminal
+settings-none = none
+
+## Books
+
+book-written = Wrote the book to { $path }.
+export-written = Exported the full text to { $path }.
+book-title = The I Ching
+book-subtitle = The Book of Changes
+book-contents = Contents
+book-all-hexagrams = All hexagrams
+book-hexagrams = Hexagrams
+book-trigrams = Trigrams
+# An ordering of the hexagrams, by its name on the command line.
+ordering = { $ordering ->
+        [binary] binary (Fu Xi)
+        [mawangdui] Mawangdui
+       *[king-wen] King Wen
+    }
+book-ordering = In the { ordering } sequence.
+book-above = Above
+book-below = Below
+book-related = Related hexagrams
+book-nuclear = Nuclear
+book-inverse = Inverse
+book-complement = Complement
+book-sequence = Sequence
+book-previous = Previous
+book-next = Next
+book-hexagrams-above = Hexagrams with this trigram above
+book-hexagrams-below = Hexagrams with this trigram below
+
+## The browser
+
+browse-grid = Hexagrams, { ordering } sequence
+browse-lines = Lines and relations
+browse-help = arrows move · 1-6 flip a line · n/i/c related hexagrams · o ordering · PgUp/PgDn scroll · q quit
+
+## The shell
+
+repl-welcome = Explore how hexagrams change. Start with `cast` or e.g. `lines 789678`, then try `flip 3`, `relate`, `nuclear` or `invert`. Type `help` for every command, and `quit` to leave.
+repl-saved = Saved to { $path }.
+repl-history = History
+repl-nothing-cast = Nothing has been cast yet.
+
+## Casting step by step
+
+casting-question = Take a moment to think about what you want to ask.
+    Your question (or Enter to cast without one):
+casting-begin = The hexagram is cast from the bottom line up. Keep your question in mind.
+casting-cast-line = Press Enter to cast line { $position }, or type in your own cast:
+casting-toss-coin = Press Enter to toss coin { $coin } of line { $position }, or type in your own toss:
+casting-invalid-entry = Type h or t for a coin, three coins such as hht or 3 3 2, or a line's value from 6 to 9. Or just press Enter.
+casting-whole-line = The yarrow stalks cast a whole line at once, so type the line's value from 6 to 9, or three coins.
+casting-single-coin = This line's tosses have begun, so type a single coin.
+casting-coin = { $side ->
+        [heads] heads
+       *[tails] tails
+    }
+casting-line = Line { $position } is { $value }, { odds-line-name }.
@@ -0,0 +1,233 @@
+# 本应用的简体中文消息。消息须与 en.ftl 一一对应，顺序相同。
+
+## 卦
+
+hexagram-title = 第{ $number }卦  { $symbol }
+judgement = 卦辞
+images = 象辞
+lines = 爻辞
+line-position = 第{ $position }爻
+line-traditional = { $position ->
+        [1] 初{ $value }
+        [2] { $value }二
+        [3] { $value }三
+        [4] { $value }四
+        [5] { $value }五
+       *[6] 上{ $value }
+    }
+yang-line-value = 九
+yin-line-value = 六
+
+## 十翼
+
+tuan = 彖传
+small-image = 象曰：{ $text }
+wenyan = 文言
+sequence = 序卦
+miscellaneous = 杂卦
+
+## 经卦
+
+trigram-title = 经卦 { $number }  { $symbol }
+attribute = 卦德
+image-in-nature = 卦象
+family-relationship = 家人
+trigram-qian = 创造
+    .attribute = 健
+    .image = 天
+    .family = 父
+trigram-kun = 接受
+    .attribute = 顺
+    .image = 地
+    .family = 母
+trigram-zhen = 震动
+    .attribute = 动
+    .image = 雷
+    .family = 长男
+trigram-kan = 深渊
+    .attribute = 陷
+    .image = 水
+    .family = 中男
+trigram-gen = 静止
+    .attribute = 止
+    .image = 山
+    .family = 少男
+trigram-xun = 柔顺
+    .attribute = 入
+    .image = 风
+    .family = 长女
+trigram-li = 附丽
+    .attribute = 丽
+    .image = 火
+    .family = 中女
+trigram-dui = 喜悦
+    .attribute = 说
+    .image = 泽
+    .family = 少女
+
+## 占卜
+
+hexagram-of-the-day = { $date } 的每日一卦
+question = 问：{ $question }
+lines-are-changing = 有变爻！请参考：
+line-changes = { $label }变
+relating-line = 之卦{ $label }
+changes-into = 变为：
+zhu-xi-advice = { $changing ->
+        [3] 三爻变，朱熹以本卦与之卦的卦辞占，以本卦为主。
+       *[6] 六爻皆变，朱熹以之卦的卦辞占。
+    }
+method = { $method ->
+        [coin-toss] 掷钱法
+       *[ancient-yarrow-stalk] 蓍草法
+    }
+
+## 占卜记录
+
+reading-title = 记录 { $id }
+reading-heading = 记录 { $id }，{ $timestamp }，以{ method }起卦
+notes = 笔记
+journal-title = 占卜记录
+journal-empty = 尚无记录。使用 `divine` 起卦后，记录会保存在这里。
+reading-deleted = 已删除记录 { $id }。
+
+## 记录统计
+
+statistics-title = 记录统计
+statistics-readings = 记录
+statistics-total = 合计
+statistics-hexagrams = 卦
+statistics-trigrams = 本卦中的经卦
+statistics-changing-lines = 变爻（自下而上）
+statistics-line-values = 以{ method }所得的爻（共 { $count } 爻）
+statistics-comparison = 与{ method }相比：χ² = { $statistic }（自由度 { $degrees }），p = { $p }
+statistics-too-few-lines = 所得的爻太少，此比较尚不可靠。
+statistics-unlikely = 这些爻不太可能是以此法所得。
+statistics-consistent = 这些爻与此法相符。
+
+## 概率
+
+odds-title = { method }的概率
+odds-lines = 爻
+odds-line-name = { $value ->
+        [6] 老阴
+        [7] 少阳
+        [8] 少阴
+       *[9] 老阳
+    }
+odds-moving-lines = 变爻数
+odds-primary-hexagrams = 本卦
+odds-primary-hexagram = 本卦 { $hexagram } { $name }：{ $probability }
+odds-relating-hexagrams = 之卦
+odds-relating-explanation = 同时得到本卦与之卦的概率。
+odds-no-relating = （无）
+
+## 表头
+
+column-id = 编号
+column-date = 日期
+column-primary = 本卦
+column-relating = 之卦
+column-question = 问题
+column-method = 方法
+column-readings = 记录
+column-hexagram = 卦
+column-as-primary = 作本卦
+column-as-relating = 作之卦
+column-name = 名称
+column-trigram = 经卦
+column-pinyin = 拼音
+column-count = 次数
+column-share = 占比
+column-line = 爻
+column-value = 数
+column-expected = 期望
+column-probability = 概率
+column-moving-lines = 变爻
+column-setting = 设置
+column-number = #
+column-command = 命令
+column-changes-into = 变为
+
+## 搜索
+
+search-title = “{ $query }”的搜索结果
+search-nothing-found = 没有与搜索中每个词都相符的结果。
+search-best-results = 显示 { $total } 个结果中最好的 { $shown } 个。
+search-passage = { $passage ->
+        [name] 卦名
+        [judgement] 卦辞
+        [images] 象辞
+        [classical-judgement] 卦辞原文
+        [classical-images] 象辞原文
+        [classical-line] 第{ $position }爻原文
+       *[line] 第{ $position }爻
+    }
+
+## 设置
+
+settings-title = 设置
+settings-read-from = 读取自 { $path }。
+settings-no-config-file-at = { $path } 没有配置文件，因此以下为默认设置。
+settings-no-config-file = 没有配置文件。
+settings-built-in-data = 应用内置的文本
+settings-not-wrapped = 不换行
+settings-terminal-width = 终端的宽度
+settings-none = 无
+
+## 书
+
+book-written = 已将书写入 { $path }。
+export-written = 已将全文导出到 { $path }。
+book-title = 易经
+book-subtitle = 变化之书
+book-contents = 目录
+book-all-hexagrams = 全部卦
+book-hexagrams = 六十四卦
+book-trigrams = 经卦
+ordering = { $ordering ->
+        [binary] 二进制（伏羲）
+        [mawangdui] 马王堆
+       *[king-wen] 文王
+    }
+book-ordering = 按{ ordering }卦序排列。
+book-above = 上卦
+book-below = 下卦
+book-related = 相关的卦
+book-nuclear = 互卦
+book-inverse = 综卦
+book-complement = 错卦
+book-sequence = 卦序
+book-previous = 上一卦
+book-next = 下一卦
+book-hexagrams-above = 以此为上卦的卦
+book-hexagrams-below = 以此为下卦的卦
+
+## 浏览
+
+browse-grid = 六十四卦（{ ordering }卦序）
+browse-lines = 爻与相关的卦
+browse-help = 方向键移动 · 1-6 变一爻 · n/i/c 相关的卦 · o 卦序 · PgUp/PgDn 滚动 · q 退出
+
+## 交互模式
+
+repl-welcome = 探索卦的变化。先用 `cast` 起卦，或用 `lines 789678` 之类设置各爻，再试试 `flip 3`、`relate`、`nuclear` 或 `invert`。输入 `help` 查看所有命令，输入 `quit` 退出。
+repl-saved = 已保存到 { $path }。
+repl-history = 历史
+repl-nothing-cast = 尚未起卦。
+
+## 逐爻起卦
+
+casting-question = 请花点时间想想你要问的事。
+    你的问题（或按 Enter 不带问题起卦）：
+casting-begin = 卦由下往上逐爻而起。请把问题放在心上。
+casting-cast-line = 按 Enter 起第 { $position } 爻，或输入你自己起的爻：
+casting-toss-coin = 按 Enter 掷第 { $position } 爻的第 { $coin } 枚钱，或输入你自己掷的结果：
+casting-invalid-entry = 输入 h 或 t 表示一枚钱，三枚钱如 hht 或 3 3 2，或 6 到 9 的爻值。或直接按 Enter。
+casting-whole-line = 蓍草法一次起一整爻，请输入 6 到 9 的爻值，或三枚钱。
+casting-single-coin = 此爻已开始掷钱，请输入一枚钱。
+casting-coin = { $side ->
+        [heads] 正面
+       *[tails] 反面
+    }
+casting-line = 第 { $position } 爻为 { $value }，{ odds-line-name }。
@@ -0,0 +1,233 @@
+# 本應用的繁體中文訊息。訊息須與 en.ftl 一一對應，順序相同。
+
+## 卦
+
+hexagram-title = 第{ $number }卦  { $symbol }
+judgement = 卦辭
+images = 象辭
+lines = 爻辭
+line-position = 第{ $position }爻
+line-traditional = { $position ->
+        [1] 初{ $value }
+        [2] { $value }二
+        [3] { $value }三
+        [4] { $value }四
+        [5] { $value }五
+       *[6] 上{ $value }
+    }
+yang-line-value = 九
+yin-line-value = 六
+
+## 十翼
+
+tuan = 彖傳
+small-image = 象曰：{ $text }
+wenyan = 文言
+sequence = 序卦
+miscellaneous = 雜卦
+
+## 經卦
+
+trigram-title = 經卦 { $number }  { $symbol }
+attribute = 卦德
+image-in-nature = 卦象
+family-relationship = 家人
+trigram-qian = 創造
+    .attribute = 健
+    .image = 天
+    .family = 父
+trigram-kun = 接受
+    .attribute = 順
+    .image = 地
+    .family = 母
+trigram-zhen = 震動
+    .attribute = 動
+    .image = 雷
+    .family = 長男
+trigram-kan = 深淵
+    .attribute = 陷
+    .image = 水
+    .family = 中男
+trigram-gen = 靜止
+    .attribute = 止
+    .image = 山
+    .family = 少男
+trigram-xun = 柔順
+    .attribute = 入
+    .image = 風
+    .family = 長女
+trigram-li = 附麗
+    .attribute = 麗
+    .image = 火
+    .family = 中女
+trigram-dui = 喜悅
+    .attribute = 說
+    .image = 澤
+    .family = 少女
+
+## 占卜
+
+hexagram-of-the-day = { $date } 的每日一卦
+question = 問：{ $question }
+lines-are-changing = 有變爻！請參考：
+line-changes = { $label }變
+relating-line = 之卦{ $label }
+changes-into = 變為：
+zhu-xi-advice = { $changing ->
+        [3] 三爻變，朱熹以本卦與之卦的卦辭占，以本卦為主。
+       *[6] 六爻皆變，朱熹以之卦的卦辭占。
+    }
+method = { $method ->
+        [coin-toss] 擲錢法
+       *[ancient-yarrow-stalk] 蓍草法
+    }
+
+## 占卜記錄
+
+reading-title = 記錄 { $id }
+reading-heading = 記錄 { $id }，{ $timestamp }，以{ method }起卦
+notes = 筆記
+journal-title = 占卜記錄
+journal-empty = 尚無記錄。使用 `divine` 起卦後，記錄會保存在這裡。
+reading-deleted = 已刪除記錄 { $id }。
+
+## 記錄統計
+
+statistics-title = 記錄統計
+statistics-readings = 記錄
+statistics-total = 合計
+statistics-hexagrams = 卦
+statistics-trigrams = 本卦中的經卦
+statistics-changing-lines = 變爻（自下而上）
+statistics-line-values = 以{ method }所得的爻（共 { $count } 爻）
+statistics-comparison = 與{ method }相比：χ² = { $statistic }（自由度 { $degrees }），p = { $p }
+statistics-too-few-lines = 所得的爻太少，此比較尚不可靠。
+statistics-unlikely = 這些爻不太可能是以此法所得。
+statistics-consistent = 這些爻與此法相符。
+
+## 機率
+
+odds-title = { method }的機率
+odds-lines = 爻
+odds-line-name = { $value ->
+        [6] 老陰
+        [7] 少陽
+        [8] 少陰
+       *[9] 老陽
+    }
+odds-moving-lines = 變爻數
+odds-primary-hexagrams = 本卦
+odds-primary-hexagram = 本卦 { $hexagram } { $name }：{ $probability }
+odds-relating-hexagrams = 之卦
+odds-relating-explanation = 同時得到本卦與之卦的機率。
+odds-no-relating = （無）
+
+## 表頭
+
+column-id = 編號
+column-date = 日期
+column-primary = 本卦
+column-relating = 之卦
+column-question = 問題
+column-method = 方法
+column-readings = 記錄
+column-hexagram = 卦
+column-as-primary = 作本卦
+column-as-relating = 作之卦
+column-name = 名稱
+column-trigram = 經卦
+column-pinyin = 拼音
+column-count = 次數
+column-share = 佔比
+column-line = 爻
+column-value = 值
+column-expected = 期望
+column-probability = 機率
+column-moving-lines = 變爻
+column-setting = 設定
+column-number = #
+column-command = 命令
+column-changes-into = 變為
+
+## 搜尋
+
+search-title = 「{ $query }」的搜尋結果
+search-nothing-found = 沒有與搜尋中每個詞都相符的結果。
+search-best-results = 顯示 { $total } 個結果中最好的 { $shown } 個。
+search-passage = { $passage ->
+        [name] 卦名
+        [judgement] 卦辭
+        [images] 象辭
+        [classical-judgement] 卦辭原文
+        [classical-images] 象辭原文
+        [classical-line] 第{ $position }爻原文
+       *[line] 第{ $position }爻
+    }
+
+## 設定
+
+settings-title = 設定
+settings-read-from = 讀取自 { $path }。
+settings-no-config-file-at = { $path } 沒有設定檔，因此以下為預設設定。
+settings-no-config-file = 沒有設定檔。
+settings-built-in-data = 應用內建的文本
+settings-not-wrapped = 不換行
+settings-terminal-width = 終端機的寬度
+settings-none = 無
+
+## 書
+
+book-written = 已將書寫入 { $path }。
+export-written = 已將全文匯出到 { $path }。
+book-title = 易經
+book-subtitle = 變化之書
+book-contents = 目錄
+book-all-hexagrams = 全部卦
+book-hexagrams = 六十四卦
+book-trigrams = 經卦
+ordering = { $ordering ->
+        [binary] 二進制（伏羲）
+        [mawangdui] 馬王堆
+       *[king-wen] 文王
+    }
+book-ordering = 按{ ordering }卦序排列。
+book-above = 上卦
+book-below = 下卦
+book-related = 相關的卦
+book-nuclear = 互卦
+book-inverse = 綜卦
+book-complement = 錯卦
+book-sequence = 卦序
+book-previous = 上一卦
+book-next = 下一卦
+book-hexagrams-above = 以此為上卦的卦
+book-hexagrams-below = 以此為下卦的卦
+
+## 瀏覽
+
+browse-grid = 六十四卦（{ ordering }卦序）
+browse-lines = 爻與相關的卦
+browse-help = 方向鍵移動 · 1-6 變一爻 · n/i/c 相關的卦 · o 卦序 · PgUp/PgDn 捲動 · q 離開
+
+## 互動模式
+
+repl-welcome = 探索卦的變化。先用 `cast` 起卦，或用 `lines 789678` 之類設定各爻，再試試 `flip 3`、`relate`、`nuclear` 或 `invert`。輸入 `help` 查看所有命令，輸入 `quit` 離開。
+repl-saved = 已儲存到 { $path }。
+repl-history = 歷史
+repl-nothing-cast = 尚未起卦。
+
+## 逐爻起卦
+
+casting-question = 請花點時間想想你要問的事。
+    你的問題（或按 Enter 不帶問題起卦）：
+casting-begin = 卦由下往上逐爻而起。請把問題放在心上。
+casting-cast-line = 按 Enter 起第 { $position } 爻，或輸入你自己起的爻：
+casting-toss-coin = 按 Enter 擲第 { $position } 爻的第 { $coin } 枚錢，或輸入你自己擲的結果：
+casting-invalid-entry = 輸入 h 或 t 表示一枚錢，三枚錢如 hht 或 3 3 2，或 6 到 9 的爻值。或直接按 Enter。
+casting-whole-line = 蓍草法一次起一整爻，請輸入 6 到 9 的爻值，或三枚錢。
+casting-single-coin = 此爻已開始擲錢，請輸入一枚錢。
+casting-coin = { $side ->
+        [heads] 正面
+       *[tails] 反面
+    }
+casting-line = 第 { $position } 爻為 { $value }，{ odds-line-name }。
@@ -0,0 +1,355 @@
+//! Looking up hexagrams in each of the ways that `hexagram` accepts. Every kind of query
+//! resolves to a [`Hexagram`], whose text is then fetched from the repository.
+
+use crate::hexagram_json::HexagramJsonInfo;
+use iching::{
+    hexagram::{Hexagram, HexagramOrdering},
+    hexagram_repository::HexagramRepository,
+    search::{edit_distance, normalize},
+    trigram::Trigram,
+};
+
+/// Queries for a name can be this many edits away from a name, per three letters of the name,
+/// and still find it.
+const LETTERS_PER_TYPO: usize = 3;
+/// The number of names suggested when nothing is called what was asked for.
+const SUGGESTIONS: usize = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("{0:?} isn't a hexagram symbol. Hexagram symbols run from ䷀ to ䷿")]
+    UnknownSymbol(String),
+    #[error("{0:?} isn't a binary number of 6 digits, such as 010001")]
+    InvalidBinary(String),
+    #[error(
+        "{0:?} isn't 6 lines, such as 100010 or 977878. Give each line from the bottom up, as 0 \
+         (yin) or 1 (yang) or as its value from 6 to 9"
+    )]
+    InvalidLines(String),
+    #[error(
+        "{0:?} isn't a name. Give a name with letters or Chinese characters in it, such as \
+         \"The Creative\" or 乾"
+    )]
+    EmptyName(String),
+    #[error("No hexagram is called {query:?}. Did you mean {}?", or_list(.suggestions))]
+    UnknownName {
+        query: String,
+        suggestions: Vec<String>,
+    },
+    #[error(
+        "{query:?} could be {}. Give more of the name, or its tone marks",
+        or_list(.candidates)
+    )]
+    AmbiguousName {
+        query: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// List names as `a, b or c`.
+fn or_list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [rest @ .., last] => format!("{} or {last}", rest.join(", ")),
+    }
+}
+
+/// The ways that a hexagram can be looked up.
+#[derive(Clone, Debug)]
+pub(crate) enum HexagramQuery {
+    /// The hexagram's number (1-64) in an ordering.
+    Number(u8, HexagramOrdering),
+    /// The hexagram's name in English, Chinese characters or pinyin. Names don't need to be
+    /// exact.
+    Name(String),
+    /// The hexagram's unicode symbol, e.g. `䷀`.
+    Symbol(String),
+    /// The hexagram's lines as a binary number, top line first, with yang as 1, e.g. `010001`.
+    Binary(String),
+    /// The hexagram's lines from the bottom up, either as 0 (yin) and 1 (yang) or as their values
+    /// from 6 to 9, e.g. `100010` or `977878`. Changing lines are ignored.
+    Lines(String),
+    /// The hexagram's upper and lower trigrams.
+    Trigrams { above: Trigram, below: Trigram },
+}
+
+impl HexagramQuery {
+    /// Find the hexagram that this query is for.
+    pub(crate) fn resolve(
+        &self,
+        hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+    ) -> Result<Hexagram, Error> {
+        match self {
+            HexagramQuery::Number(number, ordering) => Ok(ordering
+                .hexagram_at(*number)
+                .expect("clap has validated this number already")),
+            HexagramQuery::Name(name) => find_by_name(name, hexagrams),
+            HexagramQuery::Symbol(symbol) => {
+                Hexagram::from_symbol(symbol).ok_or_else(|| Error::UnknownSymbol(symbol.clone()))
+            }
+            HexagramQuery::Binary(binary) => {
+                let values = parse_lines(binary, &[('0', 8), ('1', 7)])
+                    .ok_or_else(|| Error::InvalidBinary(binary.clone()))?;
+                Ok(from_line_values(values, true))
+            }
+            HexagramQuery::Lines(lines) => {
+                let values = parse_line_values(lines)?;
+                Ok(from_line_values(values, false).primary_hexagram())
+            }
+            HexagramQuery::Trigrams { above, below } => Ok(Hexagram::new(*above, *below)),
+        }
+    }
+}
+
+/// Turn 6 digits into line values, with the given value for each digit.
+fn parse_lines(digits: &str, values: &[(char, u8)]) -> Option<[u8; 6]> {
+    let values: Vec<u8> = digits
+        .trim()
+        .chars()
+        .map(|digit| {
+            values
+                .iter()
+                .find(|(known, _)| *known == digit)
+                .map(|(_, value)| *value)
+        })
+        .collect::<Option<_>>()?;
+
+    values.try_into().ok()
+}
+
+/// Parse the lines of a hexagram from the bottom up, either as 0 (yin) and 1 (yang) or as their
+/// values from 6 to 9, e.g. `100010` or `986878`. Lines given as 0 and 1 aren't changing.
+pub(crate) fn parse_line_values(lines: &str) -> Result<[u8; 6], Error> {
+    parse_lines(
+        lines,
+        &[('0', 8), ('1', 7), ('6', 6), ('7', 7), ('8', 8), ('9', 9)],
+    )
+    .ok_or_else(|| Error::InvalidLines(lines.to_owned()))
+}
+
+fn from_line_values(mut values: [u8; 6], top_down: bool) -> Hexagram {
+    if top_down {
+        values.reverse();
+    }
+    Hexagram::try_from(values).expect("only valid line values are parsed")
+}
+
+/// Simplify a name for comparison: lowercase, with only letters, digits and single spaces, and
+/// without a leading "the".
+fn simplify(name: &str, keep_tone_marks: bool) -> String {
+    let name = if keep_tone_marks {
+        name.to_lowercase()
+    } else {
+        normalize(name)
+    };
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    match words.as_slice() {
+        ["the", rest @ ..] if !rest.is_empty() => rest.join(" "),
+        words => words.join(" "),
+    }
+}
+
+/// Every name that a hexagram can be looked up by: its full English name, each of the
+/// alternatives in it (e.g. `Wind` in `The Gentle (The Penetrating, Wind)`), its Chinese name
+/// in traditional and simplified characters and its pinyin.
+fn names(info: &HexagramJsonInfo) -> Vec<&str> {
+    let name = info.name();
+    let mut names = vec![
+        name.english.as_str(),
+        &name.chinese,
+        &name.chinese_simplified,
+        &name.pinyin,
+    ];
+    names.extend(
+        name.english
+            .split(['(', ')', '[', ']', ','])
+            .map(str::trim)
+            .filter(|part| !part.is_empty()),
+    );
+    names
+}
+
+/// Find a hexagram by name. Names are tried exactly, then without tone marks, then as part of a
+/// longer name and finally allowing for typos.
+fn find_by_name(
+    query: &str,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<Hexagram, Error> {
+    let infos: Vec<&HexagramJsonInfo> = (1..=64)
+        .filter_map(|number| hexagrams.get_by_number(number))
+        .collect();
+    let label = |info: &HexagramJsonInfo| {
+        format!(
+            "{} {} {}",
+            info.symbol(),
+            info.number(),
+            info.english_name()
+        )
+    };
+    // Give the one hexagram that matches, or complain that several do.
+    let unique = |matches: Vec<&&HexagramJsonInfo>| -> Option<Result<Hexagram, Error>> {
+        let mut matches = matches;
+        matches.dedup_by_key(|info| info.number());
+        match matches.as_slice() {
+            [] => None,
+            [info] => Some(Ok(*info.hexagram())),
+            matches => Some(Err(Error::AmbiguousName {
+                query: query.to_owned(),
+                candidates: matches.iter().map(|info| label(info)).collect(),
+            })),
+        }
+    };
+
+    let exact = simplify(query, true);
+    let loose = simplify(query, false);
+    // Every name would match an empty query.
+    if loose.is_empty() {
+        return Err(Error::EmptyName(query.to_owned()));
+    }
+    let matches_exactly = |keep_tone_marks: bool, query: &str| {
+        infos
+            .iter()
+            .filter(|info| {
+                names(info)
+                    .iter()
+                    .any(|name| simplify(name, keep_tone_marks) == query)
+            })
+            .collect::<Vec<_>>()
+    };
+    if let Some(found) = unique(matches_exactly(true, &exact)) {
+        return found;
+    }
+    if let Some(found) = unique(matches_exactly(false, &loose)) {
+        return found;
+    }
+
+    let partial_matches: Vec<_> = infos
+        .iter()
+        .filter(|info| {
+            names(info)
+                .iter()
+                .any(|name| format!(" {} ", simplify(name, false)).contains(&format!(" {loose} ")))
+        })
+        .collect();
+    if let Some(found) = unique(partial_matches) {
+        return found;
+    }
+
+    let mut distances: Vec<(usize, &&HexagramJsonInfo)> = infos
+        .iter()
+        .map(|info| {
+            // Single words are compared too, so that a typo in one word of a long name is
+            // forgiven.
+            let names: Vec<String> = names(info)
+                .iter()
+                .map(|name| simplify(name, false))
+                .collect();
+            let words = names.iter().flat_map(|name| name.split(' '));
+            let distance = names
+                .iter()
+                .map(String::as_str)
+                .chain(words)
+                .map(|name| edit_distance(&loose, name))
+                .min()
+                .expect("every hexagram has names");
+            (distance, info)
+        })
+        .collect();
+    distances.sort_by_key(|(distance, _)| *distance);
+    let allowed = (loose.chars().count() / LETTERS_PER_TYPO).max(1);
+    let best = distances[0].0;
+    if best <= allowed {
+        let closest = distances
+            .iter()
+            .take_while(|(distance, _)| *distance == best)
+            .map(|(_, info)| *info)
+            .collect();
+        if let Some(found) = unique(closest) {
+            return found;
+        }
+    }
+
+    Err(Error::UnknownName {
+        query: query.to_owned(),
+        suggestions: distances
+            .iter()
+            .take(SUGGESTIONS)
+            .map(|(_, info)| label(info))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+    use iching::trigram::TrigramName;
+
+    #[test]
+    fn test_every_kind_of_query_finds_the_same_hexagram() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        // ䷂ Difficulty at the Beginning
+        let difficulty = Hexagram::try_from([7, 8, 8, 8, 7, 8]).unwrap();
+
+        let queries = [
+            HexagramQuery::Number(3, HexagramOrdering::KingWen),
+            HexagramQuery::Number(35, HexagramOrdering::Binary),
+            HexagramQuery::Name(String::from("difficulty at the beginning")),
+            HexagramQuery::Name(String::from("Dificulty at beginning")),
+            HexagramQuery::Name(String::from("dificulty")),
+            HexagramQuery::Name(String::from("zhun")),
+            HexagramQuery::Name(String::from("屯")),
+            HexagramQuery::Symbol(String::from("䷂")),
+            HexagramQuery::Binary(String::from("010001")),
+            HexagramQuery::Lines(String::from("100010")),
+            HexagramQuery::Lines(String::from("986878")),
+            HexagramQuery::Trigrams {
+                above: TrigramName::Kan.into(),
+                below: TrigramName::Zhen.into(),
+            },
+        ];
+        for query in queries {
+            assert_eq!(query.resolve(&hexagrams).unwrap(), difficulty, "{query:?}");
+        }
+    }
+
+    #[test]
+    fn test_names_that_fit_several_hexagrams_are_ambiguous() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+
+        // Both ䷀ Qián and ䷎ Qiān.
+        let err = HexagramQuery::Name(String::from("qian"))
+            .resolve(&hexagrams)
+            .unwrap_err();
+        assert!(matches!(err, Error::AmbiguousName { .. }), "{err}");
+        let creative = HexagramQuery::Name(String::from("qián"))
+            .resolve(&hexagrams)
+            .unwrap();
+        assert_eq!(creative, Hexagram::try_from([7; 6]).unwrap());
+
+        let err = HexagramQuery::Name(String::from("completion"))
+            .resolve(&hexagrams)
+            .unwrap_err();
+        assert!(err.to_string().contains("63 After Completion"), "{err}");
+        assert!(err.to_string().contains("64 Before Completion"), "{err}");
+    }
+
+    #[test]
+    fn test_names_without_letters_are_rejected() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+
+        for query in ["", "   ", "?!"] {
+            let err = HexagramQuery::Name(String::from(query))
+                .resolve(&hexagrams)
+                .unwrap_err();
+            assert!(matches!(err, Error::EmptyName(_)), "{query:?}: {err}");
+        }
+    }
+}
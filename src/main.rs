@@ -1,13 +1,46 @@
+mod book;
+mod browse;
+mod casting;
+mod config;
+mod export;
 mod hexagram_json;
+mod journal;
+mod journal_stats;
+mod locale;
+mod lookup;
+mod probability_tables;
+mod raster;
+mod render;
+mod repl;
+mod search_results;
+mod serve;
+mod view;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use chrono::{Local, NaiveDate, SubsecRound};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use config::{Config, Settings};
 use hexagram_json::{HexagramJson, HexagramJsonInfo};
 use iching::{
-    divination_method::DivinationMethod, hexagram::Hexagram,
-    hexagram_repository::HexagramRepository, trigram::Trigram,
+    daily::hexagram_of_the_day,
+    divination_method::DivinationMethod,
+    hexagram::{Hexagram, HexagramOrdering},
+    hexagram_repository::HexagramRepository,
+    interpretation::{LineLabels, School},
+    trigram::{Trigram, TrigramName},
+};
+use journal::{Journal, NewReading};
+use journal_stats::JournalStatistics;
+use locale::Lang;
+use lookup::HexagramQuery;
+use probability_tables::ProbabilityTables;
+use search_results::SearchResults;
+use serde_derive::{Deserialize, Serialize};
+use std::path::PathBuf;
+use termcolor::StandardStream;
+use view::{
+    write_view, HexagramView, JournalEntryView, JournalListView, Layer, OutputFormat, ReadingView,
+    Texts, TrigramView,
 };
-use std::io::Write;
-use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
 
 // If the ABOUT is defined like this instead of using a doc comment, then the `--help` output will
 // respect the line breaks.
@@ -35,14 +68,72 @@ https://en.wikipedia.org/wiki/I_Ching
 #[derive(Parser)]
 #[command(author, version, arg_required_else_help(true), about = ABOUT)]
 struct Args {
-    /// Set whether output should be colorful or not
-    #[arg(long, value_enum, default_value_t = ColorPreference::Auto)]
-    color: ColorPreference,
+    /// Set whether output should be colorful or not. Defaults to auto
+    #[arg(long, value_enum)]
+    color: Option<ColorPreference>,
+    /// The config file to read settings from. Defaults to iching/config.toml in your config
+    /// directory
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Seed the random number generator, making readings reproducible. `daily` is seeded by the
+    /// date instead
+    #[arg(long, global = true, value_name = "SEED")]
+    seed: Option<u64>,
+    /// The journal file that readings are stored in. Defaults to a file in your data directory
+    #[arg(long, global = true, value_name = "PATH")]
+    journal: Option<PathBuf>,
+    /// The format to write output in. Defaults to text
+    #[arg(long, global = true, value_enum)]
+    format: Option<OutputFormat>,
+    /// The file to read the hexagram texts from, instead of the texts built into the app. It must
+    /// be in the same format as src/hexagrams.json
+    #[arg(long, global = true, value_name = "PATH")]
+    data: Option<PathBuf>,
+    /// How to name the lines of hexagrams. Defaults to positions
+    #[arg(long, global = true, value_enum)]
+    line_labels: Option<LineLabels>,
+    /// The school of interpretation that picks which changing lines of a reading to consider.
+    /// Defaults to every changing line
+    #[arg(long, global = true, value_enum)]
+    school: Option<School>,
+    /// The colors of text output: dark, light, high-contrast, monochrome, 16-color or a theme
+    /// from the config file. Defaults to dark
+    #[arg(long, global = true, value_name = "THEME")]
+    theme: Option<String>,
+    /// The number of columns to wrap text output to, or 0 not to wrap it. Defaults to the width
+    /// of the terminal
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    width: Option<usize>,
+    /// The language of messages and labels: en, zh-Hans or zh-Hant. Defaults to the language of
+    /// your locale, or English
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        ignore_case = true,
+        value_name = "LANG"
+    )]
+    lang: Option<Lang>,
+    /// Which texts of the hexagrams to show: the translation, the original classical Chinese or
+    /// both. Defaults to the translation
+    #[arg(long, global = true, value_enum)]
+    texts: Option<Texts>,
+    /// The commentaries of the Ten Wings to show along with the text, separated by commas, e.g.
+    /// `--with tuan,small-images`. `commentary` shows all of them
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "COMMENTARIES"
+    )]
+    with: Vec<Layer>,
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 enum ColorPreference {
     /// Output will be colorful when a terminal is detected
     Auto,
@@ -75,143 +166,616 @@ enum Commands {
         /// the I-Ching
         #[arg(short, long, value_name = "QUESTION")]
         question: Option<String>,
-        /// The method of divination to use.
-        #[arg(short, long, value_name = "DIVINATION METHOD", value_enum, default_value_t = DivinationMethod::AncientYarrowStalk)]
-        method: DivinationMethod,
+        /// The method of divination to use. Defaults to the ancient yarrow stalk method, unless the
+        /// config file says otherwise
+        #[arg(short, long, value_name = "DIVINATION METHOD", value_enum)]
+        method: Option<DivinationMethod>,
+        /// Don't store this reading in the journal
+        #[arg(long)]
+        no_journal: bool,
+        /// Draw the primary and relating hexagrams as big lines, side by side
+        #[arg(long)]
+        big: bool,
+        /// Also save the reading as an image, either PNG or SVG depending on the file's extension
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Cast the hexagram step by step, one line at a time from the bottom up
+        ///
+        /// The question is asked for if it wasn't given, and Enter casts each line, or each coin
+        /// with the coin toss method. Lines and coins that were cast by hand can be typed in
+        /// instead. The steps are shown on stderr, so the reading itself can still be redirected
+        #[arg(short, long)]
+        interactive: bool,
     },
-    /// Look up a hexagram by its King Wen sequence number
+    /// Receive the hexagram of the day
+    ///
+    /// Everyone asking on the same day (and with the same salt) receives the same hexagram.
+    Daily {
+        /// The day to receive the hexagram for, formatted as YYYY-MM-DD. Defaults to today.
+        #[arg(short, long, value_name = "DATE")]
+        date: Option<NaiveDate>,
+        /// Mix some text into the day's hexagram, so that a group can share its own hexagram
+        #[arg(short, long, value_name = "SALT")]
+        salt: Option<String>,
+        /// The method of divination to use. Defaults to the ancient yarrow stalk method, unless the
+        /// config file says otherwise
+        #[arg(short, long, value_name = "DIVINATION METHOD", value_enum)]
+        method: Option<DivinationMethod>,
+    },
+    /// Look up a hexagram by its number, name, symbol, lines or trigrams
+    ///
+    /// Names can be given in English, in Chinese characters or in pinyin, with or without tone
+    /// marks. Parts of names and small typos are forgiven, as long as only one hexagram fits.
+    #[command(group(clap::ArgGroup::new("query").required(true).args(["number", "name", "symbol", "binary", "lines", "above"])))]
     Hexagram {
-        /// The hexagram's number
+        /// The hexagram's number, in the King Wen sequence unless another ordering is given
         #[arg(long, value_name = "HEXAGRAM NUMBER", value_parser = clap::value_parser!(u8).range(1..=64))]
-        number: u8,
+        number: Option<u8>,
+        /// The ordering that the hexagram's number is in
+        #[arg(long, value_enum, default_value_t = HexagramOrdering::KingWen, requires = "number")]
+        ordering: HexagramOrdering,
+        /// The hexagram's name, e.g. "The Creative", "乾" or "qian"
+        #[arg(long, value_name = "NAME")]
+        name: Option<String>,
+        /// The hexagram's unicode symbol, e.g. ䷀
+        #[arg(long, value_name = "SYMBOL")]
+        symbol: Option<String>,
+        /// The hexagram's lines as a binary number, top line first, with 1 for yang, e.g. 010001
+        #[arg(long, value_name = "BINARY")]
+        binary: Option<String>,
+        /// The hexagram's lines from the bottom up, as 0 (yin) or 1 (yang) or as the values of a
+        /// cast from 6 to 9, e.g. 100010 or 986878. Changing lines are ignored
+        #[arg(long, value_name = "LINES")]
+        lines: Option<String>,
+        /// The hexagram's upper trigram, by any name that `trigram` accepts
+        #[arg(long, value_name = "TRIGRAM", requires = "below")]
+        above: Option<TrigramName>,
+        /// The hexagram's lower trigram, by any name that `trigram` accepts
+        #[arg(long, value_name = "TRIGRAM", requires = "above")]
+        below: Option<TrigramName>,
+        /// Draw the hexagram as big lines
+        #[arg(long)]
+        big: bool,
     },
-    /// Look up a trigram by its King Wen sequence number
+    /// Look up a trigram by its name, symbol, lines, image or family member, or by its number
+    #[command(group(clap::ArgGroup::new("query").required(true).args(["trigram", "number"])))]
     Trigram {
+        /// The trigram's pinyin or English name, Chinese character or symbol, its lines from the
+        /// bottom up, its image in nature or its family member, e.g. "qian", "Heaven", "乾", "☰",
+        /// "111" or "father"
+        #[arg(value_name = "TRIGRAM")]
+        trigram: Option<TrigramName>,
         /// The trigram's number
         #[arg(short, long, value_name = "TRIGRAM NUMBER", value_parser = clap::value_parser!(u8).range(1..=8))]
-        number: u8,
+        number: Option<u8>,
+    },
+    /// Search the names, judgements, images and line texts of every hexagram
+    ///
+    /// Results are ranked by how well they match, best first. Names can be searched in English,
+    /// in Chinese characters or in pinyin, with or without tone marks.
+    Search {
+        /// The words to search for. Every word must appear in a result
+        #[arg(value_name = "QUERY", required = true)]
+        query: Vec<String>,
+        /// Show at most this many results
+        #[arg(short, long, value_name = "COUNT", default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show the odds of casting each line and hexagram
+    Probabilities {
+        /// The method of divination to show the odds of. Defaults to the ancient yarrow stalk
+        /// method, unless the config file says otherwise
+        #[arg(short, long, value_name = "DIVINATION METHOD", value_enum)]
+        method: Option<DivinationMethod>,
+        /// Show the odds of each relating hexagram for the primary hexagram with this King Wen
+        /// sequence number
+        #[arg(long, value_name = "HEXAGRAM NUMBER", value_parser = clap::value_parser!(u8).range(1..=64))]
+        hexagram: Option<u8>,
+    },
+    /// Browse the hexagrams in a full-screen grid, with the text of the selected one
+    ///
+    /// Move around the grid with the arrow keys, flip the selected hexagram's lines with 1-6 to
+    /// visit its neighbors, and jump to its nuclear, inverse or complement hexagram with n, i or c.
+    Browse {
+        /// The order to lay out the grid in. Press o to change it while browsing
+        #[arg(long, value_enum, default_value_t = HexagramOrdering::KingWen)]
+        ordering: HexagramOrdering,
+    },
+    /// Explore how hexagrams change in an interactive shell
+    ///
+    /// Cast or set a hexagram, then flip its lines or move to its relating, nuclear, inverse or
+    /// complement hexagram. Type `help` in the shell for every command.
+    Repl,
+    /// Serve readings and the texts as a JSON API on the local machine
+    ///
+    /// The endpoints are `POST /divine`, `GET /hexagrams/{n}`, `GET /trigrams/{n}` and
+    /// `GET /search?q=`. They return the same JSON as `--format json`.
+    Serve {
+        /// The port to listen on. 0 picks any free port
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Write a website with the full text of every hexagram and trigram
+    ///
+    /// The website is made of static HTML pages that only link to each other, so it can be
+    /// browsed offline. Writing it again gives exactly the same files.
+    Book {
+        /// The directory to write the website into. It's created if it doesn't exist
+        #[arg(value_name = "DIRECTORY")]
+        directory: PathBuf,
+        /// The order to list the hexagrams in on the index page
+        #[arg(long, value_enum, default_value_t = HexagramOrdering::KingWen)]
+        ordering: HexagramOrdering,
+    },
+    /// Export the full text of every hexagram, to read on an e-reader or to print
+    ///
+    /// The text is written as a single Markdown document or as an EPUB 3 book, depending on the
+    /// file's extension.
+    Export {
+        /// The file to export to, ending in .md or .epub
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+        /// The order to put the hexagrams in
+        #[arg(long, value_enum, default_value_t = HexagramOrdering::KingWen)]
+        ordering: HexagramOrdering,
+    },
+    /// Look back on past readings
+    Journal {
+        #[command(subcommand)]
+        command: JournalCommands,
+    },
+    /// Inspect the settings from the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Show the settings that commands run with, after the config file and the command line
+    /// options are applied
+    Show,
+}
+
+#[derive(Subcommand)]
+enum JournalCommands {
+    /// List all readings in the journal, oldest first
+    List,
+    /// Show a past reading in full
+    Show {
+        /// The reading's ID, as shown by `journal list`
+        id: u64,
+    },
+    /// Add a note to a past reading
+    Note {
+        /// The reading's ID, as shown by `journal list`
+        id: u64,
+        /// The text of the note
+        note: String,
     },
+    /// Delete a reading from the journal
+    Delete {
+        /// The reading's ID, as shown by `journal list`
+        id: u64,
+    },
+    /// Show how often each hexagram, trigram and changing line has appeared
+    ///
+    /// The lines cast with each method are also compared against the odds of every method, as a
+    /// check that the random number generator behaves.
+    Stats,
 }
 
 fn main() {
     let cli = Args::parse();
+    // The hexagram of the day is the same for everyone, so seeding it would only mislead.
+    if cli.seed.is_some() && matches!(cli.command, Some(Commands::Daily { .. })) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "the argument '--seed <SEED>' cannot be used with 'daily', whose hexagram is \
+                 seeded by the date; use '--salt' for a hexagram of your own",
+            )
+            .exit();
+    }
+    let settings = load_settings(&cli).unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    });
 
-    let color = if cli.color == ColorPreference::Auto && !atty::is(atty::Stream::Stdout) {
+    let color = if settings.color == ColorPreference::Auto && !atty::is(atty::Stream::Stdout) {
         termcolor::ColorChoice::Never
     } else {
-        cli.color.into()
+        settings.color.into()
     };
     let mut output = termcolor::StandardStream::stdout(color);
 
     let mut hexagrams = HexagramJson::new();
-    hexagrams
-        .initialize()
-        .expect("hexagrams loaded successfully");
+    if let Some(data) = &settings.data {
+        hexagrams = hexagrams.with_data_file(data.clone());
+    }
+    // The built-in hexagrams always load, but a data file might not.
+    if let Err(err) = hexagrams.initialize() {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
 
     if let Some(command) = cli.command {
-        match command {
-            Commands::Divine { question, method } => {
-                let hexagram = Hexagram::new_random(method);
+        let options = Options {
+            seed: cli.seed,
+            settings,
+        };
+        if let Err(err) = run_command(&mut output, options, command, &hexagrams) {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Work out the settings to run with: the command line options, then the config file, then the
+/// defaults.
+fn load_settings(cli: &Args) -> Result<Settings, config::Error> {
+    let (config, config_file) = Config::load(cli.config.as_deref())?;
+    let defaults = Settings::default();
+    let theme = match cli.theme.as_ref().or(config.theme.as_ref()) {
+        Some(name) => config.theme(name)?,
+        None => defaults.theme,
+    };
+
+    Ok(Settings {
+        config_file,
+        method: config.method.unwrap_or(defaults.method),
+        data: cli.data.clone().or(config.data),
+        format: cli.format.or(config.format).unwrap_or(defaults.format),
+        color: cli.color.or(config.color).unwrap_or(defaults.color),
+        journal: cli.journal.clone().or(config.journal),
+        line_labels: cli
+            .line_labels
+            .or(config.line_labels)
+            .unwrap_or(defaults.line_labels),
+        school: cli.school.or(config.school).unwrap_or(defaults.school),
+        theme,
+        width: cli.width.or(config.width),
+        lang: cli
+            .lang
+            .or(config.lang)
+            .unwrap_or_else(Lang::from_environment),
+        texts: cli.texts.or(config.texts).unwrap_or(defaults.texts),
+        with: if cli.with.is_empty() {
+            config.with.unwrap_or(defaults.with)
+        } else {
+            cli.with.clone()
+        },
+        image: config.image,
+    })
+}
 
-                print_fortune(&mut output, question.as_deref(), hexagram, &hexagrams);
+/// The global arguments that commands are run with.
+struct Options {
+    seed: Option<u64>,
+    settings: Settings,
+}
+
+fn run_command(
+    output: &mut StandardStream,
+    options: Options,
+    command: Commands,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Commands::Divine {
+            question,
+            method,
+            no_journal,
+            big,
+            output: image_path,
+            interactive,
+        } => {
+            let method = method.unwrap_or(options.settings.method);
+            let (question, hexagram) = if interactive {
+                let rng = options
+                    .seed
+                    .map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed);
+                casting::cast_interactively(
+                    &mut std::io::stdin().lock(),
+                    &mut std::io::stderr().lock(),
+                    question,
+                    method,
+                    options.settings.lang,
+                    &rng,
+                )?
+            } else {
+                let hexagram = match options.seed {
+                    Some(seed) => Hexagram::new_seeded(method, seed),
+                    None => Hexagram::new_random(method),
+                };
+                (question, hexagram)
+            };
+            let reading = NewReading {
+                timestamp: Local::now().fixed_offset().trunc_subsecs(0),
+                question: question.clone(),
+                method,
+                lines: hexagram.line_values(),
+                primary: hexagrams.get_info_for_hexagram(&hexagram).number(),
+                relating: hexagram
+                    .relating_hexagram()
+                    .map(|h| hexagrams.get_info_for_hexagram(&h).number()),
+            };
+
+            let mut view = ReadingView::new(question.as_deref(), method, &hexagram, hexagrams)
+                .with_school(options.settings.school)
+                .with_line_labels(options.settings.line_labels)
+                .with_lang(options.settings.lang)
+                .with_texts(options.settings.texts)
+                .with_commentary(&options.settings.with);
+            if big {
+                view = view.with_big_lines();
             }
-            Commands::Hexagram {
-                number: hexagram_number,
-            } => {
-                let hexagram = hexagrams
-                    .get_by_number(hexagram_number)
-                    .expect("clap has validated this number already");
-
-                hexagram
-                    .write_to(&mut output)
-                    .expect("hexagram written successfully");
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &view,
+            )?;
+            if let Some(path) = image_path {
+                raster::save_image(&path, &view, &options.settings.image.style())?;
             }
-            Commands::Trigram {
-                number: trigram_number,
-            } => {
-                let trigram: Trigram = trigram_number
-                    .try_into()
-                    .expect("clap has validated this number already");
-                trigram
-                    .write_to(&mut output)
-                    .expect("trigram written successfully");
+
+            if !no_journal {
+                // A reading is still worth something if it can't be stored, so only warn.
+                if let Err(err) = record_reading(options.settings.journal, reading) {
+                    eprintln!("warning: the reading was not stored in the journal. {err}");
+                }
+            }
+        }
+        Commands::Daily { date, salt, method } => {
+            let date = date.unwrap_or_else(|| Local::now().date_naive());
+            let method = method.unwrap_or(options.settings.method);
+            let hexagram = hexagram_of_the_day(date, salt.as_deref(), method);
+
+            let view = ReadingView::new(None, method, &hexagram, hexagrams)
+                .with_date(date)
+                .with_school(options.settings.school)
+                .with_line_labels(options.settings.line_labels)
+                .with_lang(options.settings.lang)
+                .with_texts(options.settings.texts)
+                .with_commentary(&options.settings.with);
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &view,
+            )?;
+        }
+        Commands::Hexagram {
+            number,
+            ordering,
+            name,
+            symbol,
+            binary,
+            lines,
+            above,
+            below,
+            big,
+        } => {
+            let query = if let Some(number) = number {
+                HexagramQuery::Number(number, ordering)
+            } else if let Some(name) = name {
+                HexagramQuery::Name(name)
+            } else if let Some(symbol) = symbol {
+                HexagramQuery::Symbol(symbol)
+            } else if let Some(binary) = binary {
+                HexagramQuery::Binary(binary)
+            } else if let Some(lines) = lines {
+                HexagramQuery::Lines(lines)
+            } else {
+                let (Some(above), Some(below)) = (above, below) else {
+                    unreachable!("clap requires one way of looking up a hexagram");
+                };
+                HexagramQuery::Trigrams {
+                    above: above.into(),
+                    below: below.into(),
+                }
+            };
+            let hexagram = query.resolve(hexagrams)?;
+
+            let mut view = HexagramView::from(hexagrams.get_info_for_hexagram(&hexagram))
+                .with_lines()
+                .with_line_labels(options.settings.line_labels)
+                .with_lang(options.settings.lang)
+                .with_texts(options.settings.texts)
+                .with_commentary(&options.settings.with);
+            if big {
+                view = view.with_big_lines();
             }
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &view,
+            )?;
+        }
+        Commands::Trigram { trigram, number } => {
+            let trigram: Trigram = match (trigram, number) {
+                (Some(name), _) => name.into(),
+                (None, Some(number)) => number
+                    .try_into()
+                    .expect("clap has validated this number already"),
+                (None, None) => unreachable!("clap requires a trigram or its number"),
+            };
+
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &TrigramView::from(&trigram).with_lang(options.settings.lang),
+            )?;
+        }
+        Commands::Search { query, limit } => {
+            let query = query.join(" ");
+            let results =
+                SearchResults::new(&query, limit, hexagrams).with_lang(options.settings.lang);
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &results,
+            )?;
+        }
+        Commands::Probabilities { method, hexagram } => {
+            let method = method.unwrap_or(options.settings.method);
+            let tables = ProbabilityTables::new(method, hexagram, hexagrams)
+                .with_lang(options.settings.lang);
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &tables,
+            )?;
         }
+        Commands::Browse { ordering } => browse::browse(ordering, &options.settings, hexagrams)?,
+        Commands::Serve { port } => serve::serve(port, &options.settings, hexagrams)?,
+        Commands::Repl => repl::repl(output, &options.settings, options.seed, hexagrams)?,
+        Commands::Book {
+            directory,
+            ordering,
+        } => {
+            book::write_book(&directory, ordering, &options.settings, hexagrams)?;
+            println!(
+                "{}",
+                options.settings.lang.messages().format(
+                    "book-written",
+                    &[("path", directory.display().to_string().into())]
+                )
+            );
+        }
+        Commands::Export { path, ordering } => {
+            export::export(&path, ordering, &options.settings, hexagrams)?;
+            println!(
+                "{}",
+                options.settings.lang.messages().format(
+                    "export-written",
+                    &[("path", path.display().to_string().into())]
+                )
+            );
+        }
+        Commands::Journal { command } => {
+            run_journal_command(output, options, command, hexagrams)?;
+        }
+        Commands::Config {
+            command: ConfigCommands::Show,
+        } => write_view(
+            output,
+            options.settings.format,
+            &options.settings.theme,
+            options.settings.text_width(),
+            &options.settings.image.style(),
+            &options.settings,
+        )?,
     }
+
+    Ok(())
+}
+
+fn record_reading(
+    journal_path: Option<PathBuf>,
+    reading: NewReading,
+) -> Result<(), journal::Error> {
+    let path = Journal::path_or_default(journal_path)?;
+    let mut journal = Journal::load(&path)?;
+    journal.add(reading);
+    journal.save(&path)
 }
 
-fn print_fortune(
+fn run_journal_command(
     output: &mut StandardStream,
-    question: Option<&str>,
-    hexagram: Hexagram,
+    options: Options,
+    command: JournalCommands,
     hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
-) {
-    // Get the primary hexagram. panic if the repository doesn't contain the hexagram.
-    let hexagram_info_pre_changes = hexagrams.get_info_for_hexagram(&hexagram);
-
-    // Get the relating hexagram, if any lines in the primary hexagram are changing.
-    let hexagram_info_post_changes = hexagram
-        .relating_hexagram()
-        .map(|h| hexagrams.get_info_for_hexagram(&h));
-
-    // If the user provided a question, then print it out
-    if let Some(question_text) = question {
-        println!("Q: {question_text}\n");
-    }
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Journal::path_or_default(options.settings.journal.clone())?;
+    let mut journal = Journal::load(&path)?;
 
-    // Print info for the primary hexagram
-    hexagram_info_pre_changes
-        .write_to(output)
-        .expect("hexagram info written successfully");
-
-    // Print info for any changing lines
-    print_changing_lines_info(output, &hexagram, hexagram_info_pre_changes)
-        .expect("changing lines info written successfully");
-
-    // Print info for the relating hexagram (if applicable)
-    if let Some(hexagram_info) = hexagram_info_post_changes {
-        println!("Changes into:\n");
-        hexagram_info
-            .write_to(output)
-            .expect("hexagram info written successfully");
-    }
-}
+    match command {
+        JournalCommands::List => {
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &JournalListView::new(journal.readings()).with_lang(options.settings.lang),
+            )?;
+        }
+        JournalCommands::Show { id } => {
+            let reading = journal.get(id)?;
+            let hexagram = reading.hexagram()?;
 
-fn print_changing_lines_info<T>(
-    output: &mut T,
-    hexagram: &Hexagram,
-    hexagram_info: &HexagramJsonInfo,
-) -> Result<(), std::io::Error>
-where
-    T: WriteColor + Write,
-{
-    // Get a list of changing lines by their positions.
-    let changing_line_positions = hexagram.get_changing_line_positions();
-
-    // If there are no changing lines, return.
-    if !changing_line_positions.is_empty() {
-        writeln!(output)?;
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(130))))
-            .expect("output stream color can be set");
-        writeln!(output, "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "Lines are changing! Consider:")?;
-        // Else, print out the changing line meanings.
-        for line_meaning in hexagram_info.line_meanings(&changing_line_positions) {
-            writeln!(output)?;
-            output
-                .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(220))))
-                .expect("output stream color can be set");
-            writeln!(output, "Line {} changes:", line_meaning.position)?;
-            output.reset().expect("output stream color can be reset");
-            writeln!(output, "{}", line_meaning.meaning)?;
-        }
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(130))))
-            .expect("output stream color can be set");
-        writeln!(output, "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~\n")?;
-        output.reset().expect("output stream color can be reset");
+            let view = JournalEntryView::new(
+                reading.id,
+                reading.timestamp,
+                &reading.notes,
+                ReadingView::new(
+                    reading.question.as_deref(),
+                    reading.method,
+                    &hexagram,
+                    hexagrams,
+                )
+                .with_school(options.settings.school)
+                .with_line_labels(options.settings.line_labels)
+                .with_lang(options.settings.lang)
+                .with_texts(options.settings.texts)
+                .with_commentary(&options.settings.with),
+            );
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &view,
+            )?;
+        }
+        JournalCommands::Note { id, note } => {
+            journal.add_note(id, note)?;
+            journal.save(&path)?;
+        }
+        JournalCommands::Delete { id } => {
+            journal.delete(id)?;
+            journal.save(&path)?;
+            println!(
+                "{}",
+                options
+                    .settings
+                    .lang
+                    .messages()
+                    .format("reading-deleted", &[("id", id.into())])
+            );
+        }
+        JournalCommands::Stats => {
+            let statistics = JournalStatistics::new(journal.readings(), hexagrams)?
+                .with_lang(options.settings.lang);
+            write_view(
+                output,
+                options.settings.format,
+                &options.settings.theme,
+                options.settings.text_width(),
+                &options.settings.image.style(),
+                &statistics,
+            )?;
+        }
     }
 
     Ok(())
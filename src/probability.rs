@@ -0,0 +1,187 @@
+use crate::{
+    divination_method::DivinationMethod,
+    line::{Line, Line::*},
+    Hexagram,
+};
+use num_rational::Ratio;
+
+/// An exact probability, expressed as a fraction.
+pub type Probability = Ratio<u64>;
+
+/// Get the probability of casting exactly this `Hexagram`, including which of its lines are
+/// changing. This is also the probability of receiving the primary and relating hexagrams that
+/// the cast produces.
+pub fn cast_probability(divination_method: DivinationMethod, hexagram: &Hexagram) -> Probability {
+    hexagram
+        .lines()
+        .map(|&line| divination_method.line_probability(line))
+        .product()
+}
+
+/// Get the probability of receiving this `Hexagram` as the primary hexagram of a reading,
+/// whether or not any of its lines are changing. Any changing lines in the given `Hexagram` are
+/// ignored.
+pub fn primary_probability(
+    divination_method: DivinationMethod,
+    hexagram: &Hexagram,
+) -> Probability {
+    hexagram
+        .lines()
+        .map(|line| {
+            let (young, old) = match line {
+                Broken { .. } => (Line::broken(), Line::broken_changing()),
+                Unbroken { .. } => (Line::unbroken(), Line::unbroken_changing()),
+            };
+            divination_method.line_probability(young) + divination_method.line_probability(old)
+        })
+        .product()
+}
+
+/// Get the probability of receiving `primary` as the primary hexagram of a reading and `relating`
+/// as its relating hexagram. Pass `None` as the `relating` hexagram to get the probability of no
+/// lines changing. Any changing lines in the given `Hexagram`s are ignored.
+///
+/// A reading only has a relating hexagram when lines are changing, so the probability of the
+/// relating hexagram being the same as the primary hexagram is zero.
+pub fn pair_probability(
+    divination_method: DivinationMethod,
+    primary: &Hexagram,
+    relating: Option<&Hexagram>,
+) -> Probability {
+    let Some(relating) = relating else {
+        return cast_probability(divination_method, &primary.primary_hexagram());
+    };
+
+    let lines: Vec<Line> = primary
+        .lines()
+        .zip(relating.lines())
+        .map(|pair| match pair {
+            (Broken { .. }, Broken { .. }) => Line::broken(),
+            (Broken { .. }, Unbroken { .. }) => Line::broken_changing(),
+            (Unbroken { .. }, Broken { .. }) => Line::unbroken_changing(),
+            (Unbroken { .. }, Unbroken { .. }) => Line::unbroken(),
+        })
+        .collect();
+
+    if !lines.iter().any(Line::is_changing) {
+        return Probability::from_integer(0);
+    }
+
+    lines
+        .iter()
+        .map(|&line| divination_method.line_probability(line))
+        .product()
+}
+
+/// Get the probability that exactly `count` lines of a cast hexagram are changing.
+pub fn moving_lines_probability(divination_method: DivinationMethod, count: usize) -> Probability {
+    const LINE_COUNT: usize = 6;
+
+    if count > LINE_COUNT {
+        return Probability::from_integer(0);
+    }
+
+    let changing: Probability = divination_method
+        .line_probabilities()
+        .iter()
+        .filter(|(line, _)| line.is_changing())
+        .map(|(_, probability)| probability)
+        .sum();
+    let unchanging = Probability::from_integer(1) - changing;
+
+    // The number of ways to choose which `count` of the lines are changing
+    let combinations = (0..count).fold(1, |combinations, i| {
+        combinations * (LINE_COUNT - i) as u64 / (i + 1) as u64
+    });
+
+    Probability::from_integer(combinations)
+        * changing.pow(count as i32)
+        * unchanging.pow((LINE_COUNT - count) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trigram::Trigram;
+
+    const METHODS: [DivinationMethod; 2] = [
+        DivinationMethod::AncientYarrowStalk,
+        DivinationMethod::CoinToss,
+    ];
+
+    /// Every possible cast, i.e. every combination of the 4 kinds of line in 6 positions.
+    fn all_casts() -> impl Iterator<Item = Hexagram> {
+        (0..4096u32).map(|n| {
+            let values: [u8; 6] =
+                std::array::from_fn(|position| 6 + (n >> (position * 2) & 3) as u8);
+            Hexagram::try_from(values).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_line_probabilities_sum_to_one() {
+        for method in METHODS {
+            let total: Probability = method.line_probabilities().iter().map(|(_, p)| p).sum();
+            assert_eq!(total, Probability::from_integer(1));
+        }
+    }
+
+    #[test]
+    fn test_cast_probabilities_sum_to_one() {
+        for method in METHODS {
+            let total: Probability = all_casts().map(|h| cast_probability(method, &h)).sum();
+            assert_eq!(total, Probability::from_integer(1));
+        }
+    }
+
+    #[test]
+    fn test_moving_lines_probabilities_sum_to_one() {
+        for method in METHODS {
+            let total: Probability = (0..=6).map(|n| moving_lines_probability(method, n)).sum();
+            assert_eq!(total, Probability::from_integer(1));
+        }
+    }
+
+    #[test]
+    fn test_pair_probability_matches_cast_probability() {
+        for method in METHODS {
+            for cast in all_casts() {
+                let primary = cast.primary_hexagram();
+                let relating = cast.relating_hexagram();
+
+                assert_eq!(
+                    pair_probability(method, &primary, relating.as_ref()),
+                    cast_probability(method, &cast)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_probabilities() {
+        let qian = Trigram(Line::unbroken(), Line::unbroken(), Line::unbroken());
+        let kun = Trigram(Line::broken(), Line::broken(), Line::broken());
+        let the_creative = Hexagram::new(qian, qian);
+        let the_receptive = Hexagram::new(kun, kun);
+        let yarrow = DivinationMethod::AncientYarrowStalk;
+
+        // Every primary hexagram is equally likely with both methods
+        assert_eq!(
+            primary_probability(yarrow, &the_creative),
+            Probability::new(1, 64)
+        );
+        // Old yang is three times as likely as old yin with yarrow stalks
+        assert_eq!(
+            pair_probability(yarrow, &the_creative, Some(&the_receptive)),
+            Probability::new(3u64.pow(6), 16u64.pow(6))
+        );
+        assert_eq!(
+            pair_probability(yarrow, &the_receptive, Some(&the_creative)),
+            Probability::new(1, 16u64.pow(6))
+        );
+        assert_eq!(
+            pair_probability(yarrow, &the_creative, Some(&the_creative)),
+            Probability::from_integer(0)
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use crate::{
+    hexagram_json::HexagramJsonInfo,
+    journal_stats::method_name,
+    locale::Lang,
+    render::{self, Column, HeadingLevel, Render, Renderer, SectionRole},
+};
+use iching::{
+    divination_method::DivinationMethod,
+    hexagram_repository::HexagramRepository,
+    line::Line,
+    probability::{self, Probability},
+};
+use num_traits::ToPrimitive;
+use serde_derive::Serialize;
+
+/// The odds of casting each kind of line, each number of moving lines and each primary hexagram
+/// with a method. If a primary hexagram is chosen, the odds of each relating hexagram for that
+/// primary hexagram are included instead of the odds of each primary hexagram.
+#[derive(Serialize)]
+pub(crate) struct ProbabilityTables<'a> {
+    method: DivinationMethod,
+    lines: Vec<LineOdds>,
+    moving_lines: Vec<MovingLinesOdds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    primary_hexagrams: Option<Vec<HexagramOdds<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    primary_hexagram: Option<HexagramOdds<'a>>,
+    /// The odds of receiving both the chosen primary hexagram and each relating hexagram. The
+    /// relating hexagram is `None` when no lines are changing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relating_hexagrams: Option<Vec<RelatingHexagramOdds<'a>>>,
+    #[serde(skip)]
+    lang: Lang,
+}
+
+#[derive(Serialize)]
+struct LineOdds {
+    value: u8,
+    symbol: String,
+    name: &'static str,
+    probability: Odds,
+}
+
+#[derive(Serialize)]
+struct MovingLinesOdds {
+    count: usize,
+    probability: Odds,
+}
+
+#[derive(Serialize)]
+struct HexagramSummary<'a> {
+    number: usize,
+    symbol: &'a str,
+    english: &'a str,
+}
+
+impl<'a> From<&'a HexagramJsonInfo> for HexagramSummary<'a> {
+    fn from(hexagram_info: &'a HexagramJsonInfo) -> Self {
+        HexagramSummary {
+            number: hexagram_info.number(),
+            symbol: hexagram_info.symbol(),
+            english: hexagram_info.english_name(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HexagramOdds<'a> {
+    #[serde(flatten)]
+    hexagram: HexagramSummary<'a>,
+    probability: Odds,
+}
+
+#[derive(Serialize)]
+struct RelatingHexagramOdds<'a> {
+    relating: Option<HexagramSummary<'a>>,
+    probability: Odds,
+}
+
+/// An exact probability, along with its approximate decimal value.
+#[derive(Serialize)]
+struct Odds {
+    numerator: u64,
+    denominator: u64,
+    decimal: f64,
+}
+
+impl From<Probability> for Odds {
+    fn from(probability: Probability) -> Self {
+        Odds {
+            numerator: *probability.numer(),
+            denominator: *probability.denom(),
+            decimal: probability
+                .to_f64()
+                .expect("probabilities are representable as floats"),
+        }
+    }
+}
+
+impl std::fmt::Display for Odds {
+    /// Format the odds as both a fraction and a percentage, e.g. `3/16 (18.7500%)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} ({:.4}%)",
+            self.numerator,
+            self.denominator,
+            self.decimal * 100.0
+        )
+    }
+}
+
+impl std::fmt::Display for HexagramSummary<'_> {
+    /// Format the hexagram's symbol and number, e.g. `䷀ 1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.symbol, self.number)
+    }
+}
+
+impl<'a> ProbabilityTables<'a> {
+    pub(crate) fn new(
+        method: DivinationMethod,
+        hexagram_number: Option<u8>,
+        hexagrams: &'a impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+    ) -> Self {
+        let hexagram_odds = |hexagram_info: &'a HexagramJsonInfo, probability| HexagramOdds {
+            hexagram: hexagram_info.into(),
+            probability: Odds::from(probability),
+        };
+        let all_hexagrams = || {
+            (1..=64).map(|number| {
+                hexagrams
+                    .get_by_number(number)
+                    .expect("repository contains all 64 hexagrams")
+            })
+        };
+
+        let mut tables = ProbabilityTables {
+            method,
+            lines: method
+                .line_probabilities()
+                .into_iter()
+                .map(|(line, probability)| LineOdds {
+                    value: line.value(),
+                    symbol: line.to_string(),
+                    name: line_name(line),
+                    probability: probability.into(),
+                })
+                .collect(),
+            moving_lines: (0..=6)
+                .map(|count| MovingLinesOdds {
+                    count,
+                    probability: probability::moving_lines_probability(method, count).into(),
+                })
+                .collect(),
+            primary_hexagrams: None,
+            primary_hexagram: None,
+            relating_hexagrams: None,
+            lang: Lang::default(),
+        };
+
+        match hexagram_number {
+            None => {
+                tables.primary_hexagrams = Some(
+                    all_hexagrams()
+                        .map(|hexagram_info| {
+                            hexagram_odds(
+                                hexagram_info,
+                                probability::primary_probability(method, hexagram_info.hexagram()),
+                            )
+                        })
+                        .collect(),
+                );
+            }
+            Some(number) => {
+                let primary = hexagrams
+                    .get_by_number(number)
+                    .expect("clap has validated this number already");
+                tables.primary_hexagram = Some(hexagram_odds(
+                    primary,
+                    probability::primary_probability(method, primary.hexagram()),
+                ));
+
+                let no_relating = RelatingHexagramOdds {
+                    relating: None,
+                    probability: probability::pair_probability(method, primary.hexagram(), None)
+                        .into(),
+                };
+                let relating = all_hexagrams().filter_map(|relating| {
+                    let probability = probability::pair_probability(
+                        method,
+                        primary.hexagram(),
+                        Some(relating.hexagram()),
+                    );
+                    (probability != Probability::from_integer(0)).then(|| RelatingHexagramOdds {
+                        relating: Some(relating.into()),
+                        probability: probability.into(),
+                    })
+                });
+                tables.relating_hexagrams =
+                    Some(std::iter::once(no_relating).chain(relating).collect());
+            }
+        }
+
+        tables
+    }
+
+    /// Show the headings in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+}
+
+impl Render for ProbabilityTables<'_> {
+    fn title(&self) -> String {
+        self.lang
+            .messages()
+            .format("odds-title", &[("method", method_name(self.method).into())])
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        let (name, probability) = (
+            messages.get("column-name"),
+            messages.get("column-probability"),
+        );
+        let hexagram = messages.get("column-hexagram");
+        let hexagram_columns = [
+            Column::right(&hexagram),
+            Column::left(&name),
+            Column::left(&probability),
+        ];
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("odds-lines"),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .lines
+            .iter()
+            .map(|line| {
+                vec![
+                    line.value.to_string(),
+                    line.symbol.clone(),
+                    messages.format("odds-line-name", &[("value", line.value.into())]),
+                    line.probability.to_string(),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-value")),
+                Column::left(&messages.get("column-line")),
+                Column::left(&name),
+                Column::left(&probability),
+            ],
+            &rows,
+        )?;
+
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("odds-moving-lines"),
+        )?;
+        let rows: Vec<Vec<String>> = self
+            .moving_lines
+            .iter()
+            .map(|moving_lines| {
+                vec![
+                    moving_lines.count.to_string(),
+                    moving_lines.probability.to_string(),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-moving-lines")),
+                Column::left(&probability),
+            ],
+            &rows,
+        )?;
+
+        if let Some(primary_hexagrams) = &self.primary_hexagrams {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("odds-primary-hexagrams"),
+            )?;
+            let rows: Vec<Vec<String>> = primary_hexagrams
+                .iter()
+                .map(|odds| {
+                    vec![
+                        odds.hexagram.to_string(),
+                        odds.hexagram.english.to_owned(),
+                        odds.probability.to_string(),
+                    ]
+                })
+                .collect();
+            renderer.table(&hexagram_columns, &rows)?;
+        }
+
+        if let Some(primary) = &self.primary_hexagram {
+            renderer.paragraph(&messages.format(
+                "odds-primary-hexagram",
+                &[
+                    ("hexagram", primary.hexagram.to_string().into()),
+                    ("name", primary.hexagram.english.into()),
+                    ("probability", primary.probability.to_string().into()),
+                ],
+            ))?;
+        }
+
+        if let Some(relating_hexagrams) = &self.relating_hexagrams {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("odds-relating-hexagrams"),
+            )?;
+            renderer.paragraph(&messages.get("odds-relating-explanation"))?;
+            let rows: Vec<Vec<String>> = relating_hexagrams
+                .iter()
+                .map(|odds| match &odds.relating {
+                    Some(hexagram) => vec![
+                        hexagram.to_string(),
+                        hexagram.english.to_owned(),
+                        odds.probability.to_string(),
+                    ],
+                    None => vec![
+                        String::new(),
+                        messages.get("odds-no-relating"),
+                        odds.probability.to_string(),
+                    ],
+                })
+                .collect();
+            renderer.table(&hexagram_columns, &rows)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The traditional name of a kind of line, e.g. "old yang".
+fn line_name(line: Line) -> &'static str {
+    match line {
+        Line::Broken { changing: true } => "old yin",
+        Line::Broken { changing: false } => "young yin",
+        Line::Unbroken { changing: true } => "old yang",
+        Line::Unbroken { changing: false } => "young yang",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hexagram_json::HexagramJson, render::MarkdownRenderer};
+
+    fn hexagrams() -> HexagramJson {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        hexagrams
+    }
+
+    fn sum<'a>(odds: impl Iterator<Item = &'a Odds>) -> Probability {
+        odds.map(|odds| Probability::new(odds.numerator, odds.denominator))
+            .sum()
+    }
+
+    #[test]
+    fn test_odds_sum_to_one_and_match_the_line_weights() {
+        let hexagrams = hexagrams();
+        for method in [
+            DivinationMethod::AncientYarrowStalk,
+            DivinationMethod::CoinToss,
+        ] {
+            let tables = ProbabilityTables::new(method, None, &hexagrams);
+            let one = Probability::from_integer(1);
+
+            assert_eq!(sum(tables.lines.iter().map(|line| &line.probability)), one);
+            assert_eq!(
+                sum(tables.moving_lines.iter().map(|moving| &moving.probability)),
+                one
+            );
+            let primary_hexagrams = tables.primary_hexagrams.as_ref().unwrap();
+            assert_eq!(primary_hexagrams.len(), 64);
+            assert_eq!(
+                sum(primary_hexagrams.iter().map(|odds| &odds.probability)),
+                one
+            );
+        }
+
+        let tables = ProbabilityTables::new(DivinationMethod::AncientYarrowStalk, None, &hexagrams);
+        let lines: Vec<_> = tables
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.value,
+                    line.name,
+                    line.probability.numerator,
+                    line.probability.denominator,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (6, "old yin", 1, 16),
+                (8, "young yin", 7, 16),
+                (9, "old yang", 3, 16),
+                (7, "young yang", 5, 16),
+            ]
+        );
+
+        let mut output = Vec::new();
+        tables
+            .render(&mut MarkdownRenderer::new(&mut output))
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("| old yin | 1/16 (6.2500%) |"));
+        assert!(output.contains("| young yang | 5/16 (31.2500%) |"));
+    }
+
+    #[test]
+    fn test_relating_hexagrams_add_up_to_the_primary_hexagram() {
+        let hexagrams = hexagrams();
+        let method = DivinationMethod::AncientYarrowStalk;
+        let tables = ProbabilityTables::new(method, Some(1), &hexagrams);
+
+        let primary = tables.primary_hexagram.as_ref().unwrap();
+        assert_eq!(primary.hexagram.number, 1);
+        assert_eq!(
+            Probability::new(
+                primary.probability.numerator,
+                primary.probability.denominator
+            ),
+            Probability::new(1, 64)
+        );
+
+        // Any of the other 63 hexagrams can be reached by changing lines, or none at all.
+        let relating_hexagrams = tables.relating_hexagrams.as_ref().unwrap();
+        assert_eq!(relating_hexagrams.len(), 64);
+        assert!(relating_hexagrams[0].relating.is_none());
+        assert_eq!(
+            Probability::new(
+                relating_hexagrams[0].probability.numerator,
+                relating_hexagrams[0].probability.denominator
+            ),
+            Probability::new(5u64.pow(6), 16u64.pow(6))
+        );
+        assert!(relating_hexagrams[1..]
+            .iter()
+            .all(|odds| odds.relating.as_ref().unwrap().number != 1));
+        assert_eq!(
+            sum(relating_hexagrams.iter().map(|odds| &odds.probability)),
+            Probability::new(1, 64)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Export of the app's SVG images as files, such as `divine --output reading.png`.
+//!
+//! PNG images are drawn with [`resvg`], which doesn't need anything but the app itself: the fonts
+//! installed on the system are never loaded, so the same image is drawn on every system. Text is
+//! set in DejaVu Sans, which covers English, pinyin and the hexagram symbols, and Chinese
+//! characters in IChing Hanzi. That font only has the characters of the names of the hexagrams
+//! and trigrams and of the title of the hexagram of the day, and is written by
+//! `src/fonts/make_hanzi.py`. Both fonts are embedded in the app.
+
+use crate::render::Render;
+use iching::svg::SvgStyle;
+use resvg::{tiny_skia, usvg};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The font that text in exported images is set in.
+pub(crate) const FONT: &[u8] = include_bytes!("fonts/DejaVuSans.ttf");
+pub(crate) const FONT_FAMILY: &str = "DejaVu Sans";
+/// The font that the Chinese names of the hexagrams and trigrams are set in, which text falls back
+/// to for the characters that [`FONT`] lacks.
+const HANZI_FONT: &[u8] = include_bytes!("fonts/IChingHanzi.ttf");
+/// PNG images are drawn at this multiple of their SVG size, so that they stay sharp on high
+/// density screens.
+const PNG_SCALE: f32 = 2.0;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Can't tell which format to save {} in. Use a file name ending in .png or .svg", .0.display())]
+    UnknownFormat(PathBuf),
+    #[error("There's nothing to draw for this output")]
+    NothingToDraw,
+    #[error("Failed to draw the image: {0}")]
+    Svg(#[from] usvg::Error),
+    #[error("The image is too big or too small to draw")]
+    InvalidSize,
+    #[error("Failed to encode the image as PNG: {0}")]
+    Png(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to save the image to {}: {1}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+}
+
+/// The formats that images can be exported in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    /// Pick the format of an image by the extension of its file name.
+    pub(crate) fn from_path(path: &Path) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("png") => Ok(ImageFormat::Png),
+            Some("svg") => Ok(ImageFormat::Svg),
+            _ => Err(Error::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+/// The style of exported images: the given style, with text set in the embedded font.
+pub(crate) fn image_style(style: &SvgStyle) -> SvgStyle {
+    SvgStyle {
+        font_family: FONT_FAMILY.to_owned(),
+        ..style.clone()
+    }
+}
+
+/// Draw a view in the given style and save it to `path`, in the format picked by
+/// [`ImageFormat::from_path`].
+pub(crate) fn save_image(path: &Path, view: &impl Render, style: &SvgStyle) -> Result<(), Error> {
+    let format = ImageFormat::from_path(path)?;
+    let svg = view.svg(&image_style(style)).ok_or(Error::NothingToDraw)?;
+    let data = match format {
+        ImageFormat::Png => svg_to_png(&svg)?,
+        ImageFormat::Svg => svg.into_bytes(),
+    };
+
+    std::fs::write(path, data).map_err(|err| Error::Io(path.to_owned(), err))
+}
+
+/// Draw an SVG image as a PNG image.
+pub(crate) fn svg_to_png(svg: &str) -> Result<Vec<u8>, Error> {
+    draw(svg)?
+        .encode_png()
+        .map_err(|err| Error::Png(err.into()))
+}
+
+/// Draw an SVG image, with text set in the embedded fonts only.
+fn draw(svg: &str) -> Result<tiny_skia::Pixmap, Error> {
+    let mut options = usvg::Options {
+        font_family: FONT_FAMILY.to_owned(),
+        ..usvg::Options::default()
+    };
+    let fonts = Arc::make_mut(&mut options.fontdb);
+    fonts.load_font_data(FONT.to_vec());
+    fonts.load_font_data(HANZI_FONT.to_vec());
+
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree.size().to_int_size().scale_by(PNG_SCALE);
+    let mut pixmap = size
+        .and_then(|size| tiny_skia::Pixmap::new(size.width(), size.height()))
+        .ok_or(Error::InvalidSize)?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(PNG_SCALE, PNG_SCALE),
+        &mut pixmap.as_mut(),
+    );
+
+    Ok(pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hexagram_json::HexagramJson, render::Render, view::ReadingView};
+    use iching::{
+        divination_method::DivinationMethod, hexagram::Hexagram,
+        hexagram_repository::HexagramRepository, svg, trigram::Trigram,
+    };
+
+    #[test]
+    fn test_format_is_picked_by_extension() {
+        assert_eq!(
+            ImageFormat::from_path(Path::new("reading.PNG")).unwrap(),
+            ImageFormat::Png
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("reading.svg")).unwrap(),
+            ImageFormat::Svg
+        );
+        assert!(ImageFormat::from_path(Path::new("reading.jpg")).is_err());
+        assert!(ImageFormat::from_path(Path::new("reading")).is_err());
+    }
+
+    #[test]
+    fn test_svg_is_drawn_as_png() {
+        let hexagram = Hexagram::try_from([7, 7, 7, 7, 7, 9]).unwrap();
+        let png = svg_to_png(&svg::hexagram(
+            &hexagram,
+            &image_style(&SvgStyle::default()),
+        ))
+        .unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    /// Draw a line of text in the embedded fonts, as the pixels of the image.
+    fn draw_text(text: &str) -> Vec<u8> {
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="240" height="40"><text x="4" y="32" font-family="{FONT_FAMILY}" font-size="32">{text}</text></svg>"#
+        );
+        draw(&svg).unwrap().data().to_vec()
+    }
+
+    /// Whether any pixel of an image is drawn.
+    fn has_ink(pixels: &[u8]) -> bool {
+        pixels.chunks(4).any(|pixel| pixel[3] > 0)
+    }
+
+    #[test]
+    fn test_chinese_names_are_drawn_in_the_embedded_font() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let mut characters: Vec<char> = (1..=64)
+            .flat_map(|number| {
+                let name = hexagrams.get_by_number(number).unwrap().name();
+                format!("{}{}", name.chinese, name.chinese_simplified)
+                    .chars()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        for number in 1..=8u8 {
+            let trigram = Trigram::try_from(number).unwrap();
+            characters.extend(trigram.chinese().chars());
+            characters.extend(trigram.chinese_simplified().chars());
+        }
+        // The title of the hexagram of the day, without its date.
+        characters.extend("的每日一卦".chars());
+        characters.sort();
+        characters.dedup();
+        // 丁 isn't in either font, so it's drawn as the missing glyph of DejaVu Sans, a box.
+        let missing = draw_text("丁");
+
+        for character in characters {
+            let drawn = draw_text(&character.to_string());
+            assert!(has_ink(&drawn), "{character} isn't drawn");
+            assert!(drawn != missing, "{character} is drawn as a box");
+        }
+    }
+
+    #[test]
+    fn test_cards_are_drawn_with_only_the_embedded_fonts() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let hexagram = Hexagram::try_from([7, 8, 9, 6, 7, 8]).unwrap();
+        let view = ReadingView::new(
+            Some("Will it rain?"),
+            DivinationMethod::AncientYarrowStalk,
+            &hexagram,
+            &hexagrams,
+        );
+        let svg = view.svg(&image_style(&SvgStyle::default())).unwrap();
+        let primary = hexagrams.get_info_for_hexagram(&hexagram).name();
+        // The same card, with the Chinese name in characters that neither font has.
+        let boxes = svg.replace(
+            &primary.chinese,
+            &"丁".repeat(primary.chinese.chars().count()),
+        );
+
+        let png = svg_to_png(&svg).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(png, svg_to_png(&svg).unwrap());
+        assert!(svg != boxes);
+        assert!(draw(&svg).unwrap().data() != draw(&boxes).unwrap().data());
+    }
+}
@@ -0,0 +1,171 @@
+//! Rendering of the app's views as text, Markdown or HTML, or for the terminal UI.
+//!
+//! Each view describes its content once, by implementing [`Render`] in terms of the building
+//! blocks offered by [`Renderer`]: headings, verses, paragraphs, lists, links, tables and figures. Each
+//! [`Renderer`] decides how those building blocks look in its format, so supporting a new format
+//! only takes a new [`Renderer`].
+
+pub(crate) mod figure;
+pub(crate) mod html;
+mod markdown;
+mod text;
+pub(crate) mod theme;
+mod tui;
+
+use crate::locale::Lang;
+use iching::svg::SvgStyle;
+
+pub(crate) use html::HtmlRenderer;
+pub(crate) use markdown::MarkdownRenderer;
+pub(crate) use text::TextRenderer;
+pub(crate) use theme::Theme;
+pub(crate) use tui::TuiRenderer;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("failed to write output: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to set the color of the output: {0}")]
+    Color(#[source] std::io::Error),
+}
+
+/// The importance of a heading. Every view starts with a [`HeadingLevel::Title`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HeadingLevel {
+    Title,
+    Section(SectionRole),
+    Subsection,
+}
+
+/// What a section holds, which picks the style that themes give its heading.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SectionRole {
+    Judgement,
+    Image,
+    Lines,
+    /// Any other section, such as a commentary or a table, which is styled as a judgement.
+    Other,
+}
+
+/// How the cells of a table column are aligned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Alignment {
+    Left,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Column<'a> {
+    pub(crate) heading: &'a str,
+    pub(crate) alignment: Alignment,
+}
+
+impl<'a> Column<'a> {
+    pub(crate) const fn left(heading: &'a str) -> Self {
+        Column {
+            heading,
+            alignment: Alignment::Left,
+        }
+    }
+
+    pub(crate) const fn right(heading: &'a str) -> Self {
+        Column {
+            heading,
+            alignment: Alignment::Right,
+        }
+    }
+}
+
+/// A piece of a row of a [`Renderer::figure`] or of a [`Renderer::snippet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Span {
+    pub(crate) text: String,
+    /// Highlighted spans are drawn in a different color, e.g. to mark changing lines.
+    pub(crate) highlighted: bool,
+}
+
+/// A link to another document, such as another page of the [book](crate::book).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Link {
+    pub(crate) text: String,
+    /// The address of the linked document, relative to the document that links to it.
+    pub(crate) target: String,
+}
+
+/// The building blocks that views are rendered with.
+pub(crate) trait Renderer {
+    /// Start a document in the given language. Called once, before anything else is rendered.
+    fn begin_document(&mut self, _title: &str, _lang: Lang) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Finish a document. Called once, after everything else has been rendered.
+    fn end_document(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Mark a place in the document that [`Link`]s can point to, as `#id`. Formats that can't
+    /// link ignore anchors.
+    fn anchor(&mut self, _id: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn heading(&mut self, level: HeadingLevel, text: &str) -> Result<(), Error>;
+
+    /// A line of secondary text directly below a title, such as a translated name.
+    fn subtitle(&mut self, text: &str) -> Result<(), Error>;
+
+    /// Text quoted from the I Ching, whose line breaks are kept.
+    fn verse(&mut self, text: &str) -> Result<(), Error>;
+
+    fn paragraph(&mut self, text: &str) -> Result<(), Error>;
+
+    /// A quoted piece of text in which some spans are highlighted, such as a search result.
+    fn snippet(&mut self, spans: &[Span]) -> Result<(), Error>;
+
+    fn list(&mut self, items: &[String]) -> Result<(), Error>;
+
+    /// A list of links to other documents. Formats that can't link show only the text of each
+    /// link.
+    fn links(&mut self, links: &[Link]) -> Result<(), Error>;
+
+    fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) -> Result<(), Error>;
+
+    /// Preformatted text art, such as the big hexagrams drawn by [`figure::big_hexagrams`]. Each
+    /// row is made of spans, which are drawn next to each other.
+    fn figure(&mut self, rows: &[Vec<Span>]) -> Result<(), Error>;
+
+    /// Start a block of content that's set apart from the rest, such as the changing lines of a
+    /// reading.
+    fn begin_aside(&mut self, title: &str) -> Result<(), Error>;
+
+    fn end_aside(&mut self) -> Result<(), Error>;
+}
+
+/// Views that can be drawn by a [`Renderer`].
+pub(crate) trait Render {
+    /// The title of the document that this view is rendered as.
+    fn title(&self) -> String;
+
+    /// The language that this view's labels are shown in.
+    fn lang(&self) -> Lang {
+        Lang::default()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), Error>;
+
+    /// Draw this view as an SVG image, if it has something to draw.
+    fn svg(&self, _style: &SvgStyle) -> Option<String> {
+        None
+    }
+}
+
+/// Render a view as a whole document.
+pub(crate) fn render_document(
+    renderer: &mut dyn Renderer,
+    view: &(impl Render + ?Sized),
+) -> Result<(), Error> {
+    renderer.begin_document(&view.title(), view.lang())?;
+    view.render(renderer)?;
+    renderer.end_document()
+}
@@ -0,0 +1,219 @@
+//! Figures of hexagrams: big ASCII-art ones, drawn with [`Line::big_rows`], and small ones that
+//! take a row per line.
+
+use super::Span;
+use iching::{hexagram::Hexagram, line::Line};
+use unicode_width::UnicodeWidthStr;
+
+/// Drawn between the primary and relating hexagrams.
+const GAP: &str = "       ";
+/// Drawn between a changing line of the primary hexagram and the line that it changes into.
+const ARROW: &str = "  ──▶  ";
+const SMALL_UNBROKEN: &str = "━━━━━━━━━";
+const SMALL_BROKEN: &str = "━━━━ ━━━━";
+
+/// Draw a hexagram as big lines, from the top line down. If any lines are changing, the relating
+/// hexagram is drawn to the right of it, with the changing lines highlighted in both.
+///
+/// Each of the `labels` is drawn above the hexagram in the same column, e.g. the names of the
+/// primary and relating hexagrams.
+pub(crate) fn big_hexagrams(hexagram: &Hexagram, labels: &[&str]) -> Vec<Vec<Span>> {
+    let primary_lines: Vec<Line> = hexagram.lines().copied().collect();
+    let relating_lines: Option<Vec<Line>> = hexagram
+        .relating_hexagram()
+        .map(|relating| relating.lines().copied().collect());
+
+    // The art is padded with a margin on each side, which is wasted space when hexagrams are
+    // side by side.
+    let all_rows = || {
+        primary_lines
+            .iter()
+            .chain(relating_lines.iter().flatten())
+            .flat_map(|line| line.big_rows())
+    };
+    let margin = all_rows()
+        .filter(|row| !row.trim().is_empty())
+        .map(|row| row.len() - row.trim_start().len())
+        .min()
+        .unwrap_or_default();
+    let width = all_rows()
+        .map(|row| row.trim_end().chars().count().saturating_sub(margin))
+        .max()
+        .unwrap_or_default();
+    let trim = |row: &str| -> String {
+        let row: String = row.chars().skip(margin).take(width).collect();
+        format!("{row:width$}")
+    };
+
+    let mut rows = Vec::new();
+    if !labels.is_empty() {
+        let label_row = labels
+            .iter()
+            .map(|label| {
+                let padding = width.saturating_sub(label.width());
+                format!("{label}{}", " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join(GAP);
+        rows.push(vec![plain(label_row.trim_end())]);
+        rows.push(Vec::new());
+    }
+
+    for (index, primary) in primary_lines.iter().enumerate() {
+        let changing = primary.is_changing();
+        let relating = relating_lines.as_ref().map(|lines| lines[index]);
+        let line_rows: Vec<&str> = primary.big_rows().collect();
+        // The bars of each line are drawn in the middle rows of its art.
+        let arrow_row = line_rows.len().saturating_sub(1) / 2;
+
+        for (row_index, row) in line_rows.into_iter().enumerate() {
+            let mut spans = vec![Span {
+                text: trim(row),
+                highlighted: changing,
+            }];
+
+            if let Some(relating) = relating {
+                let relating_row = relating
+                    .big_rows()
+                    .nth(row_index)
+                    .expect("all big lines have the same number of rows");
+                spans.push(Span {
+                    text: String::from(if changing && row_index == arrow_row {
+                        ARROW
+                    } else {
+                        GAP
+                    }),
+                    highlighted: changing,
+                });
+                spans.push(Span {
+                    text: trim(relating_row),
+                    highlighted: changing,
+                });
+            }
+
+            // Trailing whitespace would be invisible anyway.
+            while spans
+                .last()
+                .is_some_and(|span| span.text.trim_end().is_empty())
+            {
+                spans.pop();
+            }
+            if let Some(last) = spans.last_mut() {
+                last.text.truncate(last.text.trim_end().len());
+            }
+            rows.push(spans);
+        }
+    }
+
+    rows
+}
+
+/// Draw a hexagram as small lines, a row per line from the top line down, each row starting with
+/// the line's position. If any lines are changing, the relating hexagram is drawn to the right of
+/// it, with the changing lines highlighted in both.
+pub(crate) fn small_hexagrams(hexagram: &Hexagram) -> Vec<Vec<Span>> {
+    let small = |line: &Line| match line {
+        Line::Unbroken { .. } => SMALL_UNBROKEN,
+        Line::Broken { .. } => SMALL_BROKEN,
+    };
+    let relating = hexagram.relating_hexagram();
+
+    hexagram
+        .lines()
+        .zip((1..=6).rev())
+        .enumerate()
+        .map(|(index, (line, position))| {
+            let changing = line.is_changing();
+            let mut spans = vec![
+                plain(&format!("{position}  ")),
+                Span {
+                    text: small(line).to_owned(),
+                    highlighted: changing,
+                },
+            ];
+            if let Some(relating) = &relating {
+                let relating_line = relating
+                    .lines()
+                    .nth(index)
+                    .expect("hexagrams have six lines");
+                spans.push(Span {
+                    text: String::from(if changing { ARROW } else { GAP }),
+                    highlighted: changing,
+                });
+                spans.push(Span {
+                    text: small(relating_line).to_owned(),
+                    highlighted: changing,
+                });
+            }
+            spans
+        })
+        .collect()
+}
+
+fn plain(text: &str) -> Span {
+    Span {
+        text: text.to_owned(),
+        highlighted: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_text(row: &[Span]) -> String {
+        row.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_relating_hexagram_is_drawn_beside_primary() {
+        // Only the bottom line is changing.
+        let hexagram = Hexagram::try_from([9, 7, 7, 7, 7, 7]).unwrap();
+        let rows = big_hexagrams(&hexagram, &["primary", "relating"]);
+
+        assert!(row_text(&rows[0]).starts_with("primary"));
+        assert!(row_text(&rows[0]).contains("relating"));
+
+        let line_rows = &rows[2..];
+        assert_eq!(line_rows.len(), 6 * 6);
+        // The top five lines aren't changing, so they're drawn the same way on both sides and
+        // aren't highlighted.
+        for row in &line_rows[..30] {
+            assert!(row.iter().all(|span| !span.highlighted));
+            assert!(!row_text(row).contains('▶'));
+        }
+        for row in &line_rows[30..] {
+            assert!(row.iter().all(|span| span.highlighted));
+        }
+        assert!(line_rows[30..]
+            .iter()
+            .any(|row| row_text(row).contains(ARROW)));
+    }
+
+    #[test]
+    fn test_hexagram_without_changing_lines_is_drawn_alone() {
+        let hexagram = Hexagram::try_from([7, 8, 7, 8, 7, 8]).unwrap();
+        let rows = big_hexagrams(&hexagram, &[]);
+
+        assert_eq!(rows.len(), 6 * 6);
+        assert!(rows.iter().all(|row| row.len() <= 1));
+    }
+
+    #[test]
+    fn test_small_hexagrams_have_a_row_per_line() {
+        // Only the top line is changing.
+        let hexagram = Hexagram::try_from([7, 8, 7, 8, 7, 6]).unwrap();
+        let rows = small_hexagrams(&hexagram);
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(
+            row_text(&rows[0]),
+            format!("6  {SMALL_BROKEN}{ARROW}{SMALL_UNBROKEN}")
+        );
+        assert!(rows[0][1..].iter().all(|span| span.highlighted));
+        assert_eq!(
+            row_text(&rows[5]),
+            format!("1  {SMALL_UNBROKEN}{GAP}{SMALL_UNBROKEN}")
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use super::{Alignment, Column, Error, HeadingLevel, Link, Renderer, Span};
+use crate::locale::Lang;
+use std::io::Write;
+
+pub(crate) const STYLE: &str = "\
+body { font-family: serif; max-width: 40em; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+h1 { color: #c00; }
+h2 { color: #a00; }
+h3 { color: #b80; }
+.subtitle { margin: 0; font-style: italic; }
+aside { border-top: 2px solid #a50; border-bottom: 2px solid #a50; margin: 1em 0; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.6em; }
+.right { text-align: right; }
+pre.figure { line-height: 1; }
+.highlighted { color: #b80; }";
+
+/// Renders views as a standalone HTML document.
+pub(crate) struct HtmlRenderer<W> {
+    output: W,
+    /// The stylesheet that XHTML documents link to. HTML documents embed [`STYLE`] instead.
+    xhtml_stylesheet: Option<&'static str>,
+}
+
+impl<W: Write> HtmlRenderer<W> {
+    pub(crate) fn new(output: W) -> Self {
+        HtmlRenderer {
+            output,
+            xhtml_stylesheet: None,
+        }
+    }
+
+    /// Render views as XHTML documents instead, as used by EPUB books, which link to the
+    /// `stylesheet` at the given address.
+    pub(crate) fn xhtml(output: W, stylesheet: &'static str) -> Self {
+        HtmlRenderer {
+            output,
+            xhtml_stylesheet: Some(stylesheet),
+        }
+    }
+
+    /// Escape text, keeping its line breaks.
+    fn escape_lines(&self, text: &str) -> String {
+        let line_break = match self.xhtml_stylesheet {
+            None => "<br>\n",
+            Some(_) => "<br />\n",
+        };
+        escape(text).replace('\n', line_break)
+    }
+}
+
+/// Escape text so that it can be included in HTML.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn cell_class(alignment: Alignment) -> &'static str {
+    match alignment {
+        Alignment::Left => "",
+        Alignment::Right => " class=\"right\"",
+    }
+}
+
+impl<W: Write> Renderer for HtmlRenderer<W> {
+    fn begin_document(&mut self, title: &str, lang: Lang) -> Result<(), Error> {
+        let lang = lang.tag();
+        match self.xhtml_stylesheet {
+            None => writeln!(
+                self.output,
+                "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>",
+                escape(title)
+            )?,
+            Some(stylesheet) => writeln!(
+                self.output,
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n\
+                 <html xmlns=\"http://www.w3.org/1999/xhtml\" lang=\"{lang}\" xml:lang=\"{lang}\">\n\
+                 <head>\n<meta charset=\"utf-8\" />\n<title>{}</title>\n\
+                 <link rel=\"stylesheet\" type=\"text/css\" href=\"{}\" />\n</head>\n<body>",
+                escape(title),
+                escape(stylesheet)
+            )?,
+        }
+        Ok(())
+    }
+
+    fn end_document(&mut self) -> Result<(), Error> {
+        writeln!(self.output, "</body>\n</html>")?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn anchor(&mut self, id: &str) -> Result<(), Error> {
+        writeln!(self.output, "<a id=\"{}\"></a>", escape(id))?;
+        Ok(())
+    }
+
+    fn heading(&mut self, level: HeadingLevel, text: &str) -> Result<(), Error> {
+        let tag = match level {
+            HeadingLevel::Title => "h1",
+            HeadingLevel::Section(_) => "h2",
+            HeadingLevel::Subsection => "h3",
+        };
+        writeln!(self.output, "<{tag}>{}</{tag}>", escape(text))?;
+        Ok(())
+    }
+
+    fn subtitle(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.output, "<p class=\"subtitle\">{}</p>", escape(text))?;
+        Ok(())
+    }
+
+    fn verse(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(
+            self.output,
+            "<blockquote><p>{}</p></blockquote>",
+            self.escape_lines(text)
+        )?;
+        Ok(())
+    }
+
+    fn paragraph(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.output, "<p>{}</p>", self.escape_lines(text))?;
+        Ok(())
+    }
+
+    fn snippet(&mut self, spans: &[Span]) -> Result<(), Error> {
+        write!(self.output, "<blockquote><p>")?;
+        for span in spans {
+            if span.highlighted {
+                write!(self.output, "<mark>{}</mark>", escape(&span.text))?;
+            } else {
+                write!(self.output, "{}", escape(&span.text))?;
+            }
+        }
+        writeln!(self.output, "</p></blockquote>")?;
+        Ok(())
+    }
+
+    fn list(&mut self, items: &[String]) -> Result<(), Error> {
+        writeln!(self.output, "<ul>")?;
+        for item in items {
+            writeln!(self.output, "<li>{}</li>", self.escape_lines(item))?;
+        }
+        writeln!(self.output, "</ul>")?;
+        Ok(())
+    }
+
+    fn links(&mut self, links: &[Link]) -> Result<(), Error> {
+        writeln!(self.output, "<ul>")?;
+        for link in links {
+            writeln!(
+                self.output,
+                "<li><a href=\"{}\">{}</a></li>",
+                escape(&link.target),
+                escape(&link.text)
+            )?;
+        }
+        writeln!(self.output, "</ul>")?;
+        Ok(())
+    }
+
+    fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) -> Result<(), Error> {
+        writeln!(self.output, "<table>\n<thead>\n<tr>")?;
+        for column in columns {
+            writeln!(
+                self.output,
+                "<th{}>{}</th>",
+                cell_class(column.alignment),
+                escape(column.heading)
+            )?;
+        }
+        writeln!(self.output, "</tr>\n</thead>\n<tbody>")?;
+        for row in rows {
+            write!(self.output, "<tr>")?;
+            for (cell, column) in row.iter().zip(columns) {
+                write!(
+                    self.output,
+                    "<td{}>{}</td>",
+                    cell_class(column.alignment),
+                    escape(cell)
+                )?;
+            }
+            writeln!(self.output, "</tr>")?;
+        }
+        writeln!(self.output, "</tbody>\n</table>")?;
+        Ok(())
+    }
+
+    fn figure(&mut self, rows: &[Vec<Span>]) -> Result<(), Error> {
+        write!(self.output, "<pre class=\"figure\">")?;
+        for row in rows {
+            for span in row {
+                if span.highlighted {
+                    write!(
+                        self.output,
+                        "<span class=\"highlighted\">{}</span>",
+                        escape(&span.text)
+                    )?;
+                } else {
+                    write!(self.output, "{}", escape(&span.text))?;
+                }
+            }
+            writeln!(self.output)?;
+        }
+        writeln!(self.output, "</pre>")?;
+        Ok(())
+    }
+
+    fn begin_aside(&mut self, title: &str) -> Result<(), Error> {
+        writeln!(
+            self.output,
+            "<aside>\n<p><strong>{}</strong></p>",
+            escape(title)
+        )?;
+        Ok(())
+    }
+
+    fn end_aside(&mut self) -> Result<(), Error> {
+        writeln!(self.output, "</aside>")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Alignment, Column, Error, HeadingLevel, Link, Renderer, Span};
+use std::io::Write;
+
+/// Renders views as [CommonMark](https://commonmark.org/), with tables as in GitHub Flavored
+/// Markdown.
+pub(crate) struct MarkdownRenderer<W> {
+    output: W,
+}
+
+impl<W: Write> MarkdownRenderer<W> {
+    pub(crate) fn new(output: W) -> Self {
+        MarkdownRenderer { output }
+    }
+}
+
+/// Escape the characters that would end a table cell early.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape the characters that would end the text of a link early.
+fn link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+impl<W: Write> Renderer for MarkdownRenderer<W> {
+    fn end_document(&mut self) -> Result<(), Error> {
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn anchor(&mut self, id: &str) -> Result<(), Error> {
+        // CommonMark has no anchors of its own, but allows inline HTML.
+        writeln!(self.output, "<a id=\"{id}\"></a>\n")?;
+        Ok(())
+    }
+
+    fn heading(&mut self, level: HeadingLevel, text: &str) -> Result<(), Error> {
+        let marker = match level {
+            HeadingLevel::Title => "#",
+            HeadingLevel::Section(_) => "##",
+            HeadingLevel::Subsection => "###",
+        };
+        writeln!(self.output, "{marker} {text}\n")?;
+        Ok(())
+    }
+
+    fn subtitle(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.output, "*{text}*\n")?;
+        Ok(())
+    }
+
+    fn verse(&mut self, text: &str) -> Result<(), Error> {
+        // Two trailing spaces make a hard line break.
+        for line in text.lines() {
+            writeln!(self.output, "> {line}  ")?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn paragraph(&mut self, text: &str) -> Result<(), Error> {
+        writeln!(self.output, "{text}\n")?;
+        Ok(())
+    }
+
+    fn snippet(&mut self, spans: &[Span]) -> Result<(), Error> {
+        write!(self.output, "> ")?;
+        for span in spans {
+            if span.highlighted {
+                write!(self.output, "**{}**", span.text)?;
+            } else {
+                write!(self.output, "{}", span.text)?;
+            }
+        }
+        writeln!(self.output, "\n")?;
+        Ok(())
+    }
+
+    fn list(&mut self, items: &[String]) -> Result<(), Error> {
+        for item in items {
+            writeln!(self.output, "- {}", item.replace('\n', "  \n  "))?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn links(&mut self, links: &[Link]) -> Result<(), Error> {
+        for link in links {
+            writeln!(
+                self.output,
+                "- [{}]({})",
+                link_text(&link.text),
+                link.target
+            )?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) -> Result<(), Error> {
+        let header: Vec<&str> = columns.iter().map(|column| column.heading).collect();
+        let delimiters: Vec<&str> = columns
+            .iter()
+            .map(|column| match column.alignment {
+                Alignment::Left => "---",
+                Alignment::Right => "--:",
+            })
+            .collect();
+
+        writeln!(self.output, "| {} |", header.join(" | "))?;
+        writeln!(self.output, "| {} |", delimiters.join(" | "))?;
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|cell| table_cell(cell)).collect();
+            writeln!(self.output, "| {} |", cells.join(" | "))?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn figure(&mut self, rows: &[Vec<Span>]) -> Result<(), Error> {
+        writeln!(self.output, "```text")?;
+        for row in rows {
+            for span in row {
+                write!(self.output, "{}", span.text)?;
+            }
+            writeln!(self.output)?;
+        }
+        writeln!(self.output, "```\n")?;
+        Ok(())
+    }
+
+    fn begin_aside(&mut self, title: &str) -> Result<(), Error> {
+        writeln!(self.output, "---\n\n**{title}**\n")?;
+        Ok(())
+    }
+
+    fn end_aside(&mut self) -> Result<(), Error> {
+        writeln!(self.output, "---\n")?;
+        Ok(())
+    }
+}
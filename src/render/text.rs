@@ -0,0 +1,377 @@
+use super::{Alignment, Column, Error, HeadingLevel, Link, Renderer, Span, Theme};
+use termcolor::{ColorSpec, WriteColor};
+use unicode_width::UnicodeWidthStr;
+
+const ASIDE_RULE: &str = "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~";
+/// The indent of quoted text. Terminals draw tabs 8 columns wide.
+const INDENT: &str = "\t";
+const INDENT_WIDTH: usize = 8;
+/// The extra indent of the lines that a long line is wrapped onto, so that they hang below it.
+const HANGING_INDENT: &str = "  ";
+/// Text isn't wrapped narrower than this, however narrow the terminal.
+const MIN_WIDTH: usize = 20;
+
+/// Renders views as text for the terminal, in the colors of a [`Theme`]. Whether the text is
+/// colorful depends on the [`WriteColor`] that it's written to, e.g. [`termcolor::Ansi`] or
+/// [`termcolor::NoColor`].
+///
+/// Text is written as it is, unless a width is given [`with_width`](TextRenderer::with_width).
+/// Then quoted text, paragraphs and lists are reflowed to fit, with the lines that each long line
+/// is wrapped onto hanging below it. Widths are measured in terminal columns, so Chinese
+/// characters count twice.
+pub(crate) struct TextRenderer<W> {
+    output: W,
+    theme: Theme,
+    width: Option<usize>,
+    after_subtitle: bool,
+}
+
+impl<W: WriteColor> TextRenderer<W> {
+    pub(crate) fn new(output: W, theme: &Theme) -> Self {
+        TextRenderer {
+            output,
+            theme: theme.clone(),
+            width: None,
+            after_subtitle: false,
+        }
+    }
+
+    /// Wrap text to `width` columns.
+    pub(crate) fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Indent each line of `text`, wrapping the long ones to the width, if there is one.
+    fn indented(&self, text: &str) -> String {
+        text.split('\n')
+            .map(|line| match self.width {
+                Some(width) => wrap(line, width.saturating_sub(INDENT_WIDTH))
+                    .join(&format!("\n{INDENT}{HANGING_INDENT}")),
+                None => line.to_owned(),
+            })
+            .map(|line| format!("{INDENT}{line}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Write a single line of text in the given color.
+    fn write_colored(&mut self, color: ColorSpec, text: &str) -> Result<(), Error> {
+        self.output.set_color(&color).map_err(Error::Color)?;
+        writeln!(self.output, "{text}")?;
+        self.output.reset().map_err(Error::Color)
+    }
+
+    /// Write spans next to each other, with the highlighted ones in color.
+    fn write_spans(&mut self, spans: &[Span]) -> Result<(), Error> {
+        for span in spans {
+            if span.highlighted {
+                self.output
+                    .set_color(&self.theme.highlight.color_spec())
+                    .map_err(Error::Color)?;
+                write!(self.output, "{}", span.text)?;
+                self.output.reset().map_err(Error::Color)?;
+            } else {
+                write!(self.output, "{}", span.text)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Subtitles are separated from whatever follows them by a blank line.
+    fn end_subtitles(&mut self) -> Result<(), Error> {
+        if self.after_subtitle {
+            self.after_subtitle = false;
+            writeln!(self.output)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Wrap a line of text to `width` columns, leaving room for the hanging indent of every line after
+/// the first.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let width = width.max(MIN_WIDTH);
+    let options = textwrap::Options::new(width).subsequent_indent(HANGING_INDENT);
+    textwrap::wrap(line, options)
+        .into_iter()
+        .map(|wrapped| {
+            wrapped
+                .strip_prefix(HANGING_INDENT)
+                .unwrap_or(&wrapped)
+                .to_owned()
+        })
+        .collect()
+}
+
+/// Break spans into rows of at most `width` columns, at the spaces between words, leaving room for
+/// the hanging indent of every row after the first. A word that's wider than a row gets a row of
+/// its own.
+fn wrap_spans(spans: &[Span], width: usize) -> Vec<Vec<Span>> {
+    let mut rows: Vec<Vec<Span>> = vec![Vec::new()];
+    let mut row_width = 0;
+    for span in spans {
+        for (index, word) in span.text.split(' ').enumerate() {
+            let space = usize::from(index > 0);
+            let row = rows.last_mut().expect("there's always a row");
+            if row_width > 0 && row_width + space + word.width() > width {
+                // Spans can end with the space before the next one.
+                if let Some(last) = row.last_mut() {
+                    last.text.truncate(last.text.trim_end().len());
+                }
+                rows.push(vec![Span {
+                    text: word.to_owned(),
+                    highlighted: span.highlighted,
+                }]);
+                row_width = HANGING_INDENT.len() + word.width();
+                continue;
+            }
+
+            let text = if space == 1 {
+                format!(" {word}")
+            } else {
+                word.to_owned()
+            };
+            row_width += text.width();
+            match row.last_mut() {
+                Some(last) if last.highlighted == span.highlighted => last.text.push_str(&text),
+                _ => row.push(Span {
+                    text,
+                    highlighted: span.highlighted,
+                }),
+            }
+        }
+    }
+    rows
+}
+
+/// Lay out a table as lines of text, each starting with `indent` and with the cells of each column
+/// lined up. The first line is the header.
+pub(super) fn table_lines(indent: &str, columns: &[Column], rows: &[Vec<String>]) -> Vec<String> {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|cell| cell.width())
+                .chain([column.heading.width()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let format_row = |cells: &mut dyn Iterator<Item = &str>| {
+        let mut line = String::from(indent);
+        for (index, (cell, column)) in cells.zip(columns).enumerate() {
+            let padding = " ".repeat(widths[index].saturating_sub(cell.width()));
+            let is_last = index + 1 == columns.len();
+            if index > 0 {
+                line.push_str("  ");
+            }
+            match column.alignment {
+                Alignment::Left if is_last => line.push_str(cell),
+                Alignment::Left => line.extend([cell, padding.as_str()]),
+                Alignment::Right => line.extend([padding.as_str(), cell]),
+            }
+        }
+        line
+    };
+
+    let header = format_row(&mut columns.iter().map(|column| column.heading));
+    let mut lines = vec![header];
+    lines.extend(
+        rows.iter()
+            .map(|row| format_row(&mut row.iter().map(String::as_str))),
+    );
+    lines
+}
+
+impl<W: WriteColor> Renderer for TextRenderer<W> {
+    fn end_document(&mut self) -> Result<(), Error> {
+        self.end_subtitles()?;
+        self.output.flush()?;
+        Ok(())
+    }
+
+    fn heading(&mut self, level: HeadingLevel, text: &str) -> Result<(), Error> {
+        self.end_subtitles()?;
+
+        match level {
+            HeadingLevel::Title => self.write_colored(self.theme.title.color_spec(), text),
+            HeadingLevel::Section(role) => {
+                self.write_colored(self.theme.section(role).color_spec(), &format!("{text}:"))
+            }
+            HeadingLevel::Subsection => {
+                self.write_colored(self.theme.subsection.color_spec(), &format!("{text}:"))
+            }
+        }
+    }
+
+    fn subtitle(&mut self, text: &str) -> Result<(), Error> {
+        self.after_subtitle = true;
+        writeln!(self.output, "{}", self.indented(text))?;
+        Ok(())
+    }
+
+    fn verse(&mut self, text: &str) -> Result<(), Error> {
+        self.end_subtitles()?;
+        writeln!(self.output, "{}", self.indented(text))?;
+        Ok(())
+    }
+
+    fn paragraph(&mut self, text: &str) -> Result<(), Error> {
+        self.end_subtitles()?;
+        let text = match self.width {
+            Some(width) => textwrap::fill(text, width.max(MIN_WIDTH)),
+            None => text.to_owned(),
+        };
+        writeln!(self.output, "{text}\n")?;
+        Ok(())
+    }
+
+    fn snippet(&mut self, spans: &[Span]) -> Result<(), Error> {
+        self.end_subtitles()?;
+        let rows = match self.width {
+            Some(width) => wrap_spans(spans, width.saturating_sub(INDENT_WIDTH).max(MIN_WIDTH)),
+            None => vec![spans.to_vec()],
+        };
+        for (index, row) in rows.iter().enumerate() {
+            let indent = if index == 0 { "" } else { HANGING_INDENT };
+            write!(self.output, "{INDENT}{indent}")?;
+            self.write_spans(row)?;
+            writeln!(self.output)?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn list(&mut self, items: &[String]) -> Result<(), Error> {
+        self.end_subtitles()?;
+        for item in items {
+            writeln!(self.output, "{}", self.indented(item))?;
+        }
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    fn links(&mut self, links: &[Link]) -> Result<(), Error> {
+        let items: Vec<String> = links.iter().map(|link| link.text.clone()).collect();
+        self.list(&items)
+    }
+
+    fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) -> Result<(), Error> {
+        self.end_subtitles()?;
+
+        let mut lines = table_lines(INDENT, columns, rows).into_iter();
+        let header = lines.next().expect("tables have a header");
+        let mut bold = ColorSpec::new();
+        bold.set_bold(true);
+        self.write_colored(bold, &header)?;
+        for line in lines {
+            writeln!(self.output, "{line}")?;
+        }
+        writeln!(self.output)?;
+
+        Ok(())
+    }
+
+    fn figure(&mut self, rows: &[Vec<Span>]) -> Result<(), Error> {
+        self.end_subtitles()?;
+
+        for row in rows {
+            self.write_spans(row)?;
+            writeln!(self.output)?;
+        }
+        writeln!(self.output)?;
+
+        Ok(())
+    }
+
+    fn begin_aside(&mut self, title: &str) -> Result<(), Error> {
+        self.end_subtitles()?;
+        writeln!(self.output)?;
+        self.write_colored(self.theme.changing_line.color_spec(), ASIDE_RULE)?;
+        writeln!(self.output, "{title}\n")?;
+        Ok(())
+    }
+
+    fn end_aside(&mut self) -> Result<(), Error> {
+        self.end_subtitles()?;
+        self.write_colored(self.theme.changing_line.color_spec(), ASIDE_RULE)?;
+        writeln!(self.output)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{theme::Style, SectionRole};
+    use termcolor::{Color, NoColor};
+
+    fn render_verse(width: usize, text: &str) -> String {
+        let mut output = NoColor::new(Vec::new());
+        TextRenderer::new(&mut output, &Theme::default())
+            .with_width(width)
+            .verse(text)
+            .unwrap();
+        String::from_utf8(output.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_long_lines_are_wrapped_with_a_hanging_indent() {
+        assert_eq!(
+            render_verse(40, "It furthers one to appoint helpers.\nNo blame."),
+            "\tIt furthers one to appoint\n\t  helpers.\n\tNo blame.\n"
+        );
+        // Chinese characters are two columns wide, so only 10 fit in 20 columns.
+        assert_eq!(
+            render_verse(28, "元亨利貞勿用有攸往利建侯"),
+            "\t元亨利貞勿用有攸往利\n\t  建侯\n"
+        );
+    }
+
+    #[test]
+    fn test_sections_are_styled_by_their_role() {
+        let theme = Theme::default();
+        let mut output = termcolor::Ansi::new(Vec::new());
+        let mut renderer = TextRenderer::new(&mut output, &theme);
+        for role in [
+            SectionRole::Lines,
+            SectionRole::Image,
+            SectionRole::Judgement,
+        ] {
+            renderer.heading(HeadingLevel::Section(role), "").unwrap();
+        }
+        let output = String::from_utf8(output.into_inner()).unwrap();
+
+        let color = |style: Style| match style.color {
+            Some(Color::Ansi256(number)) => format!("\x1b[38;5;{number}m"),
+            color => panic!("the dark theme has ANSI 256 colors, not {color:?}"),
+        };
+        let lines = output.find(&color(theme.lines)).unwrap();
+        let image = output.find(&color(theme.image)).unwrap();
+        let judgement = output.find(&color(theme.judgement)).unwrap();
+        assert!(lines < image && image < judgement, "{output:?}");
+    }
+
+    #[test]
+    fn test_snippets_are_wrapped_between_words() {
+        let spans = [
+            Span {
+                text: String::from("to see the "),
+                highlighted: false,
+            },
+            Span {
+                text: String::from("great man"),
+                highlighted: true,
+            },
+        ];
+        let rows: Vec<Vec<String>> = wrap_spans(&spans, 12)
+            .into_iter()
+            .map(|row| row.into_iter().map(|span| span.text).collect())
+            .collect();
+        assert_eq!(rows, [vec!["to see the"], vec!["great man"]]);
+    }
+}
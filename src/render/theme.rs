@@ -0,0 +1,267 @@
+//! Themes, which pick the colors of text output by the role that each piece of text plays.
+//!
+//! A style is written as a color, optionally after `bold` and `bright`, e.g. `bold red`,
+//! `bright yellow`, `196` (an ANSI 256 color), `#d70000` or `215,0,0`. `none` leaves the text in
+//! the terminal's own color.
+
+use super::SectionRole;
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_derive::Serialize;
+use std::{fmt, str::FromStr};
+use termcolor::{Color, ColorSpec};
+
+/// The names of the built-in themes.
+pub(crate) const THEME_NAMES: [&str; 5] =
+    ["dark", "light", "high-contrast", "monochrome", "16-color"];
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Unknown color {0:?}. Use a color name such as red, an ANSI 256 color number, #rrggbb or r,g,b")]
+    UnknownColor(String),
+    #[error("The style {0:?} has more than one color")]
+    TwoColors(String),
+}
+
+/// How a piece of text is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Style {
+    pub(crate) color: Option<Color>,
+    /// Whether the color is the bright version of a basic color, as 16-color terminals have.
+    pub(crate) bright: bool,
+    pub(crate) bold: bool,
+}
+
+impl Style {
+    const fn color(color: Color) -> Self {
+        Style {
+            color: Some(color),
+            bright: false,
+            bold: false,
+        }
+    }
+
+    const fn bright(mut self) -> Self {
+        self.bright = true;
+        self
+    }
+
+    const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    const fn plain() -> Self {
+        Style {
+            color: None,
+            bright: false,
+            bold: false,
+        }
+    }
+
+    /// Get this style as a `termcolor` color spec.
+    pub(crate) fn color_spec(&self) -> ColorSpec {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(self.color)
+            .set_intense(self.bright)
+            .set_bold(self.bold);
+        spec
+    }
+}
+
+impl FromStr for Style {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::plain();
+        for word in spec.split_whitespace() {
+            match word.to_ascii_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "bright" => style.bright = true,
+                "none" => {}
+                _ if style.color.is_some() => return Err(Error::TwoColors(spec.to_owned())),
+                hex if hex.starts_with('#') => {
+                    let channel = |range| {
+                        hex.get(range)
+                            .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                            .ok_or_else(|| Error::UnknownColor(word.to_owned()))
+                    };
+                    if hex.len() != 7 {
+                        return Err(Error::UnknownColor(word.to_owned()));
+                    }
+                    style.color = Some(Color::Rgb(channel(1..3)?, channel(3..5)?, channel(5..7)?));
+                }
+                _ => {
+                    style.color = Some(
+                        word.parse()
+                            .map_err(|_| Error::UnknownColor(word.to_owned()))?,
+                    );
+                }
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = Vec::new();
+        if self.bold {
+            words.push(String::from("bold"));
+        }
+        if self.bright {
+            words.push(String::from("bright"));
+        }
+        words.push(match self.color {
+            None => String::from("none"),
+            Some(Color::Ansi256(number)) => number.to_string(),
+            Some(Color::Rgb(red, green, blue)) => format!("#{red:02x}{green:02x}{blue:02x}"),
+            Some(color) => format!("{color:?}").to_ascii_lowercase(),
+        });
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+impl serde::Serialize for Style {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The style of each role that text plays in the output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Theme {
+    pub(crate) name: String,
+    pub(crate) title: Style,
+    /// The sections of a hexagram's judgement, image and lines. Other sections take the
+    /// judgement's style.
+    pub(crate) judgement: Style,
+    pub(crate) image: Style,
+    pub(crate) lines: Style,
+    pub(crate) subsection: Style,
+    /// The rules around the changing lines of a reading, and other asides.
+    pub(crate) changing_line: Style,
+    /// Highlighted text, such as the words found by a search.
+    pub(crate) highlight: Style,
+}
+
+impl Theme {
+    /// Get the built-in theme with the given name, if there is one.
+    pub(crate) fn built_in(name: &str) -> Option<Self> {
+        let theme =
+            |[title, judgement, image, lines, subsection, changing_line, highlight]: [Style; 7]| {
+                Theme {
+                    name: name.to_owned(),
+                    title,
+                    judgement,
+                    image,
+                    lines,
+                    subsection,
+                    changing_line,
+                    highlight,
+                }
+            };
+        let ansi = |number| Style::color(Color::Ansi256(number));
+
+        match name {
+            "dark" => Some(theme([
+                ansi(196),
+                ansi(160),
+                ansi(124),
+                ansi(88),
+                ansi(220),
+                ansi(130),
+                ansi(220),
+            ])),
+            "light" => Some(theme([
+                ansi(160),
+                ansi(124),
+                ansi(88),
+                ansi(52),
+                ansi(130),
+                ansi(94),
+                ansi(166),
+            ])),
+            "high-contrast" => Some(theme([
+                Style::color(Color::Red).bright().bold(),
+                Style::color(Color::Magenta).bright().bold(),
+                Style::color(Color::Blue).bright().bold(),
+                Style::color(Color::Cyan).bright().bold(),
+                Style::plain().bold(),
+                Style::color(Color::Yellow).bright().bold(),
+                Style::color(Color::Yellow).bright().bold(),
+            ])),
+            "monochrome" => Some(theme([
+                Style::plain().bold(),
+                Style::plain().bold(),
+                Style::plain().bold(),
+                Style::plain().bold(),
+                Style::plain(),
+                Style::plain(),
+                Style::plain().bold(),
+            ])),
+            "16-color" => Some(theme([
+                Style::color(Color::Red).bright(),
+                Style::color(Color::Red),
+                Style::color(Color::Magenta),
+                Style::color(Color::Blue),
+                Style::color(Color::Yellow),
+                Style::color(Color::Yellow),
+                Style::color(Color::Yellow).bright(),
+            ])),
+            _ => None,
+        }
+    }
+
+    /// Get the style of the heading of a section with the given role.
+    pub(crate) fn section(&self, role: SectionRole) -> Style {
+        match role {
+            SectionRole::Judgement | SectionRole::Other => self.judgement,
+            SectionRole::Image => self.image,
+            SectionRole::Lines => self.lines,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::built_in("dark").expect("the dark theme is built in")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles_are_parsed_and_written_back() {
+        for spec in ["bold bright red", "196", "#d70000", "none", "bold none"] {
+            let style: Style = spec.parse().unwrap();
+            assert_eq!(style.to_string(), spec);
+        }
+        assert_eq!(
+            "215,0,0".parse::<Style>().unwrap().color,
+            Some(Color::Rgb(215, 0, 0))
+        );
+        assert!(matches!(
+            "red blue".parse::<Style>(),
+            Err(Error::TwoColors(_))
+        ));
+        assert!(matches!(
+            "#d700".parse::<Style>(),
+            Err(Error::UnknownColor(_))
+        ));
+        assert!(THEME_NAMES
+            .iter()
+            .all(|name| Theme::built_in(name).is_some()));
+    }
+}
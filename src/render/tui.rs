@@ -0,0 +1,205 @@
+use super::{text::table_lines, theme, Column, Error, HeadingLevel, Link, Renderer, Span, Theme};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{self, Line, Text},
+};
+
+const ASIDE_RULE: &str = "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~";
+/// Tabs can't be drawn in a terminal UI, so text is indented with spaces instead.
+const INDENT: &str = "    ";
+
+/// Renders views as styled text for a terminal UI, such as the [browser](crate::browse), in the
+/// colors of a [`Theme`]. The text looks like that of the [`TextRenderer`](super::TextRenderer),
+/// and is left to the UI to wrap and scroll.
+pub(crate) struct TuiRenderer {
+    lines: Vec<Line<'static>>,
+    theme: Theme,
+    after_subtitle: bool,
+}
+
+impl TuiRenderer {
+    pub(crate) fn new(theme: &Theme) -> Self {
+        TuiRenderer {
+            lines: Vec::new(),
+            theme: theme.clone(),
+            after_subtitle: false,
+        }
+    }
+
+    /// Get the rendered text.
+    pub(crate) fn into_text(self) -> Text<'static> {
+        Text::from(self.lines)
+    }
+
+    fn push_styled(&mut self, style: Style, text: &str) {
+        self.lines.push(Line::styled(text.to_owned(), style));
+    }
+
+    /// Push each line of `text`, indented.
+    fn push_indented(&mut self, text: &str) {
+        for line in text.lines() {
+            self.lines.push(Line::raw(format!("{INDENT}{line}")));
+        }
+    }
+
+    fn push_spans(&mut self, indent: &str, spans: &[Span]) {
+        let spans = std::iter::once(text::Span::raw(indent.to_owned()))
+            .chain(spans.iter().map(|span| {
+                if span.highlighted {
+                    text::Span::styled(span.text.clone(), style(self.theme.highlight))
+                } else {
+                    text::Span::raw(span.text.clone())
+                }
+            }))
+            .collect::<Vec<_>>();
+        self.lines.push(Line::from(spans));
+    }
+
+    fn push_blank(&mut self) {
+        self.lines.push(Line::default());
+    }
+
+    /// Subtitles are separated from whatever follows them by a blank line.
+    fn end_subtitles(&mut self) {
+        if self.after_subtitle {
+            self.after_subtitle = false;
+            self.push_blank();
+        }
+    }
+}
+
+/// Get a theme's style as a terminal UI style.
+fn style(style: theme::Style) -> Style {
+    use termcolor::Color as Basic;
+
+    let color = style.color.map(|color| match (color, style.bright) {
+        (Basic::Black, false) => Color::Black,
+        (Basic::Black, true) => Color::DarkGray,
+        (Basic::Red, false) => Color::Red,
+        (Basic::Red, true) => Color::LightRed,
+        (Basic::Green, false) => Color::Green,
+        (Basic::Green, true) => Color::LightGreen,
+        (Basic::Yellow, false) => Color::Yellow,
+        (Basic::Yellow, true) => Color::LightYellow,
+        (Basic::Blue, false) => Color::Blue,
+        (Basic::Blue, true) => Color::LightBlue,
+        (Basic::Magenta, false) => Color::Magenta,
+        (Basic::Magenta, true) => Color::LightMagenta,
+        (Basic::Cyan, false) => Color::Cyan,
+        (Basic::Cyan, true) => Color::LightCyan,
+        (Basic::White, false) => Color::Gray,
+        (Basic::White, true) => Color::White,
+        (Basic::Ansi256(number), _) => Color::Indexed(number),
+        (Basic::Rgb(red, green, blue), _) => Color::Rgb(red, green, blue),
+        // Colors that termcolor adds in the future are left to the terminal.
+        _ => Color::Reset,
+    });
+    let mut tui_style = Style::new();
+    if let Some(color) = color {
+        tui_style = tui_style.fg(color);
+    }
+    if style.bold {
+        tui_style = tui_style.add_modifier(Modifier::BOLD);
+    }
+    tui_style
+}
+
+impl Renderer for TuiRenderer {
+    fn end_document(&mut self) -> Result<(), Error> {
+        self.end_subtitles();
+        Ok(())
+    }
+
+    fn heading(&mut self, level: HeadingLevel, text: &str) -> Result<(), Error> {
+        self.end_subtitles();
+
+        match level {
+            HeadingLevel::Title => {
+                self.push_styled(style(self.theme.title).add_modifier(Modifier::BOLD), text);
+            }
+            HeadingLevel::Section(role) => {
+                self.push_styled(style(self.theme.section(role)), &format!("{text}:"));
+            }
+            HeadingLevel::Subsection => {
+                self.push_styled(style(self.theme.subsection), &format!("{text}:"));
+            }
+        }
+        Ok(())
+    }
+
+    fn subtitle(&mut self, text: &str) -> Result<(), Error> {
+        self.after_subtitle = true;
+        self.push_indented(text);
+        Ok(())
+    }
+
+    fn verse(&mut self, text: &str) -> Result<(), Error> {
+        self.end_subtitles();
+        self.push_indented(text);
+        Ok(())
+    }
+
+    fn paragraph(&mut self, text: &str) -> Result<(), Error> {
+        self.end_subtitles();
+        self.lines.push(Line::raw(text.to_owned()));
+        self.push_blank();
+        Ok(())
+    }
+
+    fn snippet(&mut self, spans: &[Span]) -> Result<(), Error> {
+        self.end_subtitles();
+        self.push_spans(INDENT, spans);
+        self.push_blank();
+        Ok(())
+    }
+
+    fn list(&mut self, items: &[String]) -> Result<(), Error> {
+        self.end_subtitles();
+        for item in items {
+            self.push_indented(item);
+        }
+        self.push_blank();
+        Ok(())
+    }
+
+    fn links(&mut self, links: &[Link]) -> Result<(), Error> {
+        let items: Vec<String> = links.iter().map(|link| link.text.clone()).collect();
+        self.list(&items)
+    }
+
+    fn table(&mut self, columns: &[Column], rows: &[Vec<String>]) -> Result<(), Error> {
+        self.end_subtitles();
+
+        let mut lines = table_lines(INDENT, columns, rows).into_iter();
+        let header = lines.next().expect("tables have a header");
+        self.push_styled(Style::new().add_modifier(Modifier::BOLD), &header);
+        self.lines.extend(lines.map(Line::raw));
+        self.push_blank();
+        Ok(())
+    }
+
+    fn figure(&mut self, rows: &[Vec<Span>]) -> Result<(), Error> {
+        self.end_subtitles();
+        for row in rows {
+            self.push_spans("", row);
+        }
+        self.push_blank();
+        Ok(())
+    }
+
+    fn begin_aside(&mut self, title: &str) -> Result<(), Error> {
+        self.end_subtitles();
+        self.push_blank();
+        self.push_styled(style(self.theme.changing_line), ASIDE_RULE);
+        self.lines.push(Line::raw(title.to_owned()));
+        self.push_blank();
+        Ok(())
+    }
+
+    fn end_aside(&mut self) -> Result<(), Error> {
+        self.end_subtitles();
+        self.push_styled(style(self.theme.changing_line), ASIDE_RULE);
+        self.push_blank();
+        Ok(())
+    }
+}
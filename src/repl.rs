@@ -0,0 +1,534 @@
+//! An interactive shell for exploring how hexagrams change into each other, opened by `repl`.
+//!
+//! The shell keeps a current hexagram, which each command either shows or changes: casting a new
+//! one, setting its lines, flipping a line or moving to a related hexagram. Every hexagram of the
+//! session is kept, so that `history` can show the path that was taken. Output is written in the
+//! same `--format` as the one-shot commands, and readings follow the same settings.
+
+use crate::{
+    config::Settings,
+    hexagram_json::HexagramJsonInfo,
+    locale::Lang,
+    lookup::{self, parse_line_values},
+    raster,
+    render::{self, figure::small_hexagrams, Column, HeadingLevel, Render, Renderer, Theme},
+    view::{self, write_view, Layer, OutputFormat, ReadingView, Texts},
+};
+use clap::{Parser, Subcommand};
+use iching::{
+    divination_method::DivinationMethod,
+    hexagram::Hexagram,
+    hexagram_repository::HexagramRepository,
+    interpretation::{LineLabels, School},
+    svg::SvgStyle,
+};
+use rustyline::{error::ReadlineError, DefaultEditor};
+use serde_derive::Serialize;
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+};
+use termcolor::{NoColor, WriteColor};
+
+const PROMPT: &str = "iching> ";
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(
+        "There's no hexagram yet. Cast one with `cast`, or set its lines with e.g. `lines 789678`"
+    )]
+    NoHexagram,
+    #[error("No lines are changing, so there's no relating hexagram")]
+    NoRelatingHexagram,
+    #[error(transparent)]
+    Lines(#[from] lookup::Error),
+    #[error(transparent)]
+    View(#[from] view::Error),
+    #[error(transparent)]
+    Image(#[from] raster::Error),
+    #[error(
+        "Can't tell which format to save {} in. Use a file name ending in .txt, .md, .html, \
+         .json, .yaml, .svg or .png",
+        .0.display()
+    )]
+    UnknownFormat(PathBuf),
+    #[error("Failed to save to {}: {1}", .0.display())]
+    Save(PathBuf, #[source] io::Error),
+    #[error("Failed to write output: {0}")]
+    Output(#[from] io::Error),
+    #[error("Failed to read the next command: {0}")]
+    Readline(#[from] ReadlineError),
+}
+
+// A line typed into the shell, parsed as if it were the arguments of a command.
+#[derive(Parser)]
+#[command(
+    multicall = true,
+    about = "Each command shows the current hexagram, or moves to another one."
+)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Subcommand)]
+enum ReplCommand {
+    /// Cast a new hexagram
+    Cast {
+        /// The method of divination to use. Defaults to the method of the last cast, or to the
+        /// configured method
+        #[arg(short, long, value_name = "DIVINATION METHOD", value_enum)]
+        method: Option<DivinationMethod>,
+    },
+    /// Set the hexagram's lines, from the bottom up
+    Lines {
+        /// Each line as 0 (yin) or 1 (yang), or as its value from 6 to 9, e.g. 789678
+        lines: String,
+    },
+    /// Flip a line into its opposite. The other lines keep their changes
+    Flip {
+        /// The line's position, from 1 at the bottom to 6 at the top
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=6))]
+        position: u8,
+    },
+    /// Move to the relating hexagram, which the changing lines change into
+    Relate,
+    /// Move to the nuclear hexagram, made of the second to fifth lines
+    Nuclear,
+    /// Turn the hexagram upside down
+    Invert,
+    /// Replace every line with its opposite
+    Complement,
+    /// Show the hexagram's full text
+    Show,
+    /// List the hexagrams of this session, oldest first
+    History,
+    /// Save the hexagram's full text, or its image, to a file
+    ///
+    /// The format is picked by the file's extension: .txt, .md, .html, .json, .yaml, .svg or
+    /// .png.
+    Save {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Leave the shell
+    #[command(alias = "exit")]
+    Quit,
+}
+
+/// Whether the shell keeps running after a command.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+    Continue,
+    Quit,
+}
+
+/// A hexagram of the session, and the command that led to it.
+struct Step {
+    command: String,
+    hexagram: Hexagram,
+}
+
+/// The state of the shell.
+pub(crate) struct Session<'a, R> {
+    hexagrams: &'a R,
+    format: OutputFormat,
+    theme: Theme,
+    /// The number of columns to wrap text to, found when the shell starts.
+    width: Option<usize>,
+    /// The style that readings are drawn in, for `--format svg` and for saving images.
+    image: SvgStyle,
+    rng: fastrand::Rng,
+    /// The method of the last cast, which readings are shown and saved with.
+    method: DivinationMethod,
+    school: School,
+    line_labels: LineLabels,
+    lang: Lang,
+    texts: Texts,
+    with: Vec<Layer>,
+    history: Vec<Step>,
+}
+
+impl<'a, R: HexagramRepository<HexagramInfo = HexagramJsonInfo>> Session<'a, R> {
+    pub(crate) fn new(hexagrams: &'a R, settings: &Settings, rng: fastrand::Rng) -> Self {
+        Session {
+            hexagrams,
+            format: settings.format,
+            theme: settings.theme.clone(),
+            width: settings.text_width(),
+            image: settings.image.style(),
+            rng,
+            method: settings.method,
+            school: settings.school,
+            line_labels: settings.line_labels,
+            lang: settings.lang,
+            texts: settings.texts,
+            with: settings.with.clone(),
+            history: Vec::new(),
+        }
+    }
+
+    fn current(&self) -> Result<Hexagram, Error> {
+        self.history
+            .last()
+            .map(|step| step.hexagram)
+            .ok_or(Error::NoHexagram)
+    }
+
+    fn reading(&self) -> Result<ReadingView<'a>, Error> {
+        Ok(
+            ReadingView::new(None, self.method, &self.current()?, self.hexagrams)
+                .with_school(self.school)
+                .with_line_labels(self.line_labels)
+                .with_lang(self.lang)
+                .with_texts(self.texts)
+                .with_commentary(&self.with),
+        )
+    }
+
+    fn step_view(&self, step: &Step) -> StepView<'a> {
+        let label = |hexagram: &Hexagram| {
+            let info = self.hexagrams.get_info_for_hexagram(hexagram);
+            HexagramLabel {
+                number: info.number(),
+                symbol: info.symbol(),
+                english: info.english_name(),
+            }
+        };
+
+        StepView {
+            command: step.command.clone(),
+            lines: step.hexagram.line_values(),
+            changing_lines: step.hexagram.get_changing_line_positions(),
+            primary: label(&step.hexagram),
+            relating: step.hexagram.relating_hexagram().as_ref().map(label),
+            hexagram: step.hexagram,
+        }
+    }
+
+    /// Run a line typed into the shell, writing its output to `output`.
+    pub(crate) fn run_line(
+        &mut self,
+        line: &str,
+        output: &mut impl WriteColor,
+    ) -> Result<Action, Error> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Action::Continue);
+        }
+        let command = match ReplLine::try_parse_from(line.split_whitespace()) {
+            Ok(parsed) => parsed.command,
+            // Help and mistakes are both explained by clap.
+            Err(err) => {
+                write!(output, "{}", err.render())?;
+                return Ok(Action::Continue);
+            }
+        };
+
+        let hexagram = match command {
+            ReplCommand::Cast { method } => {
+                self.method = method.unwrap_or(self.method);
+                Hexagram::new_random_with_rng(self.method, &self.rng)
+            }
+            ReplCommand::Lines { lines } => Hexagram::try_from(parse_line_values(&lines)?)
+                .expect("parsed lines have valid values"),
+            ReplCommand::Flip { position } => {
+                let mut values = self.current()?.line_values();
+                let value = &mut values[usize::from(position) - 1];
+                *value = match value {
+                    6 | 8 => 7,
+                    _ => 8,
+                };
+                Hexagram::try_from(values).expect("flipped lines have valid values")
+            }
+            ReplCommand::Relate => self
+                .current()?
+                .relating_hexagram()
+                .ok_or(Error::NoRelatingHexagram)?,
+            ReplCommand::Nuclear => self.current()?.nuclear_hexagram(),
+            ReplCommand::Invert => self.current()?.inverse_hexagram(),
+            ReplCommand::Complement => self.current()?.complement_hexagram(),
+            ReplCommand::Show => {
+                write_view(
+                    output,
+                    self.format,
+                    &self.theme,
+                    self.width,
+                    &self.image,
+                    &self.reading()?,
+                )?;
+                return Ok(Action::Continue);
+            }
+            ReplCommand::History => {
+                let steps = self
+                    .history
+                    .iter()
+                    .map(|step| self.step_view(step))
+                    .collect();
+                write_view(
+                    output,
+                    self.format,
+                    &self.theme,
+                    self.width,
+                    &self.image,
+                    &HistoryView {
+                        steps,
+                        lang: self.lang,
+                    },
+                )?;
+                return Ok(Action::Continue);
+            }
+            ReplCommand::Save { path } => {
+                self.save(&path)?;
+                writeln!(
+                    output,
+                    "{}",
+                    self.lang
+                        .messages()
+                        .format("repl-saved", &[("path", path.display().to_string().into())])
+                )?;
+                return Ok(Action::Continue);
+            }
+            ReplCommand::Quit => return Ok(Action::Quit),
+        };
+
+        let step = Step {
+            command: line.to_owned(),
+            hexagram,
+        };
+        write_view(
+            output,
+            self.format,
+            &self.theme,
+            self.width,
+            &self.image,
+            &self.step_view(&step),
+        )?;
+        self.history.push(step);
+
+        Ok(Action::Continue)
+    }
+
+    /// Save the current hexagram's reading to a file, in the format picked by its extension.
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let reading = self.reading()?;
+        if raster::ImageFormat::from_path(path)
+            .is_ok_and(|format| format == raster::ImageFormat::Png)
+        {
+            return Ok(raster::save_image(path, &reading, &self.image)?);
+        }
+
+        let format =
+            OutputFormat::from_path(path).ok_or_else(|| Error::UnknownFormat(path.to_owned()))?;
+        let file = File::create(path).map_err(|err| Error::Save(path.to_owned(), err))?;
+        let mut output = NoColor::new(BufWriter::new(file));
+        // Files aren't wrapped to the terminal.
+        write_view(
+            &mut output,
+            format,
+            &self.theme,
+            None,
+            &self.image,
+            &reading,
+        )?;
+        output
+            .into_inner()
+            .into_inner()
+            .map_err(|err| Error::Save(path.to_owned(), err.into_error()))?;
+
+        Ok(())
+    }
+}
+
+/// Run the shell until it's quit, or until its input ends.
+pub(crate) fn repl(
+    output: &mut impl WriteColor,
+    settings: &Settings,
+    seed: Option<u64>,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Error> {
+    let rng = seed.map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed);
+    let mut session = Session::new(hexagrams, settings, rng);
+    let mut editor = DefaultEditor::new()?;
+    writeln!(output, "{}\n", settings.lang.messages().get("repl-welcome"))?;
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        // Only commands that were typed in are remembered, so a failure to do so doesn't matter.
+        let _ = editor.add_history_entry(&line);
+
+        match session.run_line(&line, output) {
+            Ok(Action::Continue) => {}
+            Ok(Action::Quit) => return Ok(()),
+            Err(err) => writeln!(output, "error: {err}")?,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HexagramLabel<'a> {
+    number: usize,
+    symbol: &'a str,
+    english: &'a str,
+}
+
+impl HexagramLabel<'_> {
+    fn label(&self) -> String {
+        format!("{} {} {}", self.symbol, self.number, self.english)
+    }
+}
+
+/// A hexagram of the session, drawn small after each command that changes it.
+#[derive(Serialize)]
+struct StepView<'a> {
+    command: String,
+    lines: [u8; 6],
+    changing_lines: Vec<usize>,
+    primary: HexagramLabel<'a>,
+    relating: Option<HexagramLabel<'a>>,
+    #[serde(skip)]
+    hexagram: Hexagram,
+}
+
+impl Render for StepView<'_> {
+    fn title(&self) -> String {
+        match &self.relating {
+            Some(relating) => format!("{} ──▶ {}", self.primary.label(), relating.label()),
+            None => self.primary.label(),
+        }
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        renderer.figure(&small_hexagrams(&self.hexagram))
+    }
+}
+
+/// Every hexagram of the session, oldest first.
+#[derive(Serialize)]
+struct HistoryView<'a> {
+    steps: Vec<StepView<'a>>,
+    #[serde(skip)]
+    lang: Lang,
+}
+
+impl Render for HistoryView<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().get("repl-history")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        if self.steps.is_empty() {
+            return renderer.paragraph(&messages.get("repl-nothing-cast"));
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                vec![
+                    (index + 1).to_string(),
+                    step.command.clone(),
+                    step.primary.label(),
+                    step.relating
+                        .as_ref()
+                        .map_or_else(String::new, HexagramLabel::label),
+                ]
+            })
+            .collect();
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-number")),
+                Column::left(&messages.get("column-command")),
+                Column::left(&messages.get("column-hexagram")),
+                Column::left(&messages.get("column-changes-into")),
+            ],
+            &rows,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+
+    fn run(session: &mut Session<HexagramJson>, line: &str) -> Result<String, Error> {
+        let mut output = NoColor::new(Vec::new());
+        session.run_line(line, &mut output)?;
+        Ok(String::from_utf8(output.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn test_commands_move_between_hexagrams() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let mut session = Session::new(
+            &hexagrams,
+            &Settings::default(),
+            fastrand::Rng::with_seed(1),
+        );
+
+        assert!(matches!(
+            run(&mut session, "flip 1"),
+            Err(Error::NoHexagram)
+        ));
+
+        // ䷂ Difficulty at the Beginning, changing into ䷇ Holding Together.
+        let output = run(&mut session, "lines 988878").unwrap();
+        assert!(output.starts_with("䷂ 3 Difficulty at the Beginning ──▶ ䷇ 8 Holding Together"));
+        // Flipping the top line keeps the change of the bottom line.
+        run(&mut session, "flip 6").unwrap();
+        assert_eq!(session.current().unwrap().line_values(), [9, 8, 8, 8, 7, 7]);
+        run(&mut session, "relate").unwrap();
+        assert!(matches!(
+            run(&mut session, "relate"),
+            Err(Error::NoRelatingHexagram)
+        ));
+        run(&mut session, "invert").unwrap();
+
+        let history = run(&mut session, "history").unwrap();
+        assert!(history.contains("lines 988878"));
+        assert!(history.contains("invert"));
+        assert_eq!(session.history.len(), 4);
+
+        assert!(run(&mut session, "flip 7").unwrap().contains("error"));
+        assert_eq!(
+            session
+                .run_line("quit", &mut NoColor::new(Vec::new()))
+                .unwrap(),
+            Action::Quit
+        );
+    }
+
+    #[test]
+    fn test_save_picks_the_format_by_extension() {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        let mut session = Session::new(&hexagrams, &Settings::default(), fastrand::Rng::new());
+        run(&mut session, "lines 777777").unwrap();
+        let path = std::env::temp_dir().join(format!("iching-repl-{}.md", std::process::id()));
+
+        run(&mut session, &format!("save {}", path.display())).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(saved.starts_with("# Hexagram No. 1  ䷀"), "{saved}");
+        assert!(matches!(
+            session.save(Path::new("reading.pdf")),
+            Err(Error::UnknownFormat(_))
+        ));
+    }
+}
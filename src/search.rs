@@ -0,0 +1,425 @@
+//! Full-text search over passages of text, such as the judgements and line texts of the
+//! hexagrams.
+//!
+//! Text is split into terms that are compared without regard to case or to the tone marks of
+//! pinyin, so `qian` finds `Qián`. Words are split at spaces and punctuation, while each Chinese
+//! character is a term of its own, since Chinese isn't written with spaces. Chinese characters
+//! are compared in their simplified forms, so `潜龙` finds `潛龍`. A query term also finds longer
+//! terms that start with it, so `dragon` finds `dragons`.
+
+mod simplified;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Bound,
+};
+
+/// Common English words that are left out of queries, so that a query can be written as a
+/// phrase like "the dragon in the field".
+const STOP_WORDS: [&str; 16] = [
+    "a", "an", "and", "are", "at", "by", "for", "in", "is", "it", "of", "on", "or", "the", "to",
+    "with",
+];
+/// How many characters of context a snippet shows around the first match, on each side.
+const SNIPPET_CONTEXT: usize = 60;
+/// Matches of the start of a longer term count for this much of a whole match.
+const PREFIX_MATCH_WEIGHT: f64 = 0.5;
+
+/// A term of a passage, and where it is in the passage's text.
+#[derive(Debug)]
+struct Token {
+    term: String,
+    /// The byte range of the term in the passage's text.
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug)]
+struct Passage<K> {
+    key: K,
+    text: String,
+    weight: f64,
+    tokens: Vec<Token>,
+}
+
+/// A full-text search index over passages of text. Each passage is identified by a key of type
+/// `K`, which is given back with each [`SearchHit`].
+#[derive(Debug)]
+pub struct SearchIndex<K> {
+    passages: Vec<Passage<K>>,
+    /// The passages that contain each term, by their index in `passages`.
+    postings: BTreeMap<String, BTreeSet<usize>>,
+}
+
+/// A passage that matches a query.
+#[derive(Debug)]
+pub struct SearchHit<'a, K> {
+    pub key: &'a K,
+    /// How well the passage matches the query. Hits are ordered by score, best first.
+    pub score: f64,
+    /// A short piece of the passage around its first match, with every match highlighted.
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// A piece of a [`SearchHit::snippet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnippetPart {
+    pub text: String,
+    /// Whether this piece matches a term of the query.
+    pub highlighted: bool,
+}
+
+impl<K> Default for SearchIndex<K> {
+    fn default() -> Self {
+        SearchIndex {
+            passages: Vec::new(),
+            postings: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K> SearchIndex<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a passage to the index. Matches in passages with a higher `weight` score higher, e.g.
+    /// to rank matches in the name of a hexagram above matches in its line texts.
+    pub fn add(&mut self, key: K, text: &str, weight: f64) {
+        let index = self.passages.len();
+        let tokens = tokenize(text);
+        for token in &tokens {
+            self.postings
+                .entry(token.term.clone())
+                .or_default()
+                .insert(index);
+        }
+        self.passages.push(Passage {
+            key,
+            text: text.to_owned(),
+            weight,
+            tokens,
+        });
+    }
+
+    /// Find the passages that match every term of the query, best match first.
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'_, K>> {
+        let mut query_terms: Vec<String> = tokenize(query)
+            .into_iter()
+            .map(|token| token.term)
+            .filter(|term| !STOP_WORDS.contains(&term.as_str()))
+            .collect();
+        query_terms.sort_unstable();
+        query_terms.dedup();
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let passage_count = self.passages.len() as f64;
+        let mut scores: BTreeMap<usize, f64> = BTreeMap::new();
+        for (term_index, query_term) in query_terms.iter().enumerate() {
+            let matches = self.matching_terms(query_term);
+            let matching_passages: BTreeSet<usize> = matches
+                .iter()
+                .flat_map(|(_, passages)| passages.iter().copied())
+                .collect();
+            // Rare terms say more about a passage than common ones.
+            let rarity = (1.0 + passage_count / matching_passages.len().max(1) as f64).ln();
+
+            let mut term_scores: BTreeMap<usize, f64> = BTreeMap::new();
+            for (term, passages) in matches {
+                let match_weight = if term == query_term {
+                    1.0
+                } else {
+                    PREFIX_MATCH_WEIGHT
+                };
+                for &index in passages {
+                    let passage = &self.passages[index];
+                    let count = passage
+                        .tokens
+                        .iter()
+                        .filter(|token| &token.term == term)
+                        .count() as f64;
+                    *term_scores.entry(index).or_default() +=
+                        passage.weight * match_weight * (1.0 + count.ln()) * rarity;
+                }
+            }
+
+            // Only passages that match every term so far are kept.
+            if term_index == 0 {
+                scores = term_scores;
+            } else {
+                scores = scores
+                    .into_iter()
+                    .filter_map(|(index, score)| {
+                        term_scores
+                            .get(&index)
+                            .map(|term_score| (index, score + term_score))
+                    })
+                    .collect();
+            }
+        }
+
+        let mut hits: Vec<SearchHit<K>> = scores
+            .into_iter()
+            .map(|(index, score)| {
+                let passage = &self.passages[index];
+                SearchHit {
+                    key: &passage.key,
+                    score,
+                    snippet: snippet(passage, &query_terms),
+                }
+            })
+            .collect();
+        // Ties keep the order that passages were added in, since the sort is stable.
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    }
+
+    /// Get the terms of the index that are the same as, or start with, a term of a query.
+    fn matching_terms(&self, query_term: &str) -> Vec<(&String, &BTreeSet<usize>)> {
+        self.postings
+            .range::<str, _>((Bound::Included(query_term), Bound::Unbounded))
+            .take_while(|(term, _)| term.starts_with(query_term))
+            .collect()
+    }
+}
+
+/// Whether a character is written on its own, without spaces around it.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{20000}'..='\u{2ffff}'
+    )
+}
+
+/// Remove the tone marks from a lowercase pinyin vowel. `ü` becomes `u`, as it's typed on most
+/// keyboards.
+fn remove_tone_mark(c: char) -> char {
+    match c {
+        'ā' | 'á' | 'ǎ' | 'à' => 'a',
+        'ē' | 'é' | 'ě' | 'è' => 'e',
+        'ī' | 'í' | 'ǐ' | 'ì' => 'i',
+        'ō' | 'ó' | 'ǒ' | 'ò' => 'o',
+        'ū' | 'ú' | 'ǔ' | 'ù' | 'ǖ' | 'ǘ' | 'ǚ' | 'ǜ' | 'ü' => 'u',
+        c => c,
+    }
+}
+
+/// Turn text into the form that it's indexed and searched by: lowercase, without the tone marks
+/// of pinyin and with Chinese characters in their simplified forms.
+pub fn normalize(word: &str) -> String {
+    word.chars()
+        .flat_map(char::to_lowercase)
+        .map(remove_tone_mark)
+        .map(simplified::simplify)
+        .collect()
+}
+
+/// The number of single character insertions, deletions and substitutions that turn `a` into
+/// `b`, for forgiving typos in names.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Split text into terms.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    let end_word = |tokens: &mut Vec<Token>, start: Option<usize>, end: usize| {
+        if let Some(start) = start {
+            tokens.push(Token {
+                term: normalize(&text[start..end]),
+                start,
+                end,
+            });
+        }
+    };
+
+    for (index, c) in text.char_indices() {
+        if is_cjk(c) {
+            end_word(&mut tokens, word_start.take(), index);
+            end_word(&mut tokens, Some(index), index + c.len_utf8());
+        } else if c.is_alphanumeric() {
+            word_start.get_or_insert(index);
+        } else {
+            end_word(&mut tokens, word_start.take(), index);
+        }
+    }
+    end_word(&mut tokens, word_start, text.len());
+
+    tokens
+}
+
+/// Cut a snippet out of a passage around its first match, highlighting every match. Line breaks
+/// are shown as ` / `, as is usual when quoting verse.
+fn snippet<K>(passage: &Passage<K>, query_terms: &[String]) -> Vec<SnippetPart> {
+    let matched: Vec<&Token> = passage
+        .tokens
+        .iter()
+        .filter(|token| query_terms.iter().any(|term| token.term.starts_with(term)))
+        .collect();
+    let Some(first) = matched.first() else {
+        return Vec::new();
+    };
+
+    let text = &passage.text;
+    let start = text[..first.start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+        .map_or(0, |(index, _)| index);
+    let end = text[first.end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT)
+        .map_or(text.len(), |(index, _)| first.end + index);
+    // Don't cut words in half.
+    let start = if start == 0 {
+        0
+    } else {
+        text[start..first.start]
+            .find(char::is_whitespace)
+            .map_or(start, |space| start + space + 1)
+    };
+    let end = if end == text.len() {
+        end
+    } else {
+        text[first.end..end]
+            .rfind(char::is_whitespace)
+            .map_or(end, |space| first.end + space)
+    };
+
+    let mut parts = Vec::new();
+    let mut push = |text: &str, highlighted: bool| {
+        if !text.is_empty() {
+            parts.push(SnippetPart {
+                text: text.replace('\n', " / "),
+                highlighted,
+            });
+        }
+    };
+    if start > 0 {
+        push("…", false);
+    }
+    let mut position = start;
+    for token in matched
+        .iter()
+        .filter(|token| token.start >= start && token.end <= end)
+    {
+        push(&text[position..token.start], false);
+        push(&text[token.start..token.end], true);
+        position = token.end;
+    }
+    push(&text[position..end], false);
+    if end < text.len() {
+        push("…", false);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex<&'static str> {
+        let mut index = SearchIndex::new();
+        index.add("creative", "乾 Qián\nThe Creative", 3.0);
+        index.add("line 1", "Hidden dragon. Do not act.", 1.0);
+        index.add(
+            "line 2",
+            "Dragon appearing in the field.\nIt furthers one to see the great man.",
+            1.0,
+        );
+        index.add("line 6", "Arrogant dragon will have cause to repent.", 1.0);
+        index
+    }
+
+    fn keys(hits: &[SearchHit<&'static str>]) -> Vec<&'static str> {
+        hits.iter().map(|hit| *hit.key).collect()
+    }
+
+    #[test]
+    fn test_every_term_must_match() {
+        let index = index();
+
+        assert_eq!(keys(&index.search("dragon in the field")), ["line 2"]);
+        assert_eq!(index.search("dragon").len(), 3);
+        assert!(index.search("tiger").is_empty());
+        assert!(index.search("the").is_empty());
+    }
+
+    #[test]
+    fn test_repeated_terms_count_once() {
+        let index = index();
+        let once = index.search("dragon field");
+        let twice = index.search("dragon field dragon");
+
+        assert_eq!(keys(&twice), ["line 2"]);
+        assert_eq!(twice[0].score, once[0].score);
+    }
+
+    #[test]
+    fn test_pinyin_chinese_and_prefixes_match() {
+        let index = index();
+
+        assert_eq!(keys(&index.search("qian")), ["creative"]);
+        assert_eq!(keys(&index.search("QIÁN")), ["creative"]);
+        assert_eq!(keys(&index.search("乾")), ["creative"]);
+        assert_eq!(keys(&index.search("appear")), ["line 2"]);
+    }
+
+    #[test]
+    fn test_simplified_and_traditional_characters_match() {
+        let mut index = SearchIndex::new();
+        index.add("line 1", "潛龍勿用。", 1.0);
+        index.add("creative", "乾：元亨利貞。", 1.0);
+
+        assert_eq!(keys(&index.search("潜龙")), ["line 1"]);
+        assert_eq!(keys(&index.search("潛龍")), ["line 1"]);
+        assert_eq!(keys(&index.search("元亨利贞")), ["creative"]);
+        // 乾 is the name of a hexagram, not the simplified 干 of "dry".
+        assert!(index.search("干").is_empty());
+        assert_eq!(
+            index.search("龙")[0].snippet[1],
+            SnippetPart {
+                text: String::from("龍"),
+                highlighted: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_snippets_highlight_matches() {
+        let index = index();
+        let hits = index.search("field great");
+
+        assert_eq!(
+            hits[0].snippet,
+            [
+                ("Dragon appearing in the ", false),
+                ("field", true),
+                (". / It furthers one to see the ", false),
+                ("great", true),
+                (" man.", false),
+            ]
+            .map(|(text, highlighted)| SnippetPart {
+                text: text.to_owned(),
+                highlighted
+            })
+        );
+    }
+}
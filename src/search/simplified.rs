@@ -0,0 +1,354 @@
+//! The simplified forms of the traditional characters that the texts of the I Ching are written
+//! in, so that a search in either script finds the same passages.
+//!
+//! The table was made with ICU's `Traditional-Simplified` transform, e.g. by running every
+//! character of `src/hexagrams.json`, `src/classical.json` and `src/commentary.json` through
+//! `uconv -x Traditional-Simplified`. 乾 is left out: it's the name of the first hexagram and
+//! trigram in both scripts, and only becomes 干 when it means "dry".
+
+/// Pairs of a traditional character and its simplified form, ordered by the traditional
+/// character.
+const TRADITIONAL_TO_SIMPLIFIED: [(char, char); 335] = [
+    ('並', '并'),
+    ('亂', '乱'),
+    ('來', '来'),
+    ('係', '系'),
+    ('備', '备'),
+    ('傷', '伤'),
+    ('傾', '倾'),
+    ('僕', '仆'),
+    ('儀', '仪'),
+    ('億', '亿'),
+    ('儉', '俭'),
+    ('兌', '兑'),
+    ('內', '内'),
+    ('兩', '两'),
+    ('則', '则'),
+    ('剛', '刚'),
+    ('剝', '剥'),
+    ('動', '动'),
+    ('勝', '胜'),
+    ('勞', '劳'),
+    ('勢', '势'),
+    ('勸', '劝'),
+    ('厲', '厉'),
+    ('叢', '丛'),
+    ('咷', '啕'),
+    ('問', '问'),
+    ('啞', '哑'),
+    ('喪', '丧'),
+    ('嚮', '向'),
+    ('嚴', '严'),
+    ('國', '国'),
+    ('園', '园'),
+    ('執', '执'),
+    ('堅', '坚'),
+    ('塗', '涂'),
+    ('壯', '壮'),
+    ('奮', '奋'),
+    ('婦', '妇'),
+    ('學', '学'),
+    ('宮', '宫'),
+    ('實', '实'),
+    ('寧', '宁'),
+    ('寬', '宽'),
+    ('寵', '宠'),
+    ('對', '对'),
+    ('屨', '屦'),
+    ('帥', '帅'),
+    ('師', '师'),
+    ('帶', '带'),
+    ('幹', '干'),
+    ('幾', '几'),
+    ('廟', '庙'),
+    ('廬', '庐'),
+    ('弒', '弑'),
+    ('張', '张'),
+    ('強', '强'),
+    ('彙', '汇'),
+    ('後', '后'),
+    ('從', '从'),
+    ('復', '复'),
+    ('恆', '恒'),
+    ('悶', '闷'),
+    ('惡', '恶'),
+    ('惻', '恻'),
+    ('愛', '爱'),
+    ('慍', '愠'),
+    ('慶', '庆'),
+    ('憂', '忧'),
+    ('憊', '惫'),
+    ('應', '应'),
+    ('懲', '惩'),
+    ('懷', '怀'),
+    ('懼', '惧'),
+    ('戔', '戋'),
+    ('戰', '战'),
+    ('戶', '户'),
+    ('揚', '扬'),
+    ('揮', '挥'),
+    ('損', '损'),
+    ('撝', '㧑'),
+    ('擊', '击'),
+    ('據', '据'),
+    ('攣', '挛'),
+    ('敗', '败'),
+    ('敵', '敌'),
+    ('數', '数'),
+    ('於', '于'),
+    ('時', '时'),
+    ('晉', '晋'),
+    ('晝', '昼'),
+    ('暉', '晖'),
+    ('暢', '畅'),
+    ('會', '会'),
+    ('東', '东'),
+    ('棄', '弃'),
+    ('棟', '栋'),
+    ('楊', '杨'),
+    ('業', '业'),
+    ('極', '极'),
+    ('榮', '荣'),
+    ('樂', '乐'),
+    ('橈', '桡'),
+    ('歲', '岁'),
+    ('歷', '历'),
+    ('歸', '归'),
+    ('殺', '杀'),
+    ('氣', '气'),
+    ('決', '决'),
+    ('況', '况'),
+    ('淵', '渊'),
+    ('渙', '涣'),
+    ('湯', '汤'),
+    ('滅', '灭'),
+    ('滿', '满'),
+    ('漣', '涟'),
+    ('漸', '渐'),
+    ('潛', '潜'),
+    ('澤', '泽'),
+    ('濕', '湿'),
+    ('濟', '济'),
+    ('瀆', '渎'),
+    ('災', '灾'),
+    ('為', '为'),
+    ('無', '无'),
+    ('爛', '烂'),
+    ('爾', '尔'),
+    ('牽', '牵'),
+    ('猶', '犹'),
+    ('獄', '狱'),
+    ('獨', '独'),
+    ('獲', '获'),
+    ('瑣', '琐'),
+    ('甕', '瓮'),
+    ('異', '异'),
+    ('當', '当'),
+    ('疇', '畴'),
+    ('發', '发'),
+    ('盡', '尽'),
+    ('眾', '众'),
+    ('碩', '硕'),
+    ('確', '确'),
+    ('祿', '禄'),
+    ('禦', '御'),
+    ('禮', '礼'),
+    ('稱', '称'),
+    ('穀', '谷'),
+    ('積', '积'),
+    ('穫', '获'),
+    ('窮', '穷'),
+    ('竄', '窜'),
+    ('節', '节'),
+    ('篤', '笃'),
+    ('約', '约'),
+    ('納', '纳'),
+    ('純', '纯'),
+    ('紛', '纷'),
+    ('紱', '绂'),
+    ('終', '终'),
+    ('絕', '绝'),
+    ('統', '统'),
+    ('經', '经'),
+    ('維', '维'),
+    ('綸', '纶'),
+    ('緩', '缓'),
+    ('繫', '系'),
+    ('繼', '继'),
+    ('續', '续'),
+    ('罰', '罚'),
+    ('罷', '罢'),
+    ('義', '义'),
+    ('習', '习'),
+    ('聖', '圣'),
+    ('聞', '闻'),
+    ('聰', '聪'),
+    ('聲', '声'),
+    ('聽', '听'),
+    ('膚', '肤'),
+    ('臨', '临'),
+    ('與', '与'),
+    ('興', '兴'),
+    ('舊', '旧'),
+    ('艱', '艰'),
+    ('茲', '兹'),
+    ('莧', '苋'),
+    ('華', '华'),
+    ('萬', '万'),
+    ('著', '着'),
+    ('蒞', '莅'),
+    ('蓋', '盖'),
+    ('薦', '荐'),
+    ('藉', '借'),
+    ('藥', '药'),
+    ('蘇', '苏'),
+    ('處', '处'),
+    ('虛', '虚'),
+    ('號', '号'),
+    ('虧', '亏'),
+    ('蠱', '蛊'),
+    ('衛', '卫'),
+    ('見', '见'),
+    ('視', '视'),
+    ('親', '亲'),
+    ('覿', '觌'),
+    ('觀', '观'),
+    ('觸', '触'),
+    ('訟', '讼'),
+    ('設', '设'),
+    ('試', '试'),
+    ('詳', '详'),
+    ('誅', '诛'),
+    ('語', '语'),
+    ('誠', '诚'),
+    ('誡', '诫'),
+    ('誥', '诰'),
+    ('說', '说'),
+    ('誰', '谁'),
+    ('諸', '诸'),
+    ('謀', '谋'),
+    ('謂', '谓'),
+    ('謙', '谦'),
+    ('講', '讲'),
+    ('謹', '谨'),
+    ('識', '识'),
+    ('議', '议'),
+    ('譽', '誉'),
+    ('變', '变'),
+    ('豐', '丰'),
+    ('豶', '豮'),
+    ('貝', '贝'),
+    ('貞', '贞'),
+    ('負', '负'),
+    ('財', '财'),
+    ('貫', '贯'),
+    ('貳', '贰'),
+    ('貴', '贵'),
+    ('賁', '贲'),
+    ('資', '资'),
+    ('賓', '宾'),
+    ('賞', '赏'),
+    ('賢', '贤'),
+    ('賤', '贱'),
+    ('踰', '逾'),
+    ('躋', '跻'),
+    ('躍', '跃'),
+    ('車', '车'),
+    ('載', '载'),
+    ('輔', '辅'),
+    ('輕', '轻'),
+    ('輝', '辉'),
+    ('輪', '轮'),
+    ('輻', '辐'),
+    ('輿', '舆'),
+    ('辭', '辞'),
+    ('辯', '辩'),
+    ('連', '连'),
+    ('進', '进'),
+    ('過', '过'),
+    ('違', '违'),
+    ('遠', '远'),
+    ('遯', '遁'),
+    ('遲', '迟'),
+    ('遷', '迁'),
+    ('遺', '遗'),
+    ('邇', '迩'),
+    ('鄰', '邻'),
+    ('醜', '丑'),
+    ('鉉', '铉'),
+    ('錫', '锡'),
+    ('錯', '错'),
+    ('長', '长'),
+    ('門', '门'),
+    ('閉', '闭'),
+    ('開', '开'),
+    ('閑', '闲'),
+    ('間', '间'),
+    ('闃', '阒'),
+    ('關', '关'),
+    ('陰', '阴'),
+    ('陸', '陆'),
+    ('陽', '阳'),
+    ('階', '阶'),
+    ('隕', '陨'),
+    ('際', '际'),
+    ('隨', '随'),
+    ('險', '险'),
+    ('隱', '隐'),
+    ('雖', '虽'),
+    ('雜', '杂'),
+    ('離', '离'),
+    ('難', '难'),
+    ('雲', '云'),
+    ('電', '电'),
+    ('靈', '灵'),
+    ('靜', '静'),
+    ('鞏', '巩'),
+    ('頂', '顶'),
+    ('順', '顺'),
+    ('須', '须'),
+    ('預', '预'),
+    ('頤', '颐'),
+    ('頰', '颊'),
+    ('頻', '频'),
+    ('顒', '颙'),
+    ('願', '愿'),
+    ('顛', '颠'),
+    ('類', '类'),
+    ('顯', '显'),
+    ('風', '风'),
+    ('飛', '飞'),
+    ('飪', '饪'),
+    ('飭', '饬'),
+    ('飲', '饮'),
+    ('飽', '饱'),
+    ('飾', '饰'),
+    ('養', '养'),
+    ('餘', '余'),
+    ('饋', '馈'),
+    ('馬', '马'),
+    ('馮', '冯'),
+    ('馴', '驯'),
+    ('驅', '驱'),
+    ('驕', '骄'),
+    ('驚', '惊'),
+    ('體', '体'),
+    ('魚', '鱼'),
+    ('鮒', '鲋'),
+    ('鳥', '鸟'),
+    ('鳴', '鸣'),
+    ('鴻', '鸿'),
+    ('鶴', '鹤'),
+    ('麗', '丽'),
+    ('黃', '黄'),
+    ('齎', '赍'),
+    ('龍', '龙'),
+    ('龜', '龟'),
+];
+
+/// Get the simplified form of a character, or the character itself if it has no other form.
+pub(super) fn simplify(c: char) -> char {
+    TRADITIONAL_TO_SIMPLIFIED
+        .binary_search_by_key(&c, |&(traditional, _)| traditional)
+        .map_or(c, |index| TRADITIONAL_TO_SIMPLIFIED[index].1)
+}
@@ -0,0 +1,299 @@
+use crate::{
+    hexagram_json::HexagramJsonInfo,
+    locale::{Lang, Messages},
+    render::{self, HeadingLevel, Render, Renderer, Span},
+};
+use iching::{
+    hexagram_repository::HexagramRepository,
+    search::{SearchIndex, SnippetPart},
+};
+use serde_derive::Serialize;
+
+/// Matches in names count the most, and matches in the line texts the least, since a query
+/// that matches a hexagram's name is most likely about that hexagram.
+const NAME_WEIGHT: f64 = 3.0;
+const JUDGEMENT_WEIGHT: f64 = 1.5;
+const IMAGES_WEIGHT: f64 = 1.2;
+const LINE_WEIGHT: f64 = 1.0;
+
+/// The part of a hexagram's text that a search result comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind", content = "position")]
+enum Passage {
+    Name,
+    Judgement,
+    Images,
+    /// The text of a line, by its position from the bottom line up.
+    Line(usize),
+    /// The original Chinese of the judgement.
+    ClassicalJudgement,
+    /// The original Chinese of the images.
+    ClassicalImages,
+    /// The original Chinese of a line, by its position from the bottom line up.
+    ClassicalLine(usize),
+}
+
+/// Build a search index over the names, judgements, images and line texts of every hexagram, both
+/// in the translation and in the original Chinese. Passages are keyed by the hexagram's number
+/// and the part of its text that they come from.
+fn build_index(
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> SearchIndex<(u8, Passage)> {
+    let mut index = SearchIndex::new();
+    for number in 1..=64 {
+        let info = hexagrams
+            .get_by_number(number)
+            .expect("the repository has every hexagram");
+        let name = info.name();
+        let mut names = vec![name.english.as_str(), &name.chinese];
+        if name.chinese_simplified != name.chinese {
+            names.push(&name.chinese_simplified);
+        }
+        names.push(&name.pinyin);
+        index.add((number, Passage::Name), &names.join("\n"), NAME_WEIGHT);
+        index.add(
+            (number, Passage::Judgement),
+            info.judgement(),
+            JUDGEMENT_WEIGHT,
+        );
+        index.add((number, Passage::Images), info.images(), IMAGES_WEIGHT);
+        for line in info.lines() {
+            index.add(
+                (number, Passage::Line(line.position)),
+                &line.meaning,
+                LINE_WEIGHT,
+            );
+        }
+
+        let classical = info.classical();
+        index.add(
+            (number, Passage::ClassicalJudgement),
+            &classical.judgement,
+            JUDGEMENT_WEIGHT,
+        );
+        index.add(
+            (number, Passage::ClassicalImages),
+            &classical.images,
+            IMAGES_WEIGHT,
+        );
+        for line in &classical.lines {
+            index.add(
+                (number, Passage::ClassicalLine(line.position)),
+                &line.meaning,
+                LINE_WEIGHT,
+            );
+        }
+    }
+
+    index
+}
+
+/// The passages that match a query, as found by `search`.
+#[derive(Serialize)]
+pub(crate) struct SearchResults<'a> {
+    query: &'a str,
+    /// The number of matching passages, which can be more than the number of results.
+    total: usize,
+    results: Vec<SearchResult<'a>>,
+    #[serde(skip)]
+    lang: Lang,
+}
+
+#[derive(Serialize)]
+struct SearchResult<'a> {
+    number: usize,
+    symbol: &'a str,
+    english: &'a str,
+    passage: Passage,
+    score: f64,
+    /// The text around the first match, without the highlighting.
+    snippet: String,
+    #[serde(skip)]
+    snippet_parts: Vec<SnippetPart>,
+}
+
+impl<'a> SearchResults<'a> {
+    /// Search the text of every hexagram, keeping at most `limit` of the best results.
+    pub(crate) fn new(
+        query: &'a str,
+        limit: usize,
+        hexagrams: &'a impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+    ) -> Self {
+        let index = build_index(hexagrams);
+        let hits = index.search(query);
+        let results = hits
+            .iter()
+            .take(limit)
+            .map(|hit| {
+                let (number, passage) = *hit.key;
+                let info = hexagrams
+                    .get_by_number(number)
+                    .expect("the index only has hexagrams from the repository");
+                SearchResult {
+                    number: info.number(),
+                    symbol: info.symbol(),
+                    english: info.english_name(),
+                    passage,
+                    score: hit.score,
+                    snippet: hit.snippet.iter().map(|part| part.text.as_str()).collect(),
+                    snippet_parts: hit.snippet.clone(),
+                }
+            })
+            .collect();
+
+        SearchResults {
+            query,
+            total: hits.len(),
+            results,
+            lang: Lang::default(),
+        }
+    }
+
+    /// Show the headings and messages in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+}
+
+impl SearchResult<'_> {
+    fn heading(&self, messages: &Messages) -> String {
+        let (passage, position) = match self.passage {
+            Passage::Name => ("name", 0),
+            Passage::Judgement => ("judgement", 0),
+            Passage::Images => ("images", 0),
+            Passage::Line(position) => ("line", position),
+            Passage::ClassicalJudgement => ("classical-judgement", 0),
+            Passage::ClassicalImages => ("classical-images", 0),
+            Passage::ClassicalLine(position) => ("classical-line", position),
+        };
+        let passage = messages.format(
+            "search-passage",
+            &[("passage", passage.into()), ("position", position.into())],
+        );
+        format!(
+            "{} {} {}, {passage}",
+            self.symbol, self.number, self.english
+        )
+    }
+}
+
+impl Render for SearchResults<'_> {
+    fn title(&self) -> String {
+        self.lang
+            .messages()
+            .format("search-title", &[("query", self.query.into())])
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+
+        if self.results.is_empty() {
+            return renderer.paragraph(&messages.get("search-nothing-found"));
+        }
+        if self.total > self.results.len() {
+            renderer.paragraph(&messages.format(
+                "search-best-results",
+                &[
+                    ("shown", self.results.len().into()),
+                    ("total", self.total.into()),
+                ],
+            ))?;
+        }
+
+        for result in &self.results {
+            renderer.heading(HeadingLevel::Subsection, &result.heading(messages))?;
+            let spans: Vec<Span> = result
+                .snippet_parts
+                .iter()
+                .map(|part| Span {
+                    text: part.text.clone(),
+                    highlighted: part.highlighted,
+                })
+                .collect();
+            renderer.snippet(&spans)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+
+    fn hexagrams() -> HexagramJson {
+        let mut hexagrams = HexagramJson::new();
+        hexagrams.initialize().unwrap();
+        hexagrams
+    }
+
+    #[test]
+    fn test_dragon_in_the_field_is_found() {
+        let hexagrams = hexagrams();
+        let results = SearchResults::new("dragon in the field", 10, &hexagrams);
+
+        assert_eq!(results.results[0].number, 1);
+        assert_eq!(results.results[0].passage, Passage::Line(2));
+    }
+
+    #[test]
+    fn test_names_are_found_by_pinyin_and_chinese() {
+        let hexagrams = hexagrams();
+
+        for (query, number) in [
+            ("zhun", 3),
+            ("zhūn", 3),
+            ("屯", 3),
+            ("歸妹", 54),
+            ("归妹", 54),
+        ] {
+            let results = SearchResults::new(query, 10, &hexagrams);
+            assert_eq!(results.results[0].number, number, "{query}");
+            assert_eq!(results.results[0].passage, Passage::Name, "{query}");
+        }
+    }
+
+    #[test]
+    fn test_results_are_shown_in_the_chosen_language() {
+        let hexagrams = hexagrams();
+        let results = SearchResults::new("dragon", 1, &hexagrams).with_lang(Lang::ZhHant);
+        let mut output = Vec::new();
+        let mut renderer = render::MarkdownRenderer::new(&mut output);
+        render::render_document(&mut renderer, &results).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("# 「dragon」的搜尋結果"), "{output}");
+        assert!(output.contains("䷀ 1 The Creative, 第"), "{output}");
+        assert!(output.contains("中最好的 1 個"), "{output}");
+    }
+
+    #[test]
+    fn test_original_texts_are_found() {
+        let hexagrams = hexagrams();
+
+        for (query, number, passage) in [
+            ("元亨利貞", 1, Passage::ClassicalJudgement),
+            ("潛龍", 1, Passage::ClassicalLine(1)),
+            ("天行健", 1, Passage::ClassicalImages),
+        ] {
+            let results = SearchResults::new(query, 10, &hexagrams);
+            assert!(
+                results
+                    .results
+                    .iter()
+                    .any(|result| result.number == number && result.passage == passage),
+                "{query}"
+            );
+        }
+        // Only ䷀ The Creative has the hidden dragon.
+        let results = SearchResults::new("潛龍", 10, &hexagrams);
+        assert_eq!(results.total, 1);
+    }
+}
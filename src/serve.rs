@@ -0,0 +1,263 @@
+//! A local HTTP server with a JSON API, opened by `serve`, so that other apps can cast readings
+//! and look up the texts without linking to this crate.
+//!
+//! Responses have the same JSON as the one-shot commands with `--format json`, with the
+//! commentaries and texts that were asked for with `--with` and `--texts` unless a request asks
+//! for others. Requests are
+//! handled one at a time, which is plenty for a server that only listens on the local machine.
+
+use crate::{
+    config::Settings,
+    hexagram_json::HexagramJsonInfo,
+    search_results::SearchResults,
+    view::{HexagramView, Layer, ReadingView, Texts, TrigramView},
+};
+use clap::ValueEnum;
+use iching::{
+    divination_method::DivinationMethod,
+    hexagram::{Hexagram, HexagramOrdering},
+    hexagram_repository::HexagramRepository,
+    trigram::Trigram,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// How many search results are returned when the request doesn't say.
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Failed to listen on port {0}: {1}")]
+    Listen(u16, #[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to write output: {0}")]
+    Output(#[from] io::Error),
+}
+
+/// Why a request couldn't be answered. These are sent back to the client, with their status code.
+#[derive(Debug, thiserror::Error)]
+enum RequestError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0} isn't allowed here")]
+    MethodNotAllowed(Method),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("Failed to write the response: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl RequestError {
+    fn status(&self) -> u16 {
+        match self {
+            RequestError::NotFound(_) => 404,
+            RequestError::MethodNotAllowed(_) => 405,
+            RequestError::BadRequest(_) => 400,
+            RequestError::Json(_) => 500,
+        }
+    }
+}
+
+/// The body of `POST /divine`. Every field can be left out, as can the body itself. The method
+/// defaults to the configured one.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DivineRequest {
+    method: Option<DivinationMethod>,
+    question: Option<String>,
+    /// Casting with the same seed and method always gives the same hexagram.
+    seed: Option<u64>,
+    /// The commentaries to include, instead of the configured ones.
+    with: Option<Vec<Layer>>,
+    /// Which texts of the hexagrams to show, instead of the configured ones.
+    texts: Option<Texts>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Serve the API on `port` of the local machine until the process is stopped. Port 0 picks any
+/// free port. The address that's served on is printed once the server is listening.
+pub(crate) fn serve(
+    port: u16,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<(), Error> {
+    let server = Server::http(("127.0.0.1", port)).map_err(|err| Error::Listen(port, err))?;
+    let mut stdout = io::stdout().lock();
+    writeln!(
+        stdout,
+        "Serving the I Ching at http://{}. Press Ctrl+C to stop.",
+        server.server_addr()
+    )?;
+    stdout.flush()?;
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(&mut request, settings, hexagrams) {
+            Ok(body) => (200, body),
+            Err(err) => (
+                err.status(),
+                serde_json::to_string(&ErrorBody {
+                    error: err.to_string(),
+                })
+                .expect("error bodies can be serialized"),
+            ),
+        };
+        let content_type =
+            Header::from_bytes("Content-Type", "application/json").expect("the header is valid");
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type);
+        // A client that hung up doesn't stop the server from answering the others.
+        if let Err(err) = request.respond(response) {
+            eprintln!("warning: failed to respond to a request. {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Answer a request with a JSON body.
+fn handle(
+    request: &mut Request,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<String, RequestError> {
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let params: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Post, ["divine"]) => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .read_to_string(&mut body)
+                .map_err(|err| {
+                    RequestError::BadRequest(format!("Failed to read the body: {err}"))
+                })?;
+            divine(&body, settings, hexagrams)
+        }
+        (Method::Get, ["hexagrams", number]) => {
+            let ordering = match params.get("ordering") {
+                Some(ordering) => parse_value_enum("ordering", ordering)?,
+                None => HexagramOrdering::KingWen,
+            };
+            let hexagram = number
+                .parse()
+                .ok()
+                .and_then(|number| ordering.hexagram_at(number))
+                .ok_or_else(|| {
+                    RequestError::NotFound(format!(
+                        "There's no hexagram No. {number}. Hexagrams are numbered 1 to 64"
+                    ))
+                })?;
+            let layers = match params.get("with") {
+                Some(layers) => layers
+                    .split(',')
+                    .map(|layer| parse_value_enum("commentary", layer.trim()))
+                    .collect::<Result<_, _>>()?,
+                None => settings.with.clone(),
+            };
+            let texts = match params.get("texts") {
+                Some(texts) => parse_value_enum("texts", texts)?,
+                None => settings.texts,
+            };
+            let view = HexagramView::from(hexagrams.get_info_for_hexagram(&hexagram))
+                .with_lines()
+                .with_lang(settings.lang)
+                .with_texts(texts)
+                .with_commentary(&layers);
+            Ok(serde_json::to_string(&view)?)
+        }
+        (Method::Get, ["trigrams", number]) => {
+            let trigram = number
+                .parse::<u8>()
+                .ok()
+                .and_then(|number| Trigram::try_from(number).ok())
+                .ok_or_else(|| {
+                    RequestError::NotFound(format!(
+                        "There's no trigram No. {number}. Trigrams are numbered 1 to 8"
+                    ))
+                })?;
+            Ok(serde_json::to_string(
+                &TrigramView::from(&trigram).with_lang(settings.lang),
+            )?)
+        }
+        (Method::Get, ["search"]) => {
+            let query = params
+                .get("q")
+                .filter(|query| !query.trim().is_empty())
+                .ok_or_else(|| {
+                    RequestError::BadRequest(String::from("Give the words to search for as ?q="))
+                })?;
+            let limit = match params.get("limit") {
+                Some(limit) => limit.parse().map_err(|_| {
+                    RequestError::BadRequest(format!("The limit must be a count, not {limit}"))
+                })?,
+                None => DEFAULT_SEARCH_LIMIT,
+            };
+            Ok(serde_json::to_string(
+                &SearchResults::new(query, limit, hexagrams).with_lang(settings.lang),
+            )?)
+        }
+        (method, ["divine"] | ["hexagrams", _] | ["trigrams", _] | ["search"]) => {
+            Err(RequestError::MethodNotAllowed(method.clone()))
+        }
+        _ => Err(RequestError::NotFound(format!(
+            "There's nothing at {path}. Try POST /divine, GET /hexagrams/{{n}}, \
+             GET /trigrams/{{n}} or GET /search?q="
+        ))),
+    }
+}
+
+/// Cast a reading, as asked for by the body of `POST /divine`.
+fn divine(
+    body: &str,
+    settings: &Settings,
+    hexagrams: &impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+) -> Result<String, RequestError> {
+    let request: DivineRequest = if body.trim().is_empty() {
+        DivineRequest::default()
+    } else {
+        serde_json::from_str(body).map_err(|err| {
+            RequestError::BadRequest(format!("The body isn't a valid request: {err}"))
+        })?
+    };
+    let method = request.method.unwrap_or(settings.method);
+    let hexagram = match request.seed {
+        Some(seed) => Hexagram::new_seeded(method, seed),
+        None => Hexagram::new_random(method),
+    };
+
+    let layers = request.with.unwrap_or_else(|| settings.with.clone());
+    let view = ReadingView::new(request.question.as_deref(), method, &hexagram, hexagrams)
+        .with_school(settings.school)
+        .with_lang(settings.lang)
+        .with_texts(request.texts.unwrap_or(settings.texts))
+        .with_commentary(&layers);
+    Ok(serde_json::to_string(&view)?)
+}
+
+/// Parse a query parameter with the same names as the command line option for it.
+fn parse_value_enum<T: ValueEnum>(parameter: &str, value: &str) -> Result<T, RequestError> {
+    T::from_str(value, true).map_err(|_| {
+        let names: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|variant| variant.to_possible_value())
+            .map(|value| value.get_name().to_owned())
+            .collect();
+        RequestError::BadRequest(format!(
+            "Unknown {parameter} {value}. Use one of {}",
+            names.join(", ")
+        ))
+    })
+}
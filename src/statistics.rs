@@ -0,0 +1,189 @@
+use serde_derive::Serialize;
+
+/// The result of [Pearson's chi-squared test](https://en.wikipedia.org/wiki/Pearson%27s_chi-squared_test)
+/// comparing observed counts against a theoretical distribution.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct ChiSquare {
+    /// The chi-squared statistic. Larger values mean the observed counts are further from the
+    /// expected counts.
+    pub statistic: f64,
+    /// The number of degrees of freedom, one fewer than the number of categories.
+    pub degrees_of_freedom: usize,
+    /// The probability of seeing a statistic at least this large if the counts really were drawn
+    /// from the theoretical distribution. Small values (e.g. below 0.05) suggest they weren't.
+    pub p_value: f64,
+    /// The smallest expected count of any category. The test is unreliable when this is below 5.
+    pub min_expected: f64,
+}
+
+impl ChiSquare {
+    /// Run a goodness-of-fit test of `observed` counts against the `probabilities` of each
+    /// category. Both slices must be the same length and the probabilities should sum to 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths or if there are fewer than two categories.
+    pub fn goodness_of_fit(observed: &[u64], probabilities: &[f64]) -> Self {
+        assert_eq!(
+            observed.len(),
+            probabilities.len(),
+            "each observed count must have a probability"
+        );
+        assert!(
+            observed.len() >= 2,
+            "a chi-squared test needs at least two categories"
+        );
+
+        let total: u64 = observed.iter().sum();
+        let mut statistic = 0.0;
+        let mut min_expected = f64::INFINITY;
+        for (&observed, &probability) in observed.iter().zip(probabilities) {
+            let expected = total as f64 * probability;
+            min_expected = min_expected.min(expected);
+            if expected > 0.0 {
+                statistic += (observed as f64 - expected).powi(2) / expected;
+            } else if observed > 0 {
+                // Something impossible happened.
+                statistic = f64::INFINITY;
+            }
+        }
+
+        let degrees_of_freedom = observed.len() - 1;
+        let p_value = if statistic.is_finite() {
+            regularized_upper_gamma(degrees_of_freedom as f64 / 2.0, statistic / 2.0)
+        } else {
+            0.0
+        };
+
+        ChiSquare {
+            statistic,
+            degrees_of_freedom,
+            p_value,
+            min_expected,
+        }
+    }
+}
+
+/// The regularized upper incomplete gamma function Q(a, x), which is also the survival function
+/// of the chi-squared distribution (with `a = k / 2` and `x = statistic / 2`.)
+///
+/// Uses a series expansion for small `x` and a continued fraction otherwise, as described in
+/// _Numerical Recipes_, section 6.2.
+fn regularized_upper_gamma(a: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 1000;
+    const EPSILON: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let log_prefactor = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut n = a;
+        for _ in 0..MAX_ITERATIONS {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+
+        (1.0 - sum * log_prefactor.exp()).clamp(0.0, 1.0)
+    } else {
+        // Modified Lentz's method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+
+        (log_prefactor.exp() * h).clamp(0.0, 1.0)
+    }
+}
+
+/// The natural log of the gamma function, using the
+/// [Lanczos approximation](https://en.wikipedia.org/wiki/Lanczos_approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut y = x;
+    let mut series = 1.000_000_000_190_015;
+    for coefficient in COEFFICIENTS {
+        y += 1.0;
+        series += coefficient / y;
+    }
+
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected} but got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_p_values_match_known_critical_values() {
+        // (degrees of freedom, critical value at p = 0.05)
+        for (degrees_of_freedom, critical_value) in
+            [(1, 3.841), (3, 7.815), (7, 14.067), (63, 82.529)]
+        {
+            let p_value =
+                regularized_upper_gamma(degrees_of_freedom as f64 / 2.0, critical_value / 2.0);
+            assert_close(p_value, 0.05);
+        }
+    }
+
+    #[test]
+    fn test_perfect_fit() {
+        let test = ChiSquare::goodness_of_fit(&[25, 25, 50], &[0.25, 0.25, 0.5]);
+
+        assert_eq!(test.statistic, 0.0);
+        assert_eq!(test.degrees_of_freedom, 2);
+        assert_close(test.p_value, 1.0);
+        assert_close(test.min_expected, 25.0);
+    }
+
+    #[test]
+    fn test_impossible_outcome() {
+        let test = ChiSquare::goodness_of_fit(&[10, 1], &[1.0, 0.0]);
+
+        assert_eq!(test.p_value, 0.0);
+    }
+}
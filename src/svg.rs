@@ -0,0 +1,432 @@
+use crate::{hexagram::Hexagram, line::Line, trigram::Trigram};
+use std::fmt::Write;
+
+/// How [`Line`]s are drawn by the functions in this module. All sizes are in pixels, and colors
+/// can be anything that SVG accepts, e.g. `#c00` or `darkred`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgStyle {
+    /// The width of each line.
+    pub line_width: f64,
+    /// The thickness of each line.
+    pub line_height: f64,
+    /// The space between neighbouring lines.
+    pub line_spacing: f64,
+    /// The width of the gap in the middle of broken and changing lines.
+    pub gap_width: f64,
+    /// The space around the edge of the image.
+    pub padding: f64,
+    pub line_color: String,
+    /// Changing lines, and the lines that they change into, are drawn in this color.
+    pub changing_color: String,
+    /// Fills the image, unless it's `None`.
+    pub background: Option<String>,
+    pub text_color: String,
+    pub font_family: String,
+    pub font_size: f64,
+    /// Whether changing lines are marked with a circle (old yang) or a cross (old yin). They're
+    /// drawn in the changing color either way.
+    pub markers: bool,
+}
+
+impl SvgStyle {
+    /// Get this style with every size multiplied by `factor`, to draw bigger or smaller images.
+    pub fn scaled(mut self, factor: f64) -> Self {
+        for size in [
+            &mut self.line_width,
+            &mut self.line_height,
+            &mut self.line_spacing,
+            &mut self.gap_width,
+            &mut self.padding,
+            &mut self.font_size,
+        ] {
+            *size *= factor;
+        }
+        self
+    }
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            line_width: 120.0,
+            line_height: 14.0,
+            line_spacing: 10.0,
+            gap_width: 24.0,
+            padding: 20.0,
+            line_color: String::from("#222"),
+            changing_color: String::from("#c00"),
+            background: Some(String::from("#fff")),
+            text_color: String::from("#222"),
+            font_family: String::from("serif"),
+            font_size: 16.0,
+            markers: true,
+        }
+    }
+}
+
+/// The text of a [`reading_card`]. Line breaks in any of the text are kept.
+#[derive(Clone, Copy, Debug)]
+pub struct ReadingCard<'a> {
+    /// The hexagram that was cast. If any lines are changing, the relating hexagram is drawn
+    /// to the right of it.
+    pub hexagram: &'a Hexagram,
+    /// Drawn across the top of the card, e.g. the question that was asked.
+    pub title: Option<&'a str>,
+    /// Drawn below the primary hexagram, e.g. its number and name.
+    pub primary_label: &'a str,
+    /// Drawn below the relating hexagram, if there is one.
+    pub relating_label: Option<&'a str>,
+    /// Drawn across the bottom of the card, e.g. the judgement of the primary hexagram.
+    pub body: Option<&'a str>,
+}
+
+/// Draw a [`Hexagram`] as a standalone SVG image. Changing lines are marked with a circle (old
+/// yang) or a cross (old yin), like the art in [`symbols::big_line`](crate::symbols::big_line),
+/// unless [`SvgStyle::markers`] is turned off.
+pub fn hexagram(hexagram: &Hexagram, style: &SvgStyle) -> String {
+    let lines: Vec<Line> = hexagram.lines().copied().collect();
+    lines_image(&lines, style)
+}
+
+/// Draw a [`Trigram`] as a standalone SVG image, with changing lines marked as in [`hexagram`].
+pub fn trigram(trigram: &Trigram, style: &SvgStyle) -> String {
+    let lines: Vec<Line> = trigram.lines().copied().collect();
+    lines_image(&lines, style)
+}
+
+/// Draw a whole reading as a standalone SVG image: the primary hexagram, an arrow and the
+/// relating hexagram, each with a label below it.
+pub fn reading_card(card: &ReadingCard, style: &SvgStyle) -> String {
+    let primary: Vec<Line> = card.hexagram.lines().copied().collect();
+    let relating: Option<Vec<Line>> = card
+        .hexagram
+        .relating_hexagram()
+        .map(|relating| relating.lines().copied().collect());
+
+    let line_height = text_line_height(style);
+    let arrow_width = style.line_width / 2.0;
+    // Each hexagram is centered in a column that's wide enough for its label.
+    let primary_column = style
+        .line_width
+        .max(estimated_text_width(card.primary_label, style));
+    let relating_column = relating.as_ref().map(|_| {
+        style.line_width.max(
+            card.relating_label
+                .map(|label| estimated_text_width(label, style))
+                .unwrap_or_default(),
+        )
+    });
+    let columns_width = primary_column + relating_column.map_or(0.0, |width| arrow_width + width);
+    let text_width = [card.title, card.body]
+        .into_iter()
+        .flatten()
+        .map(|text| estimated_text_width(text, style))
+        .fold(0.0, f64::max);
+    let width = style.padding * 2.0 + columns_width.max(text_width);
+
+    let title_height = card
+        .title
+        .map_or(0.0, |title| text_height(title, style) + line_height / 2.0);
+    let lines_top = style.padding + title_height;
+    let lines_height = stack_height(primary.len(), style);
+    let labels_top = lines_top + lines_height + line_height / 2.0;
+    let labels_height = [Some(card.primary_label), card.relating_label]
+        .into_iter()
+        .flatten()
+        .map(|label| text_height(label, style))
+        .fold(0.0, f64::max);
+    let body_top = labels_top + labels_height + line_height;
+    let body_height = card.body.map_or(0.0, |body| text_height(body, style));
+    let height = body_top + body_height + style.padding;
+
+    let mut svg = String::new();
+    begin_image(&mut svg, width, height, style);
+
+    if let Some(title) = card.title {
+        text(&mut svg, width / 2.0, style.padding, title, style);
+    }
+
+    let primary_left = (width - columns_width) / 2.0;
+    let primary_center = primary_left + primary_column / 2.0;
+    draw_lines(
+        &mut svg,
+        &primary,
+        primary_center - style.line_width / 2.0,
+        lines_top,
+        style,
+        &[],
+    );
+    text(
+        &mut svg,
+        primary_center,
+        labels_top,
+        card.primary_label,
+        style,
+    );
+
+    if let (Some(relating), Some(relating_column)) = (relating, relating_column) {
+        let changed: Vec<bool> = primary.iter().map(Line::is_changing).collect();
+        let arrow_left = primary_left + primary_column;
+        let relating_center = arrow_left + arrow_width + relating_column / 2.0;
+        draw_arrow(
+            &mut svg,
+            arrow_left,
+            lines_top + lines_height / 2.0,
+            arrow_width,
+            style,
+        );
+        draw_lines(
+            &mut svg,
+            &relating,
+            relating_center - style.line_width / 2.0,
+            lines_top,
+            style,
+            &changed,
+        );
+        if let Some(label) = card.relating_label {
+            text(&mut svg, relating_center, labels_top, label, style);
+        }
+    }
+
+    if let Some(body) = card.body {
+        text(&mut svg, width / 2.0, body_top, body, style);
+    }
+
+    end_image(&mut svg);
+    svg
+}
+
+fn lines_image(lines: &[Line], style: &SvgStyle) -> String {
+    let width = style.line_width + style.padding * 2.0;
+    let height = stack_height(lines.len(), style) + style.padding * 2.0;
+
+    let mut svg = String::new();
+    begin_image(&mut svg, width, height, style);
+    draw_lines(&mut svg, lines, style.padding, style.padding, style, &[]);
+    end_image(&mut svg);
+    svg
+}
+
+/// The height of a stack of `count` lines.
+fn stack_height(count: usize, style: &SvgStyle) -> f64 {
+    let count = count as f64;
+    count * style.line_height + (count - 1.0).max(0.0) * style.line_spacing
+}
+
+fn begin_image(svg: &mut String, width: f64, height: f64, style: &SvgStyle) {
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .expect("writing to a string succeeds");
+    if let Some(background) = &style.background {
+        writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            escape(background)
+        )
+        .expect("writing to a string succeeds");
+    }
+}
+
+fn end_image(svg: &mut String) {
+    svg.push_str("</svg>\n");
+}
+
+/// Draw lines from the top down, with their top left corner at (`left`, `top`). Changing lines are
+/// drawn with markers, if the style has them. Lines whose entry in `highlighted` is `true` are
+/// drawn in the changing color, without markers.
+fn draw_lines(
+    svg: &mut String,
+    lines: &[Line],
+    left: f64,
+    top: f64,
+    style: &SvgStyle,
+    highlighted: &[bool],
+) {
+    for (index, line) in lines.iter().enumerate() {
+        let y = top + index as f64 * (style.line_height + style.line_spacing);
+        let is_highlighted = highlighted.get(index).copied().unwrap_or_default();
+        let color = if line.is_changing() || is_highlighted {
+            &style.changing_color
+        } else {
+            &style.line_color
+        };
+
+        let has_gap = line.is_changing() || matches!(line, Line::Broken { .. });
+        if has_gap {
+            let half_width = (style.line_width - style.gap_width) / 2.0;
+            rect(svg, left, y, half_width, style.line_height, color);
+            rect(
+                svg,
+                left + half_width + style.gap_width,
+                y,
+                half_width,
+                style.line_height,
+                color,
+            );
+        } else {
+            rect(svg, left, y, style.line_width, style.line_height, color);
+        }
+
+        let center_x = left + style.line_width / 2.0;
+        let center_y = y + style.line_height / 2.0;
+        let radius = (style.gap_width.min(style.line_height + style.line_spacing) / 2.0) * 0.7;
+        let stroke_width = (style.line_height / 5.0).max(1.0);
+        match line {
+            _ if !style.markers => {}
+            Line::Unbroken { changing: true } => {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{center_x}" cy="{center_y}" r="{radius}" fill="none" stroke="{}" stroke-width="{stroke_width}"/>"#,
+                    escape(color)
+                )
+                .expect("writing to a string succeeds");
+            }
+            Line::Broken { changing: true } => {
+                let (x1, x2) = (center_x - radius, center_x + radius);
+                let (y1, y2) = (center_y - radius, center_y + radius);
+                writeln!(
+                    svg,
+                    r#"<path d="M {x1} {y1} L {x2} {y2} M {x2} {y1} L {x1} {y2}" stroke="{}" stroke-width="{stroke_width}"/>"#,
+                    escape(color)
+                )
+                .expect("writing to a string succeeds");
+            }
+            _ => {}
+        }
+    }
+}
+
+fn rect(svg: &mut String, x: f64, y: f64, width: f64, height: f64, color: &str) {
+    writeln!(
+        svg,
+        r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{}"/>"#,
+        escape(color)
+    )
+    .expect("writing to a string succeeds");
+}
+
+/// Draw an arrow pointing right, centered vertically on `center_y`.
+fn draw_arrow(svg: &mut String, left: f64, center_y: f64, width: f64, style: &SvgStyle) {
+    let start = left + width * 0.2;
+    let end = left + width * 0.8;
+    let head = style.line_height;
+    writeln!(
+        svg,
+        r#"<path d="M {start} {center_y} L {end} {center_y} M {} {} L {end} {center_y} L {} {}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+        end - head,
+        center_y - head / 2.0,
+        end - head,
+        center_y + head / 2.0,
+        escape(&style.line_color),
+        (style.line_height / 5.0).max(1.0),
+    )
+    .expect("writing to a string succeeds");
+}
+
+/// The distance between the baselines of neighbouring lines of text.
+fn text_line_height(style: &SvgStyle) -> f64 {
+    style.font_size * 1.5
+}
+
+/// How tall `text` is once drawn, with one line of text for each of its lines.
+fn text_height(text: &str, style: &SvgStyle) -> f64 {
+    text.lines().count() as f64 * text_line_height(style)
+}
+
+/// A rough guess of how wide the longest line of `text` is once drawn, since the actual width
+/// depends on the font.
+fn estimated_text_width(text: &str, style: &SvgStyle) -> f64 {
+    text.lines()
+        .map(|line| line.chars().count() as f64 * style.font_size * 0.6)
+        .fold(0.0, f64::max)
+}
+
+/// Draw each line of `text`, centered horizontally on `center_x`, starting at `top`.
+fn text(svg: &mut String, center_x: f64, top: f64, text: &str, style: &SvgStyle) {
+    for (index, line) in text.lines().enumerate() {
+        // Place the baseline so that the text sits in the middle of its line.
+        let y = top + (index as f64 + 0.5) * text_line_height(style) + style.font_size * 0.35;
+        writeln!(
+            svg,
+            r#"<text x="{center_x}" y="{y}" text-anchor="middle" font-family="{}" font-size="{}" fill="{}">{}</text>"#,
+            escape(&style.font_family),
+            style.font_size,
+            escape(&style.text_color),
+            escape(line)
+        )
+        .expect("writing to a string succeeds");
+    }
+}
+
+/// Escape text so that it can be included in XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changing_lines_are_marked() {
+        // Old yin at the bottom and old yang at the top
+        let hexagram = Hexagram::try_from([6, 7, 8, 7, 8, 9]).unwrap();
+        let svg = super::hexagram(&hexagram, &SvgStyle::default());
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle ").count(), 1);
+        assert_eq!(svg.matches("<path ").count(), 1);
+        // The background, 2 halves for each of the 2 changing and 2 broken lines, and 2 whole
+        // unbroken lines
+        assert_eq!(svg.matches("<rect ").count(), 1 + 2 * 4 + 2);
+    }
+
+    #[test]
+    fn test_markers_can_be_left_out_and_images_scaled() {
+        let hexagram = Hexagram::try_from([6, 7, 8, 7, 8, 9]).unwrap();
+        let style = SvgStyle {
+            markers: false,
+            ..SvgStyle::default()
+        }
+        .scaled(2.0);
+        let svg = super::hexagram(&hexagram, &style);
+
+        assert!(!svg.contains("<circle ") && !svg.contains("<path "));
+        // Changing lines are still drawn in the changing color.
+        assert_eq!(svg.matches(r##"fill="#c00""##).count(), 4);
+        // 120 wide lines with 20 padding on each side, twice the usual size
+        assert!(svg.contains(r#" width="320" "#));
+    }
+
+    #[test]
+    fn test_reading_card_text_is_escaped_and_broken_into_lines() {
+        let hexagram = Hexagram::try_from([9, 7, 7, 7, 7, 7]).unwrap();
+        let card = ReadingCard {
+            hexagram: &hexagram,
+            title: Some("Should I <quit> & leave?"),
+            primary_label: "䷀ 1 The Creative",
+            relating_label: Some("䷫ 44 Coming to Meet"),
+            body: Some("The Creative works sublime success,\nFurthering through perseverance."),
+        };
+        let svg = reading_card(&card, &SvgStyle::default());
+
+        assert!(svg.contains("Should I &lt;quit&gt; &amp; leave?"));
+        assert!(svg.contains("䷫ 44 Coming to Meet"));
+        // Each line of the body is drawn separately.
+        assert!(svg.contains(">Furthering through perseverance.</text>"));
+    }
+}
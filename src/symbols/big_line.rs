@@ -1,3 +1,5 @@
+//! Each piece of art is a sequence of rows of equal width. Use [`rows`] to iterate over them.
+
 pub const BROKEN_CHANGING: &str = "
                             ▁   ▁                            \n
                             ▜▙ ▟▛                            \n
@@ -34,3 +36,8 @@ pub const LINE_SPACER: &str = "
                                                              \n
                                                              \n
 ";
+
+/// Iterate over the rows of a piece of big line art, without their line endings.
+pub fn rows(art: &'static str) -> impl Iterator<Item = &'static str> {
+    art.lines().filter(|row| !row.is_empty())
+}
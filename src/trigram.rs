@@ -1,12 +1,17 @@
 use crate::{
     divination_method::DivinationMethod,
     line::{Line, Line::*},
+    search::{edit_distance, normalize},
     symbols::trigram::*,
 };
 use num_bigint::BigInt;
 use serde_derive::Deserialize;
-use std::{fmt::Display, fmt::Formatter, io::Write};
-use termcolor::{Color, ColorSpec, WriteColor};
+use std::{
+    fmt::Display,
+    fmt::Formatter,
+    io::{self, Write},
+    str::FromStr,
+};
 
 const QIAN: Trigram = Trigram(Line::unbroken(), Line::unbroken(), Line::unbroken()); // "☰",
 const KUN: Trigram = Trigram(Line::broken(), Line::broken(), Line::broken()); // "☷",
@@ -25,10 +30,15 @@ pub struct Trigram(pub Line, pub Line, pub Line);
 impl Trigram {
     /// Create a new `Hexagram` from random [`Trigram`]s.
     pub fn new_random(divination_method: DivinationMethod) -> Self {
+        Self::new_random_with_rng(divination_method, &fastrand::Rng::new())
+    }
+
+    /// Create a new random `Trigram`, drawing each [`Line`] from the given [`fastrand::Rng`].
+    pub fn new_random_with_rng(divination_method: DivinationMethod, rng: &fastrand::Rng) -> Self {
         Trigram(
-            Line::new_random(divination_method),
-            Line::new_random(divination_method),
-            Line::new_random(divination_method),
+            Line::new_random_with_rng(divination_method, rng),
+            Line::new_random_with_rng(divination_method, rng),
+            Line::new_random_with_rng(divination_method, rng),
         )
     }
 
@@ -39,14 +49,22 @@ impl Trigram {
 
     /// Print the `Trigram` as large ASCII-art lines.
     pub fn print_big(&self) {
-        use crate::symbols::big_line::LINE_SPACER;
-
-        self.0.print_big();
-        print!("{LINE_SPACER}");
-        self.1.print_big();
-        print!("{LINE_SPACER}");
-        self.2.print_big();
-        print!("{LINE_SPACER}");
+        self.write_big(&mut std::io::stdout().lock())
+            .expect("stdout is writable");
+    }
+
+    /// Write the `Trigram` as large ASCII-art lines, each followed by a spacer.
+    pub fn write_big<W: Write + ?Sized>(&self, output: &mut W) -> io::Result<()> {
+        use crate::symbols::big_line::{rows, LINE_SPACER};
+
+        for line in self.lines() {
+            line.write_big(output)?;
+            for row in rows(LINE_SPACER) {
+                writeln!(output, "{row}")?;
+            }
+        }
+
+        Ok(())
     }
 
     /// Generate a new `Trigram` by using the coin toss method.
@@ -97,7 +115,7 @@ impl Trigram {
         }
     }
 
-    /// Get the Chinese character version of the `Trigram`.
+    /// Get the Chinese character version of the `Trigram`, in traditional characters.
     pub fn chinese(&self) -> String {
         use TrigramName::*;
         match self.into() {
@@ -112,6 +130,16 @@ impl Trigram {
         }
     }
 
+    /// Get the Chinese character version of the `Trigram`, in simplified characters.
+    pub fn chinese_simplified(&self) -> String {
+        use TrigramName::*;
+        match self.into() {
+            Li => String::from("离"),
+            Dui => String::from("兑"),
+            _ => self.chinese(),
+        }
+    }
+
     /// Get the English translation of the `Trigram`.
     pub fn english(&self) -> String {
         use TrigramName::*;
@@ -187,47 +215,18 @@ impl Trigram {
         }
     }
 
-    pub fn write_to<T>(&self, output: &mut T) -> Result<(), Box<dyn std::error::Error>>
-    where
-        T: WriteColor + Write,
-    {
-        // Write out header
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(196))))
-            .expect("output stream color can be set");
-
-        writeln!(output, "Trigram No. {}  {}", self.number(), self.symbol())?;
-        output.reset().expect("output stream color can be reset");
-
-        writeln!(output, "\t{}", self.english())?;
-        writeln!(output, "\t{} ({})", self.chinese(), self.pinyin())?;
-        writeln!(output)?;
-
-        // Write out attribute
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(160))))
-            .expect("output stream color can be set");
-        writeln!(output, "Attribute:")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "\t{}", self.attribute())?;
-
-        // Write out image
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(124))))
-            .expect("output stream color can be set");
-        writeln!(output, "Image in nature:")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "\t{}", self.image())?;
-
-        // Write out family relationship
-        output
-            .set_color(ColorSpec::new().set_fg(Some(Color::Ansi256(88))))
-            .expect("output stream color can be set");
-        writeln!(output, "Family Relationship:")?;
-        output.reset().expect("output stream color can be reset");
-        writeln!(output, "\t{}", self.family_relationship())?;
-
-        Ok(())
+    /// Get the `Trigram`'s lines from the bottom up, with 1 for each unbroken line and 0 for each
+    /// broken line, e.g. `100` for Zhèn.
+    pub fn line_notation(&self) -> String {
+        let mut lines: Vec<&Line> = self.lines().collect();
+        lines.reverse();
+        lines
+            .into_iter()
+            .map(|line| match line {
+                Unbroken { .. } => '1',
+                Broken { .. } => '0',
+            })
+            .collect()
     }
 
     fn try_from<N>(n: N) -> Result<Self, Error>
@@ -285,7 +284,12 @@ impl AsRef<Trigram> for Trigram {
 }
 
 /// Named trigrams according to the [Bagua](https://en.wikipedia.org/wiki/Bagua).
-#[derive(Deserialize, Clone, Debug)]
+///
+/// A `TrigramName` can be parsed from anything that names a trigram: its pinyin (with or without
+/// tone marks), its English name, its Chinese character, its symbol, its lines from the bottom up
+/// (e.g. `100`), its image in nature (e.g. `thunder`) or its family member (e.g. `first son`).
+/// Small typos are forgiven.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TrigramName {
     Dui,  // Lake or Marsh, 兌, Duì
     Gen,  // Mountain, 艮, Gèn
@@ -315,6 +319,123 @@ where
     }
 }
 
+impl TrigramName {
+    const ALL: [TrigramName; 8] = [
+        TrigramName::Qian,
+        TrigramName::Dui,
+        TrigramName::Li,
+        TrigramName::Zhen,
+        TrigramName::Xun,
+        TrigramName::Kan,
+        TrigramName::Gen,
+        TrigramName::Kun,
+    ];
+
+    /// Every name that the trigram can be parsed from, as compared by [`simplify_name`].
+    fn names(self) -> Vec<String> {
+        let trigram = Trigram::from(self);
+        let mut names = vec![
+            trigram.pinyin(),
+            trigram.chinese(),
+            trigram.chinese_simplified(),
+            trigram.symbol().to_owned(),
+            trigram.english(),
+            trigram.family_relationship(),
+            trigram.line_notation(),
+        ];
+        names.extend(trigram.image().split(", ").map(str::to_owned));
+
+        names.iter().map(|name| simplify_name(name)).collect()
+    }
+}
+
+/// Simplify a name for comparison: lowercase, without tone marks, extra spaces or a leading "the".
+fn simplify_name(name: &str) -> String {
+    let name = normalize(name);
+    let words: Vec<&str> = name.split_whitespace().collect();
+    match words.as_slice() {
+        ["the", rest @ ..] if !rest.is_empty() => rest.join(" "),
+        words => words.join(" "),
+    }
+}
+
+impl FromStr for TrigramName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let query = simplify_name(name);
+        let mut distances: Vec<(usize, TrigramName)> = TrigramName::ALL
+            .into_iter()
+            .map(|trigram| {
+                let distance = trigram
+                    .names()
+                    .iter()
+                    .map(|name| edit_distance(&query, name))
+                    .min()
+                    .expect("every trigram has names");
+                (distance, trigram)
+            })
+            .collect();
+        distances.sort_by_key(|(distance, _)| *distance);
+
+        // Allow a typo for every few letters, but only if a single trigram is that close. Symbols,
+        // characters and lines are too short to have typos in.
+        let allowed_typos = if query.chars().all(char::is_alphabetic) && query.is_ascii() {
+            query.len() / 4
+        } else {
+            0
+        };
+        match distances.as_slice() {
+            [(best, trigram), (second, _), ..] if *best <= allowed_typos && best < second => {
+                Ok(*trigram)
+            }
+            _ => Err(Error::UnknownName {
+                name: name.to_owned(),
+                suggestions: distances
+                    .iter()
+                    .take(3)
+                    .map(|(_, trigram)| *trigram)
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// List trigrams as e.g. `☳ zhen (thunder), ☵ kan (water) or ☶ gen (mountain)`.
+fn describe_suggestions(names: &[TrigramName]) -> String {
+    let descriptions: Vec<String> = names
+        .iter()
+        .map(|&name| {
+            let trigram = Trigram::from(name);
+            format!(
+                "{} {} ({})",
+                trigram.symbol(),
+                normalize(&trigram.pinyin()),
+                trigram.image()
+            )
+        })
+        .collect();
+    match descriptions.as_slice() {
+        [rest @ .., last] if !rest.is_empty() => format!("{} or {last}", rest.join(", ")),
+        descriptions => descriptions.join(""),
+    }
+}
+
+impl From<TrigramName> for Trigram {
+    fn from(name: TrigramName) -> Self {
+        match name {
+            TrigramName::Qian => QIAN,
+            TrigramName::Kun => KUN,
+            TrigramName::Zhen => ZHEN,
+            TrigramName::Kan => KAN,
+            TrigramName::Gen => GEN,
+            TrigramName::Xun => XUN,
+            TrigramName::Li => LI,
+            TrigramName::Dui => DUI,
+        }
+    }
+}
+
 impl Display for Trigram {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         writeln!(f, "{} (No. {})", self.symbol(), self.number())?;
@@ -338,4 +459,53 @@ pub enum Error {
     IntegerOutOfRange(BigInt),
     #[error("Failed to create Trigram line: {0}")]
     Line(#[from] crate::line::Error),
+    /// Thrown when parsing a `TrigramName` from text that doesn't name a trigram.
+    #[error("No trigram is called {name:?}. Did you mean {}?", describe_suggestions(.suggestions))]
+    UnknownName {
+        name: String,
+        /// The trigrams with the closest names, closest first.
+        suggestions: Vec<TrigramName>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigram_names_are_parsed_in_every_form() {
+        for name in [
+            "zhen",
+            "Zhèn",
+            "The Arousing",
+            "arousing",
+            "震",
+            "☳",
+            "100",
+            "thunder",
+            "First Son",
+            "thundr",
+        ] {
+            assert_eq!(
+                name.parse::<TrigramName>().unwrap(),
+                TrigramName::Zhen,
+                "{name}"
+            );
+        }
+        assert_eq!("离".parse::<TrigramName>().unwrap(), TrigramName::Li);
+        assert_eq!("sky".parse::<TrigramName>().unwrap(), TrigramName::Qian);
+    }
+
+    #[test]
+    fn test_unknown_trigram_names_suggest_the_closest() {
+        let err = "son".parse::<TrigramName>().unwrap_err();
+        assert!(matches!(err, Error::UnknownName { .. }));
+
+        let err = "montain range".parse::<TrigramName>().unwrap_err();
+        let Error::UnknownName { suggestions, .. } = &err else {
+            panic!("{err}");
+        };
+        assert_eq!(suggestions[0], TrigramName::Gen);
+        assert!(err.to_string().contains("☶ gen (mountain)"), "{err}");
+    }
 }
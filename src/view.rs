@@ -0,0 +1,1116 @@
+//! Views of the app's output.
+//!
+//! Each view is either drawn by one of the [renderers](crate::render) (for `--format text`,
+//! `markdown` and `html`), drawn as an SVG image (for `--format svg`) or serialized (for
+//! `--format json` and `yaml`.) The serialized fields of a reading (from `divine`, `daily` and `journal show`) are:
+//!
+//! - `date`: the day that a `daily` hexagram was received for, formatted as `YYYY-MM-DD`. Only
+//!   present for `daily` readings.
+//! - `id`, `timestamp` and `notes`: the journal entry's ID, when the reading was made (in RFC 3339
+//!   format) and any notes added to it. Only present for `journal show` readings.
+//! - `question`: the question that was asked, or `null`.
+//! - `method`: either `ancient-yarrow-stalk` or `coin-toss`.
+//! - `lines`: the value (6-9) of each line, from the bottom line up.
+//! - `changing_lines`: the positions (1-6, from the bottom line up) of the changing lines.
+//! - `primary`: the primary hexagram.
+//! - `relating`: the relating hexagram, or `null` if no lines are changing.
+//! - `changing_line_meanings`: the meanings of the changing lines of the primary hexagram, each
+//!   with a `position` and a `meaning`.
+//! - `school`: the school of interpretation that picked the lines to consider, either
+//!   `every-changing-line` or `zhu-xi`.
+//! - `lines_to_consider`: the line texts that the school says to consider, each with a `hexagram`
+//!   (`primary` or `relating`), a `position`, a `meaning`, the `classical` text of the line and,
+//!   if the Small Images were asked for with `--with`, its `small_image`.
+//!
+//! Hexagrams (from `hexagram`, or in a reading) have these fields:
+//!
+//! - `number`: the hexagram's King Wen sequence number.
+//! - `symbol`: the hexagram's unicode symbol.
+//! - `name`: the hexagram's name, with `english`, `chinese` (in traditional characters),
+//!   `chinese_simplified` and `pinyin` fields.
+//! - `above` and `below`: the hexagram's trigrams.
+//! - `judgement` and `images`: the text of the hexagram's judgement and images.
+//! - `lines`: the meaning of each line, each with a `position` and a `meaning`.
+//! - `classical`: the original Chinese text, in traditional characters, with `judgement`,
+//!   `images` and `lines` fields like the ones above.
+//! - `commentary`: the commentaries of the Ten Wings that were asked for with `--with`, in
+//!   traditional characters. Only present if any were asked for, and only with the fields of
+//!   those that were: `tuan`, `small_images` (each with a `position` and a `meaning`), `wenyan`
+//!   (`null` except for hexagrams 1 and 2), `sequence` and `miscellaneous`.
+//!
+//! Trigrams (from `trigram`, or in a hexagram) have `number`, `symbol`, `english`, `chinese`,
+//! `chinese_simplified`, `pinyin`, `attribute`, `image` and `family_relationship` fields.
+//!
+//! Fields are only ever added to these views, never removed or renamed.
+
+use crate::{
+    hexagram_json::{
+        ChangingLineMeaning, ClassicalText, Commentary, HexagramJsonInfo, NameTranslations,
+    },
+    journal::Reading,
+    journal_stats::method_name,
+    locale::{Lang, Messages},
+    render::{
+        self, figure::big_hexagrams, render_document, Column, HeadingLevel, HtmlRenderer,
+        MarkdownRenderer, Render, Renderer, SectionRole, TextRenderer, Theme,
+    },
+};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use clap::ValueEnum;
+use iching::{
+    divination_method::DivinationMethod,
+    hexagram::Hexagram,
+    hexagram_repository::HexagramRepository,
+    interpretation::{LineLabels, LineSource, School},
+    line::Line,
+    svg::{self, ReadingCard, SvgStyle},
+    trigram::Trigram,
+};
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use std::path::Path;
+use termcolor::WriteColor;
+
+/// The formats that the app can write its output in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum OutputFormat {
+    /// Colorful text, meant for people
+    Text,
+    /// JSON, meant for scripts
+    Json,
+    /// YAML, meant for scripts
+    Yaml,
+    /// Markdown, meant for documents
+    Markdown,
+    /// A standalone HTML page, meant for browsers
+    Html,
+    /// An SVG image of the hexagram, trigram or reading, meant for printing and sharing
+    Svg,
+}
+
+impl OutputFormat {
+    /// Pick the output format for a file by the extension of its name, e.g. `reading.md`.
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref()? {
+            "txt" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "yaml" | "yml" => Some(OutputFormat::Yaml),
+            "md" | "markdown" => Some(OutputFormat::Markdown),
+            "html" | "htm" => Some(OutputFormat::Html),
+            "svg" => Some(OutputFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Which texts of a hexagram are shown: the translation, the original Chinese or both.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Texts {
+    /// The translation
+    #[default]
+    Translation,
+    /// The original classical Chinese
+    Original,
+    /// The original classical Chinese, followed by the translation
+    Both,
+}
+
+impl Texts {
+    /// Pick which of a passage's texts to show, in the order they're shown.
+    fn pick<'b>(self, original: &'b str, translation: &'b str) -> Vec<&'b str> {
+        match self {
+            Texts::Translation => vec![translation],
+            Texts::Original => vec![original],
+            Texts::Both => vec![original, translation],
+        }
+    }
+}
+
+/// The commentaries of the Ten Wings that can be shown along with the text of a hexagram.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Layer {
+    /// Every commentary below
+    Commentary,
+    /// The commentary on the judgement (彖傳)
+    Tuan,
+    /// The Small Images (小象), which comment on each line
+    SmallImages,
+    /// The commentary on the words of the text (文言傳), on hexagrams 1 and 2
+    Wenyan,
+    /// The sequence of the hexagrams (序卦傳)
+    Sequence,
+    /// The miscellaneous notes on the hexagrams (雜卦傳)
+    Miscellaneous,
+    /// No commentary, e.g. to hide the ones that the config file asks for
+    None,
+}
+
+impl Layer {
+    /// Whether `layers` ask for this commentary, by name or with `commentary`.
+    fn is_in(self, layers: &[Layer]) -> bool {
+        layers.contains(&self) || layers.contains(&Layer::Commentary)
+    }
+}
+
+/// The commentaries of the Ten Wings on a hexagram that were asked for.
+#[derive(Serialize)]
+struct CommentaryView<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tuan: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    small_images: Option<&'a [ChangingLineMeaning]>,
+    /// The commentary on the words of the text, which is `null` for most hexagrams when it was
+    /// asked for, and skipped when it wasn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wenyan: Option<Option<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sequence: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    miscellaneous: Option<&'a str>,
+}
+
+impl<'a> CommentaryView<'a> {
+    /// Pick the commentaries that `layers` ask for, or `None` if they ask for none.
+    fn new(commentary: &'a Commentary, layers: &[Layer]) -> Option<Self> {
+        let pick = |layer: Layer| layer.is_in(layers);
+        let view = CommentaryView {
+            tuan: pick(Layer::Tuan).then_some(commentary.tuan.as_str()),
+            small_images: pick(Layer::SmallImages).then_some(commentary.small_images.as_slice()),
+            wenyan: pick(Layer::Wenyan).then_some(commentary.wenyan.as_deref()),
+            sequence: pick(Layer::Sequence).then_some(commentary.sequence.as_str()),
+            miscellaneous: pick(Layer::Miscellaneous).then_some(commentary.miscellaneous.as_str()),
+        };
+        let is_empty = view.tuan.is_none()
+            && view.small_images.is_none()
+            && view.wenyan.is_none()
+            && view.sequence.is_none()
+            && view.miscellaneous.is_none();
+
+        (!is_empty).then_some(view)
+    }
+
+    /// Get the Small Image of the line at `position`, if the Small Images were asked for.
+    fn small_image(&self, position: usize) -> Option<&'a str> {
+        self.small_images?
+            .iter()
+            .find(|line| line.position == position)
+            .map(|line| line.meaning.as_str())
+    }
+}
+
+/// Get the original Chinese text of the line at `position`.
+fn classical_line(classical: &ClassicalText, position: usize) -> &str {
+    classical
+        .lines
+        .iter()
+        .find(|line| line.position == position)
+        .map_or("", |line| &line.meaning)
+}
+
+/// Get a hexagram's or trigram's Chinese name in the characters of the given language:
+/// simplified for Simplified Chinese and traditional otherwise.
+fn chinese_name<'b>(lang: Lang, traditional: &'b str, simplified: &'b str) -> &'b str {
+    match lang {
+        Lang::ZhHans => simplified,
+        Lang::En | Lang::ZhHant => traditional,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    Render(#[from] render::Error),
+    #[error("failed to write JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to write YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("failed to write output: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("there's nothing to draw as SVG. Only hexagrams, trigrams and readings can be drawn")]
+    NothingToDraw,
+}
+
+/// Write a view in the given format. Text is written in the colors of the given theme, wrapped to
+/// `width` columns if there is a width, and images are drawn in the given style.
+pub(crate) fn write_view<W, V>(
+    output: &mut W,
+    format: OutputFormat,
+    theme: &Theme,
+    width: Option<usize>,
+    image: &SvgStyle,
+    view: &V,
+) -> Result<(), Error>
+where
+    W: WriteColor,
+    V: Serialize + Render,
+{
+    match format {
+        OutputFormat::Text => {
+            let mut renderer = TextRenderer::new(output, theme);
+            if let Some(width) = width {
+                renderer = renderer.with_width(width);
+            }
+            render_document(&mut renderer, view)?
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *output, view)?;
+            writeln!(output)?;
+        }
+        OutputFormat::Yaml => serde_yaml::to_writer(output, view)?,
+        OutputFormat::Markdown => render_document(&mut MarkdownRenderer::new(output), view)?,
+        OutputFormat::Html => render_document(&mut HtmlRenderer::new(output), view)?,
+        OutputFormat::Svg => {
+            let svg = view.svg(image).ok_or(Error::NothingToDraw)?;
+            output.write_all(svg.as_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub(crate) struct TrigramView {
+    number: usize,
+    symbol: String,
+    english: String,
+    chinese: String,
+    chinese_simplified: String,
+    pinyin: String,
+    attribute: String,
+    image: String,
+    family_relationship: String,
+    #[serde(skip)]
+    trigram: Trigram,
+    #[serde(skip)]
+    lang: Lang,
+}
+
+impl From<&Trigram> for TrigramView {
+    fn from(trigram: &Trigram) -> Self {
+        TrigramView {
+            number: trigram.number(),
+            symbol: trigram.symbol().to_owned(),
+            english: trigram.english(),
+            chinese: trigram.chinese(),
+            chinese_simplified: trigram.chinese_simplified(),
+            pinyin: trigram.pinyin(),
+            attribute: trigram.attribute(),
+            image: trigram.image(),
+            family_relationship: trigram.family_relationship(),
+            trigram: *trigram,
+            lang: Lang::default(),
+        }
+    }
+}
+
+impl TrigramView {
+    /// Show the labels and the trigram's names and attributes in the given language. The
+    /// serialized fields stay in English.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+}
+
+impl Render for TrigramView {
+    fn title(&self) -> String {
+        self.lang.messages().format(
+            "trigram-title",
+            &[
+                ("number", self.number.into()),
+                ("symbol", self.symbol.as_str().into()),
+            ],
+        )
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        renderer.subtitle(&messages.trigram(&self.trigram, None))?;
+        let chinese = chinese_name(self.lang, &self.chinese, &self.chinese_simplified);
+        renderer.subtitle(&format!("{chinese} ({})", self.pinyin))?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("attribute"),
+        )?;
+        renderer.verse(&messages.trigram(&self.trigram, Some("attribute")))?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Image),
+            &messages.get("image-in-nature"),
+        )?;
+        renderer.verse(&messages.trigram(&self.trigram, Some("image")))?;
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Other),
+            &messages.get("family-relationship"),
+        )?;
+        renderer.verse(&messages.trigram(&self.trigram, Some("family")))
+    }
+
+    fn svg(&self, style: &SvgStyle) -> Option<String> {
+        Some(svg::trigram(&self.trigram, style))
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct HexagramView<'a> {
+    number: usize,
+    symbol: &'a str,
+    name: &'a NameTranslations,
+    above: TrigramView,
+    below: TrigramView,
+    judgement: &'a str,
+    images: &'a str,
+    lines: &'a [ChangingLineMeaning],
+    classical: &'a ClassicalText,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commentary: Option<CommentaryView<'a>>,
+    /// Every commentary on the hexagram, which the ones that are asked for are picked from.
+    #[serde(skip)]
+    commentaries: &'a Commentary,
+    #[serde(skip)]
+    hexagram: Hexagram,
+    /// Whether the meaning of every line is rendered. Every line is always serialized.
+    #[serde(skip)]
+    render_lines: bool,
+    #[serde(skip)]
+    line_labels: LineLabels,
+    #[serde(skip)]
+    lang: Lang,
+    #[serde(skip)]
+    texts: Texts,
+    #[serde(skip)]
+    big: bool,
+}
+
+impl<'a> From<&'a HexagramJsonInfo> for HexagramView<'a> {
+    fn from(hexagram_info: &'a HexagramJsonInfo) -> Self {
+        HexagramView {
+            number: hexagram_info.number(),
+            symbol: hexagram_info.symbol(),
+            name: hexagram_info.name(),
+            above: hexagram_info.hexagram().above().into(),
+            below: hexagram_info.hexagram().below().into(),
+            judgement: hexagram_info.judgement(),
+            images: hexagram_info.images(),
+            lines: hexagram_info.lines(),
+            classical: hexagram_info.classical(),
+            commentary: None,
+            commentaries: hexagram_info.commentary(),
+            hexagram: *hexagram_info.hexagram(),
+            render_lines: false,
+            line_labels: LineLabels::default(),
+            lang: Lang::default(),
+            texts: Texts::default(),
+            big: false,
+        }
+    }
+}
+
+impl HexagramView<'_> {
+    /// Render the meaning of every line, not just the judgement and images.
+    pub(crate) fn with_lines(mut self) -> Self {
+        self.render_lines = true;
+        self
+    }
+
+    /// Draw the hexagram as big lines below its name.
+    pub(crate) fn with_big_lines(mut self) -> Self {
+        self.big = true;
+        self
+    }
+
+    /// Name the lines in the given way.
+    pub(crate) fn with_line_labels(mut self, line_labels: LineLabels) -> Self {
+        self.line_labels = line_labels;
+        self
+    }
+
+    /// Show the headings and line labels in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    /// Show the translation, the original Chinese text or both.
+    pub(crate) fn with_texts(mut self, texts: Texts) -> Self {
+        self.texts = texts;
+        self
+    }
+
+    /// Show the given commentaries of the Ten Wings along with the text.
+    pub(crate) fn with_commentary(mut self, layers: &[Layer]) -> Self {
+        self.commentary = CommentaryView::new(self.commentaries, layers);
+        self
+    }
+
+    /// A short label for the hexagram, e.g. `䷀ 1 The Creative`.
+    pub(crate) fn label(&self) -> String {
+        format!("{} {} {}", self.symbol, self.number, self.name.english)
+    }
+
+    /// The label drawn below the hexagram on a reading card, with its Chinese name, in the
+    /// characters of the language, and pinyin below the short [`label`](Self::label).
+    fn card_label(&self) -> String {
+        format!(
+            "{}\n{} ({})",
+            self.label(),
+            chinese_name(self.lang, &self.name.chinese, &self.name.chinese_simplified),
+            self.name.pinyin
+        )
+    }
+}
+
+impl Render for HexagramView<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().format(
+            "hexagram-title",
+            &[
+                ("number", self.number.into()),
+                ("symbol", self.symbol.into()),
+            ],
+        )
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        renderer.heading(HeadingLevel::Title, &self.title())?;
+        renderer.subtitle(&self.name.english)?;
+        let chinese = chinese_name(self.lang, &self.name.chinese, &self.name.chinese_simplified);
+        renderer.subtitle(&format!("{chinese} ({})", self.name.pinyin))?;
+        if self.big {
+            renderer.figure(&big_hexagrams(&self.hexagram, &[]))?;
+        }
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Judgement),
+            &messages.get("judgement"),
+        )?;
+        for text in self.texts.pick(&self.classical.judgement, self.judgement) {
+            renderer.verse(text)?;
+        }
+        let commentary = self.commentary.as_ref();
+        if let Some(tuan) = commentary.and_then(|commentary| commentary.tuan) {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Judgement),
+                &messages.get("tuan"),
+            )?;
+            renderer.verse(tuan)?;
+        }
+        renderer.heading(
+            HeadingLevel::Section(SectionRole::Image),
+            &messages.get("images"),
+        )?;
+        for text in self.texts.pick(&self.classical.images, self.images) {
+            renderer.verse(text)?;
+        }
+
+        if self.render_lines {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Lines),
+                &messages.get("lines"),
+            )?;
+            for line in self.lines {
+                let label = line_label(
+                    messages,
+                    self.line_labels,
+                    &line_at(&self.hexagram, line.position),
+                    line.position,
+                );
+                renderer.heading(HeadingLevel::Subsection, &label)?;
+                for text in self
+                    .texts
+                    .pick(classical_line(self.classical, line.position), &line.meaning)
+                {
+                    renderer.verse(text)?;
+                }
+                if let Some(image) =
+                    commentary.and_then(|commentary| commentary.small_image(line.position))
+                {
+                    renderer.verse(&messages.format("small-image", &[("text", image.into())]))?;
+                }
+            }
+        }
+
+        if let Some(wenyan) = commentary.and_then(|commentary| commentary.wenyan.flatten()) {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("wenyan"),
+            )?;
+            renderer.verse(wenyan)?;
+        }
+        if let Some(sequence) = commentary.and_then(|commentary| commentary.sequence) {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("sequence"),
+            )?;
+            renderer.verse(sequence)?;
+        }
+        if let Some(miscellaneous) = commentary.and_then(|commentary| commentary.miscellaneous) {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("miscellaneous"),
+            )?;
+            renderer.verse(miscellaneous)?;
+        }
+
+        Ok(())
+    }
+
+    fn svg(&self, style: &SvgStyle) -> Option<String> {
+        Some(svg::hexagram(&self.hexagram, style))
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ReadingView<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<NaiveDate>,
+    question: Option<&'a str>,
+    method: DivinationMethod,
+    lines: [u8; 6],
+    changing_lines: Vec<usize>,
+    primary: HexagramView<'a>,
+    relating: Option<HexagramView<'a>>,
+    changing_line_meanings: Vec<&'a ChangingLineMeaning>,
+    school: School,
+    lines_to_consider: Vec<LineToConsider<'a>>,
+    #[serde(skip)]
+    hexagram: Hexagram,
+    #[serde(skip)]
+    line_labels: LineLabels,
+    #[serde(skip)]
+    lang: Lang,
+    #[serde(skip)]
+    texts: Texts,
+    #[serde(skip)]
+    big: bool,
+}
+
+/// A line text that a reading's school of interpretation says to consider.
+#[derive(Serialize)]
+struct LineToConsider<'a> {
+    hexagram: LineSource,
+    position: usize,
+    meaning: &'a str,
+    classical: &'a str,
+    /// The Small Image of the line, if the Small Images were asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    small_image: Option<&'a str>,
+}
+
+/// Get the line of a `hexagram` at a position, from 1 at the bottom to 6 at the top.
+fn line_at(hexagram: &Hexagram, position: usize) -> Line {
+    Line::try_from(hexagram.line_values()[position - 1]).expect("hexagrams have valid lines")
+}
+
+/// Get the label of the `line` at `position`, named in the given way and language. This is
+/// [`LineLabels::label`], translated.
+fn line_label(
+    messages: &Messages,
+    line_labels: LineLabels,
+    line: &Line,
+    position: usize,
+) -> String {
+    match line_labels {
+        LineLabels::Positions => messages.format("line-position", &[("position", position.into())]),
+        LineLabels::Traditional => {
+            // Yang lines have the odd values, 7 and 9.
+            let value = if line.value() % 2 == 1 {
+                messages.get("yang-line-value")
+            } else {
+                messages.get("yin-line-value")
+            };
+            messages.format(
+                "line-traditional",
+                &[("value", value.into()), ("position", position.into())],
+            )
+        }
+    }
+}
+
+impl<'a> ReadingView<'a> {
+    pub(crate) fn new(
+        question: Option<&'a str>,
+        method: DivinationMethod,
+        hexagram: &Hexagram,
+        hexagrams: &'a impl HexagramRepository<HexagramInfo = HexagramJsonInfo>,
+    ) -> Self {
+        let primary = hexagrams.get_info_for_hexagram(hexagram);
+        let changing_lines = hexagram.get_changing_line_positions();
+
+        ReadingView {
+            date: None,
+            question,
+            method,
+            lines: hexagram.line_values(),
+            changing_line_meanings: primary.line_meanings(&changing_lines),
+            changing_lines,
+            primary: primary.into(),
+            relating: hexagram
+                .relating_hexagram()
+                .map(|h| hexagrams.get_info_for_hexagram(&h).into()),
+            school: School::default(),
+            lines_to_consider: Vec::new(),
+            hexagram: *hexagram,
+            line_labels: LineLabels::default(),
+            lang: Lang::default(),
+            texts: Texts::default(),
+            big: false,
+        }
+        .with_school(School::default())
+    }
+
+    /// Pick the line texts to consider by the rules of the given school of interpretation.
+    pub(crate) fn with_school(mut self, school: School) -> Self {
+        self.school = school;
+        self.lines_to_consider = school
+            .lines_to_read(&self.hexagram)
+            .into_iter()
+            .filter_map(|line| {
+                let hexagram = match line.source {
+                    LineSource::Primary => &self.primary,
+                    LineSource::Relating => self.relating.as_ref()?,
+                };
+                let meaning = hexagram
+                    .lines
+                    .iter()
+                    .find(|meaning| meaning.position == line.position)?;
+                Some(LineToConsider {
+                    hexagram: line.source,
+                    position: line.position,
+                    meaning: &meaning.meaning,
+                    classical: classical_line(hexagram.classical, line.position),
+                    small_image: hexagram
+                        .commentary
+                        .as_ref()
+                        .and_then(|commentary| commentary.small_image(line.position)),
+                })
+            })
+            .collect();
+        self
+    }
+
+    /// Name the lines in the given way.
+    pub(crate) fn with_line_labels(mut self, line_labels: LineLabels) -> Self {
+        self.line_labels = line_labels;
+        self.primary = self.primary.with_line_labels(line_labels);
+        self.relating = self
+            .relating
+            .map(|relating| relating.with_line_labels(line_labels));
+        self
+    }
+
+    /// Show the headings and line labels in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self.primary = self.primary.with_lang(lang);
+        self.relating = self.relating.map(|relating| relating.with_lang(lang));
+        self
+    }
+
+    /// Show the translation, the original Chinese text or both.
+    pub(crate) fn with_texts(mut self, texts: Texts) -> Self {
+        self.texts = texts;
+        self.primary = self.primary.with_texts(texts);
+        self.relating = self.relating.map(|relating| relating.with_texts(texts));
+        self
+    }
+
+    /// Show the given commentaries of the Ten Wings along with the text of both hexagrams and
+    /// the lines to consider.
+    pub(crate) fn with_commentary(mut self, layers: &[Layer]) -> Self {
+        self.primary = self.primary.with_commentary(layers);
+        self.relating = self
+            .relating
+            .map(|relating| relating.with_commentary(layers));
+        // The lines to consider quote the Small Images of the hexagrams.
+        let school = self.school;
+        self.with_school(school)
+    }
+
+    /// Draw the primary and relating hexagrams side by side as big lines, before their text.
+    pub(crate) fn with_big_lines(mut self) -> Self {
+        self.big = true;
+        self
+    }
+
+    /// Mark this reading as the hexagram of the day for the given `date`.
+    pub(crate) fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+}
+
+impl Render for ReadingView<'_> {
+    fn title(&self) -> String {
+        match (self.date, self.question) {
+            (Some(date), _) => self
+                .lang
+                .messages()
+                .format("hexagram-of-the-day", &[("date", date.to_string().into())]),
+            (None, Some(question)) => question.to_owned(),
+            (None, None) => self.primary.title(),
+        }
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        if self.date.is_some() {
+            renderer.paragraph(&format!("{}:", self.title()))?;
+        }
+        if let Some(question) = self.question {
+            renderer.paragraph(&messages.format("question", &[("question", question.into())]))?;
+        }
+        if self.big {
+            let mut labels = vec![self.primary.label()];
+            labels.extend(self.relating.as_ref().map(HexagramView::label));
+            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+            renderer.figure(&big_hexagrams(&self.hexagram, &labels))?;
+        }
+
+        self.primary.render(renderer)?;
+
+        let has_advice = self.school.judgement_advice(&self.hexagram).is_some();
+        if !self.lines_to_consider.is_empty() || has_advice {
+            renderer.begin_aside(&messages.get("lines-are-changing"))?;
+            if has_advice {
+                renderer.paragraph(&messages.format(
+                    "zhu-xi-advice",
+                    &[("changing", self.changing_lines.len().into())],
+                ))?;
+            }
+            for line in &self.lines_to_consider {
+                let (hexagram, message) = match line.hexagram {
+                    LineSource::Primary => (self.hexagram, "line-changes"),
+                    LineSource::Relating => (
+                        self.hexagram
+                            .relating_hexagram()
+                            .expect("only readings with changing lines have relating lines"),
+                        "relating-line",
+                    ),
+                };
+                let label = line_label(
+                    messages,
+                    self.line_labels,
+                    &line_at(&hexagram, line.position),
+                    line.position,
+                );
+                let heading = messages.format(message, &[("label", label.into())]);
+                renderer.heading(HeadingLevel::Subsection, &heading)?;
+                for text in self.texts.pick(line.classical, line.meaning) {
+                    renderer.paragraph(text)?;
+                }
+                if let Some(image) = line.small_image {
+                    renderer
+                        .paragraph(&messages.format("small-image", &[("text", image.into())]))?;
+                }
+            }
+            renderer.end_aside()?;
+        }
+
+        if let Some(relating) = &self.relating {
+            renderer.paragraph(&messages.get("changes-into"))?;
+            relating.render(renderer)?;
+        }
+
+        Ok(())
+    }
+
+    fn svg(&self, style: &SvgStyle) -> Option<String> {
+        let title = self
+            .date
+            .map(|_| self.title())
+            .or(self.question.map(String::from));
+        let relating_label = self.relating.as_ref().map(HexagramView::card_label);
+        let card = ReadingCard {
+            hexagram: &self.hexagram,
+            title: title.as_deref(),
+            primary_label: &self.primary.card_label(),
+            relating_label: relating_label.as_deref(),
+            body: Some(self.primary.judgement),
+        };
+
+        Some(svg::reading_card(&card, style))
+    }
+}
+
+/// A reading that's been stored in the journal.
+#[derive(Serialize)]
+pub(crate) struct JournalEntryView<'a> {
+    id: u64,
+    timestamp: DateTime<FixedOffset>,
+    notes: &'a [String],
+    #[serde(flatten)]
+    reading: ReadingView<'a>,
+}
+
+impl<'a> JournalEntryView<'a> {
+    pub(crate) fn new(
+        id: u64,
+        timestamp: DateTime<FixedOffset>,
+        notes: &'a [String],
+        reading: ReadingView<'a>,
+    ) -> Self {
+        JournalEntryView {
+            id,
+            timestamp,
+            notes,
+            reading,
+        }
+    }
+}
+
+impl Render for JournalEntryView<'_> {
+    fn title(&self) -> String {
+        self.reading
+            .lang
+            .messages()
+            .format("reading-title", &[("id", self.id.into())])
+    }
+
+    fn lang(&self) -> Lang {
+        self.reading.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.reading.lang.messages();
+        renderer.paragraph(&messages.format(
+            "reading-heading",
+            &[
+                ("id", self.id.into()),
+                (
+                    "timestamp",
+                    self.timestamp.format("%Y-%m-%d %H:%M").to_string().into(),
+                ),
+                ("method", method_name(self.reading.method).into()),
+            ],
+        ))?;
+        self.reading.render(renderer)?;
+
+        if !self.notes.is_empty() {
+            renderer.heading(
+                HeadingLevel::Section(SectionRole::Other),
+                &messages.get("notes"),
+            )?;
+            renderer.list(self.notes)?;
+        }
+
+        Ok(())
+    }
+
+    fn svg(&self, style: &SvgStyle) -> Option<String> {
+        self.reading.svg(style)
+    }
+}
+
+/// All readings stored in the journal, as listed by `journal list`. Each reading is serialized
+/// the same way as it's stored in the journal file.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub(crate) struct JournalListView<'a> {
+    readings: &'a [Reading],
+    #[serde(skip)]
+    lang: Lang,
+}
+
+impl<'a> JournalListView<'a> {
+    pub(crate) fn new(readings: &'a [Reading]) -> Self {
+        JournalListView {
+            readings,
+            lang: Lang::default(),
+        }
+    }
+
+    /// Show the headings in the given language.
+    pub(crate) fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+}
+
+impl Render for JournalListView<'_> {
+    fn title(&self) -> String {
+        self.lang.messages().get("journal-title")
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer) -> Result<(), render::Error> {
+        let messages = self.lang.messages();
+        if self.readings.is_empty() {
+            return renderer.paragraph(&messages.get("journal-empty"));
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .readings
+            .iter()
+            .map(|reading| {
+                let hexagram = reading.hexagram().ok();
+                let symbol = |with_changes| {
+                    hexagram
+                        .as_ref()
+                        .map(|hexagram| hexagram.symbol(with_changes).to_owned())
+                        .unwrap_or_default()
+                };
+                vec![
+                    reading.id.to_string(),
+                    reading.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                    format!("{} {}", symbol(false), reading.primary),
+                    reading
+                        .relating
+                        .map(|number| format!("{} {number}", symbol(true)))
+                        .unwrap_or_default(),
+                    reading.question.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+
+        renderer.table(
+            &[
+                Column::right(&messages.get("column-id")),
+                Column::left(&messages.get("column-date")),
+                Column::right(&messages.get("column-primary")),
+                Column::right(&messages.get("column-relating")),
+                Column::left(&messages.get("column-question")),
+            ],
+            &rows,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexagram_json::HexagramJson;
+    use iching::HexagramRepository;
+    use termcolor::Buffer;
+
+    fn render_hexagram_to(mut output: Buffer) -> String {
+        let mut hexagrams = HexagramJson::default();
+        hexagrams.initialize().unwrap();
+        let hexagram = hexagrams.get_by_number(1).unwrap();
+        let mut renderer = TextRenderer::new(&mut output, &Theme::default());
+        HexagramView::from(hexagram).render(&mut renderer).unwrap();
+
+        String::from_utf8(output.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_writing_without_color_works() {
+        let output = render_hexagram_to(Buffer::no_color());
+
+        assert_eq!(
+            output,
+            "Hexagram No. 1  ䷀\n\tThe Creative\n\t乾 (Qián)\n\nJudgement:\n\tThe Creative works sublime success,\n\tFurthering through perseverance.\nImages:\n\tThe movement of heaven is full of power.\n\tThus the superior man makes himself strong and untiring.\n"
+        );
+    }
+
+    #[test]
+    fn test_writing_with_color_works() {
+        let output = render_hexagram_to(Buffer::ansi());
+
+        assert_eq!(
+            output,
+            "\u{1b}[0m\u{1b}[38;5;196mHexagram No. 1  ䷀\n\u{1b}[0m\tThe Creative\n\t乾 (Qián)\n\n\u{1b}[0m\u{1b}[38;5;160mJudgement:\n\u{1b}[0m\tThe Creative works sublime success,\n\tFurthering through perseverance.\n\u{1b}[0m\u{1b}[38;5;124mImages:\n\u{1b}[0m\tThe movement of heaven is full of power.\n\tThus the superior man makes himself strong and untiring.\n"
+        );
+    }
+
+    #[test]
+    fn test_original_texts_are_shown_with_simplified_names() {
+        let mut hexagrams = HexagramJson::default();
+        hexagrams.initialize().unwrap();
+        let hexagram = hexagrams.get_by_number(30).unwrap();
+        let mut output = Buffer::no_color();
+        let mut renderer = TextRenderer::new(&mut output, &Theme::default());
+        HexagramView::from(hexagram)
+            .with_lang(Lang::ZhHans)
+            .with_texts(Texts::Both)
+            .render(&mut renderer)
+            .unwrap();
+        let output = String::from_utf8(output.into_inner()).unwrap();
+
+        assert!(output.contains("\t离 (lí)\n"), "{output}");
+        assert!(
+            output.contains("\t利貞，亨。畜牝牛，吉。\n\tThe Clinging."),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_only_the_commentaries_asked_for_are_shown() {
+        let mut hexagrams = HexagramJson::default();
+        hexagrams.initialize().unwrap();
+        let view = HexagramView::from(hexagrams.get_by_number(1).unwrap())
+            .with_lines()
+            .with_commentary(&[Layer::Tuan, Layer::SmallImages]);
+        let mut output = Buffer::no_color();
+        let mut renderer = TextRenderer::new(&mut output, &Theme::default());
+        view.render(&mut renderer).unwrap();
+        let output = String::from_utf8(output.into_inner()).unwrap();
+
+        assert!(
+            output.contains("Commentary on the Judgement:\n\t大哉乾元，萬物資始，乃統天。"),
+            "{output}"
+        );
+        assert!(
+            output.contains("\tThe image: 潛龍勿用，陽在下也。\n"),
+            "{output}"
+        );
+        assert!(!output.contains("Words of the Text"), "{output}");
+
+        let json = serde_json::to_value(&view).unwrap();
+        let commentary = json["commentary"].as_object().unwrap();
+        let fields: Vec<&str> = commentary.keys().map(String::as_str).collect();
+        assert_eq!(fields, ["small_images", "tuan"]);
+        let json = serde_json::to_value(view.with_commentary(&[Layer::None])).unwrap();
+        assert!(json.get("commentary").is_none());
+    }
+
+    #[test]
+    fn test_html_is_a_standalone_document() {
+        let mut output = Vec::new();
+        let readings = [];
+        let mut renderer = HtmlRenderer::new(&mut output);
+        render_document(&mut renderer, &JournalListView::new(&readings)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("<!DOCTYPE html>"));
+        assert!(output.contains(
+            "<p>The journal is empty. Readings are stored here after using `divine`.</p>"
+        ));
+        assert!(output.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_html_is_tagged_with_the_language() {
+        let mut hexagrams = HexagramJson::default();
+        hexagrams.initialize().unwrap();
+        let hexagram = hexagrams.get_by_number(1).unwrap();
+        let mut output = Vec::new();
+        let view = HexagramView::from(hexagram).with_lang(Lang::ZhHant);
+        render_document(&mut HtmlRenderer::new(&mut output), &view).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("<html lang=\"zh-Hant\">"), "{output}");
+    }
+}
@@ -0,0 +1,44 @@
+//! Tests of `iching daily`, run as the app itself so that its command line is checked too.
+
+use std::{
+    process::{Command, Output},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+fn daily(args: &[&str]) -> Output {
+    // An empty config file keeps the user's own config from changing the defaults.
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let config = std::env::temp_dir().join(format!(
+        "iching-daily-{}-{}.toml",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&config, "").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_iching"))
+        .args(["--color", "never", "--config"])
+        .arg(&config)
+        .args(["daily", "--date", "2023-01-01"])
+        .args(args)
+        .output()
+        .expect("the app runs");
+    std::fs::remove_file(&config).ok();
+    output
+}
+
+#[test]
+fn test_daily_is_the_same_every_time() {
+    let first = daily(&[]);
+    assert!(first.status.success());
+    assert_eq!(first.stdout, daily(&[]).stdout);
+}
+
+#[test]
+fn test_daily_rejects_a_seed() {
+    let output = daily(&["--seed", "7"]);
+    assert!(!output.status.success());
+    let error = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        error.contains("'--seed <SEED>' cannot be used with 'daily'"),
+        "{error}"
+    );
+}
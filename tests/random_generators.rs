@@ -0,0 +1,238 @@
+//! Statistical tests of the random line, trigram and hexagram generators. Large samples are drawn
+//! with every method of divination and compared against the odds given by the `probability`
+//! module. Every sample is drawn from a seeded random number generator, so these tests are
+//! deterministic.
+
+use clap::ValueEnum;
+use iching::{
+    divination_method::DivinationMethod,
+    line::Line,
+    probability::{self, Probability},
+    statistics::ChiSquare,
+    trigram::Trigram,
+    Hexagram,
+};
+use num_traits::ToPrimitive;
+
+const SEED: u64 = 0x1c41_6e67;
+const SAMPLE_SIZE: usize = 200_000;
+/// Samples are rejected when a chi-squared test gives a p-value below this. Since the samples are
+/// seeded, a failure means that the generators changed rather than that a sample was unlucky.
+const SIGNIFICANCE: f64 = 0.001;
+
+fn methods() -> impl Iterator<Item = DivinationMethod> {
+    DivinationMethod::value_variants().iter().copied()
+}
+
+fn to_f64(probability: Probability) -> f64 {
+    probability
+        .to_f64()
+        .expect("probabilities are representable as floats")
+}
+
+/// The index (0-3) of a line within `DivinationMethod::line_probabilities`.
+fn line_index(line: Line) -> usize {
+    match line {
+        Line::Broken { changing: true } => 0,
+        Line::Broken { changing: false } => 1,
+        Line::Unbroken { changing: true } => 2,
+        Line::Unbroken { changing: false } => 3,
+    }
+}
+
+/// The index of a cast among the `4.pow(number of lines)` possible casts.
+fn cast_index<'a>(lines: impl Iterator<Item = &'a Line>) -> usize {
+    lines.fold(0, |index, &line| index * 4 + line_index(line))
+}
+
+/// The index (0-63) of a hexagram's primary hexagram, treating each line as a bit.
+fn primary_index(hexagram: &Hexagram) -> usize {
+    hexagram.lines().fold(0, |index, line| {
+        index * 2 + usize::from(matches!(line, Line::Unbroken { .. }))
+    })
+}
+
+/// The inverse of [`primary_index`].
+fn hexagram_from_primary_index(index: usize) -> Hexagram {
+    let lines = [0, 1, 2, 3, 4, 5].map(|bit| if index >> bit & 1 == 1 { 7 } else { 8 });
+    Hexagram::try_from(lines).unwrap()
+}
+
+/// The probability of casting a line with the same polarity as `line`, whether or not it's
+/// changing.
+fn polarity_probability(method: DivinationMethod, line: Line) -> f64 {
+    method
+        .line_probabilities()
+        .into_iter()
+        .filter(|(candidate, _)| candidate.primary() == line.primary())
+        .map(|(_, probability)| to_f64(probability))
+        .sum()
+}
+
+fn assert_fits(observed: &[u64], probabilities: &[f64], description: &str) {
+    let test = ChiSquare::goodness_of_fit(observed, probabilities);
+
+    assert!(
+        test.min_expected >= 5.0,
+        "the sample of {description} is too small for a chi-squared test"
+    );
+    assert!(
+        test.p_value >= SIGNIFICANCE,
+        "{description} don't match their expected distribution: {test:?}"
+    );
+}
+
+#[test]
+fn test_line_frequencies_match_theory() {
+    for method in methods() {
+        let rng = fastrand::Rng::with_seed(SEED);
+        let mut counts = [0; 4];
+        for _ in 0..SAMPLE_SIZE {
+            counts[line_index(Line::new_random_with_rng(method, &rng))] += 1;
+        }
+
+        let probabilities = method.line_probabilities().map(|(_, p)| to_f64(p));
+        assert_fits(&counts, &probabilities, &format!("{method:?} lines"));
+
+        // Make sure that the test is able to tell the methods apart.
+        for other_method in methods().filter(|&other_method| other_method != method) {
+            let probabilities = other_method.line_probabilities().map(|(_, p)| to_f64(p));
+            let test = ChiSquare::goodness_of_fit(&counts, &probabilities);
+            assert!(
+                test.p_value < SIGNIFICANCE,
+                "{method:?} lines are indistinguishable from {other_method:?} lines: {test:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_trigram_frequencies_match_theory() {
+    for method in methods() {
+        let rng = fastrand::Rng::with_seed(SEED);
+        let mut trigram_counts = [0; 8];
+        let mut cast_counts = [0; 64];
+        for _ in 0..SAMPLE_SIZE {
+            let trigram = Trigram::new_random_with_rng(method, &rng);
+            trigram_counts[trigram.number() - 1] += 1;
+            cast_counts[cast_index(trigram.lines())] += 1;
+        }
+
+        let trigram_probabilities: Vec<f64> = (1..=8usize)
+            .map(|number| {
+                let trigram = Trigram::try_from(number).unwrap();
+                trigram
+                    .lines()
+                    .map(|&line| polarity_probability(method, line))
+                    .product()
+            })
+            .collect();
+        assert_fits(
+            &trigram_counts,
+            &trigram_probabilities,
+            &format!("{method:?} trigrams"),
+        );
+
+        let cast_probabilities: Vec<f64> = (0..64usize)
+            .map(|index| {
+                (0..3)
+                    .map(|position| {
+                        let (_, probability) =
+                            method.line_probabilities()[index >> (position * 2) & 3];
+                        to_f64(probability)
+                    })
+                    .product()
+            })
+            .collect();
+        assert_fits(
+            &cast_counts,
+            &cast_probabilities,
+            &format!("{method:?} trigram casts"),
+        );
+    }
+}
+
+#[test]
+fn test_hexagram_frequencies_match_theory() {
+    for method in methods() {
+        let rng = fastrand::Rng::with_seed(SEED);
+        let mut primary_counts = vec![0; 64];
+        let mut moving_line_counts = [0; 7];
+        for _ in 0..SAMPLE_SIZE {
+            let hexagram = Hexagram::new_random_with_rng(method, &rng);
+            primary_counts[primary_index(&hexagram)] += 1;
+            moving_line_counts[hexagram.get_changing_line_positions().len()] += 1;
+        }
+
+        let primary_probabilities: Vec<f64> = (0..64)
+            .map(|index| {
+                let hexagram = hexagram_from_primary_index(index);
+                to_f64(probability::primary_probability(method, &hexagram))
+            })
+            .collect();
+        assert_fits(
+            &primary_counts,
+            &primary_probabilities,
+            &format!("{method:?} primary hexagrams"),
+        );
+
+        // Casting 6 moving lines is too rare to be tested with this sample size, so it's lumped
+        // together with casting 5.
+        let mut moving_line_probabilities: Vec<f64> = (0..=6)
+            .map(|count| to_f64(probability::moving_lines_probability(method, count)))
+            .collect();
+        let six = moving_line_probabilities.pop().unwrap();
+        *moving_line_probabilities.last_mut().unwrap() += six;
+        let mut moving_line_counts = moving_line_counts.to_vec();
+        let six = moving_line_counts.pop().unwrap();
+        *moving_line_counts.last_mut().unwrap() += six;
+        assert_fits(
+            &moving_line_counts,
+            &moving_line_probabilities,
+            &format!("{method:?} moving line counts"),
+        );
+    }
+}
+
+#[test]
+fn test_all_casts_are_reachable() {
+    // The rarest yarrow stalk cast (six old yin lines) has odds of 1 in 16,777,216, so give each
+    // method plenty of draws to find every cast.
+    const MAX_DRAWS: u64 = 200_000_000;
+
+    for method in methods() {
+        let rng = fastrand::Rng::with_seed(SEED);
+        let mut seen = vec![false; 4096];
+        let mut remaining = seen.len();
+        let mut draws = 0;
+
+        while remaining > 0 && draws < MAX_DRAWS {
+            let hexagram = Hexagram::new_random_with_rng(method, &rng);
+            let index = cast_index(hexagram.lines());
+            if !seen[index] {
+                seen[index] = true;
+                remaining -= 1;
+            }
+            draws += 1;
+        }
+
+        assert_eq!(
+            remaining, 0,
+            "{remaining} {method:?} casts were never drawn in {MAX_DRAWS} draws"
+        );
+    }
+}
+
+#[test]
+fn test_seeded_generation_is_deterministic() {
+    for method in methods() {
+        let first: Vec<Hexagram> = (0..100)
+            .map(|seed| Hexagram::new_seeded(method, seed))
+            .collect();
+        let second: Vec<Hexagram> = (0..100)
+            .map(|seed| Hexagram::new_seeded(method, seed))
+            .collect();
+
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,156 @@
+//! Tests of the JSON API served by `iching serve`. Each test starts the app on a free port of the
+//! local machine and talks plain HTTP to it.
+
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A running server, which is stopped when dropped.
+struct Server {
+    process: Child,
+    address: String,
+    config: PathBuf,
+}
+
+impl Server {
+    fn start() -> Self {
+        // An empty config file keeps the user's own config from changing the defaults.
+        static SERVERS: AtomicUsize = AtomicUsize::new(0);
+        let config = std::env::temp_dir().join(format!(
+            "iching-serve-{}-{}.toml",
+            std::process::id(),
+            SERVERS.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&config, "").unwrap();
+        let mut process = Command::new(env!("CARGO_BIN_EXE_iching"))
+            .args(["serve", "--port", "0", "--config"])
+            .arg(&config)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("the app starts");
+
+        // The first line says where the server is listening, e.g. "... at http://127.0.0.1:1234."
+        let mut line = String::new();
+        BufReader::new(process.stdout.as_mut().expect("stdout is piped"))
+            .read_line(&mut line)
+            .expect("the server prints its address");
+        let address = line
+            .split("http://")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(|address| address.trim_end_matches('.').to_owned())
+            .unwrap_or_else(|| panic!("no address in {line:?}"));
+
+        Server {
+            process,
+            address,
+            config,
+        }
+    }
+
+    /// Send a request, returning the status code and the JSON body of the response.
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.address).expect("the server is listening");
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: \
+             application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            self.address,
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .expect("the response has a head and a body");
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .expect("the response has a status");
+        (
+            status,
+            serde_json::from_str(body).expect("the body is JSON"),
+        )
+    }
+
+    fn get(&self, path: &str) -> (u16, Value) {
+        self.request("GET", path, "")
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_file(&self.config);
+    }
+}
+
+#[test]
+fn test_divine_casts_a_reading() {
+    let server = Server::start();
+    let body = r#"{"method": "coin-toss", "question": "Will it rain?", "seed": 7}"#;
+
+    let (status, reading) = server.request("POST", "/divine", body);
+    assert_eq!(status, 200, "{reading}");
+    assert_eq!(reading["question"], "Will it rain?");
+    assert_eq!(reading["method"], "coin-toss");
+    assert_eq!(reading["lines"].as_array().unwrap().len(), 6);
+    // The same seed always gives the same hexagram.
+    assert_eq!(server.request("POST", "/divine", body).1, reading);
+
+    let (status, reading) = server.request("POST", "/divine", "");
+    assert_eq!(status, 200);
+    assert_eq!(reading["method"], "ancient-yarrow-stalk");
+
+    let (status, _) = server.request("POST", "/divine", r#"{"texts": "both"}"#);
+    assert_eq!(status, 200);
+
+    let (status, error) = server.request("POST", "/divine", r#"{"method": "dice"}"#);
+    assert_eq!(status, 400);
+    assert!(error["error"].as_str().unwrap().contains("dice"));
+}
+
+#[test]
+fn test_hexagrams_and_trigrams_are_looked_up_by_number() {
+    let server = Server::start();
+
+    let (status, hexagram) = server.get("/hexagrams/3");
+    assert_eq!(status, 200);
+    assert_eq!(hexagram["number"], 3);
+    assert_eq!(hexagram["lines"].as_array().unwrap().len(), 6);
+    // The last hexagram of the binary ordering is ䷀ The Creative.
+    assert_eq!(server.get("/hexagrams/64?ordering=binary").1["number"], 1);
+    assert_eq!(server.get("/hexagrams/65").0, 404);
+    assert_eq!(server.get("/hexagrams/1?ordering=random").0, 400);
+    assert_eq!(server.get("/hexagrams/1?texts=original").0, 200);
+    assert_eq!(server.get("/hexagrams/1?texts=latin").0, 400);
+
+    let (status, trigram) = server.get("/trigrams/3");
+    assert_eq!(status, 200);
+    assert_eq!(trigram["symbol"], "☲");
+    assert_eq!(server.get("/trigrams/9").0, 404);
+}
+
+#[test]
+fn test_search_finds_passages() {
+    let server = Server::start();
+
+    let (status, results) = server.get("/search?q=great%20man&limit=2");
+    assert_eq!(status, 200);
+    assert_eq!(results["query"], "great man");
+    assert_eq!(results["results"].as_array().unwrap().len(), 2);
+
+    assert_eq!(server.get("/search").0, 400);
+    assert_eq!(server.get("/nowhere").0, 404);
+    assert_eq!(server.request("DELETE", "/search?q=man", "").0, 405);
+}